@@ -0,0 +1,47 @@
+/* Interface C de lru_cache (feature `ffi`), voir src/ffi.rs */
+#ifndef LRU_CACHE_H
+#define LRU_CACHE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Cache partageable entre threads; clés et valeurs sont des octets.
+   Une panique côté Rust est rendue comme une erreur (-1 ou NULL) */
+typedef struct FfiCache lru_cache_t;
+
+lru_cache_t *lru_cache_new(size_t capacity);
+
+/* Cache persistant sur le fichier `path` (options par défaut); NULL en cas
+   d'erreur. Même format de fichier que PersistentLruCache<String, String> */
+lru_cache_t *lru_cache_open(const char *path, size_t capacity);
+
+/* 0: sauvegardé (ou cache en mémoire), -1: erreur */
+int32_t lru_cache_save(const lru_cache_t *cache);
+
+/* Libère le cache, sauvegardé selon ses options s'il est persistant */
+void lru_cache_free(lru_cache_t *cache);
+
+/* 1: valeur remplacée, 0: nouvelle entrée, -1: pointeur nul */
+int32_t lru_cache_put(const lru_cache_t *cache, const uint8_t *key, size_t key_len,
+                      const uint8_t *value, size_t value_len);
+
+/* 1: trouvée (tampon à libérer avec lru_cache_bytes_free), 0: absente,
+   -1: pointeur nul */
+int32_t lru_cache_get(const lru_cache_t *cache, const uint8_t *key, size_t key_len,
+                      uint8_t **value, size_t *value_len);
+
+/* 1: retirée, 0: absente, -1: pointeur nul */
+int32_t lru_cache_remove(const lru_cache_t *cache, const uint8_t *key, size_t key_len);
+
+size_t lru_cache_len(const lru_cache_t *cache);
+void lru_cache_bytes_free(uint8_t *value, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,162 @@
+"""Caches LRU de la crate lru_cache, via son interface C (feature `ffi`)
+
+La bibliothèque se compile avec `cargo build --release --features ffi`;
+son chemin vient de la variable d'environnement LRU_CACHE_LIB, sinon du
+nom système habituel (liblru_cache.so, liblru_cache.dylib, lru_cache.dll).
+
+    from lru_cache import PersistentLruCache
+
+    with PersistentLruCache("cache.txt", 1000) as cache:
+        cache["a"] = "1"
+        assert cache.get("a") == b"1"
+
+Clés et valeurs sont des octets; une `str` est encodée en UTF-8.
+"""
+
+import ctypes
+import ctypes.util
+import os
+import sys
+
+__all__ = ["LruCache", "PersistentLruCache"]
+
+
+def _load_library():
+    path = os.environ.get("LRU_CACHE_LIB")
+    if path is None:
+        path = ctypes.util.find_library("lru_cache")
+    if path is None:
+        if sys.platform == "win32":
+            path = "lru_cache.dll"
+        elif sys.platform == "darwin":
+            path = "liblru_cache.dylib"
+        else:
+            path = "liblru_cache.so"
+    lib = ctypes.CDLL(path)
+
+    cache_p = ctypes.c_void_p
+    bytes_p = ctypes.POINTER(ctypes.c_uint8)
+    size = ctypes.c_size_t
+
+    lib.lru_cache_new.argtypes = [size]
+    lib.lru_cache_new.restype = cache_p
+    lib.lru_cache_open.argtypes = [ctypes.c_char_p, size]
+    lib.lru_cache_open.restype = cache_p
+    lib.lru_cache_save.argtypes = [cache_p]
+    lib.lru_cache_save.restype = ctypes.c_int32
+    lib.lru_cache_free.argtypes = [cache_p]
+    lib.lru_cache_free.restype = None
+    lib.lru_cache_put.argtypes = [cache_p, ctypes.c_char_p, size, ctypes.c_char_p, size]
+    lib.lru_cache_put.restype = ctypes.c_int32
+    lib.lru_cache_get.argtypes = [
+        cache_p,
+        ctypes.c_char_p,
+        size,
+        ctypes.POINTER(bytes_p),
+        ctypes.POINTER(size),
+    ]
+    lib.lru_cache_get.restype = ctypes.c_int32
+    lib.lru_cache_remove.argtypes = [cache_p, ctypes.c_char_p, size]
+    lib.lru_cache_remove.restype = ctypes.c_int32
+    lib.lru_cache_len.argtypes = [cache_p]
+    lib.lru_cache_len.restype = size
+    lib.lru_cache_bytes_free.argtypes = [bytes_p, size]
+    lib.lru_cache_bytes_free.restype = None
+    return lib
+
+
+_lib = _load_library()
+
+
+def _bytes(data):
+    return data.encode("utf-8") if isinstance(data, str) else bytes(data)
+
+
+class LruCache:
+    """Cache LRU en mémoire de `capacity` entrées"""
+
+    def __init__(self, capacity):
+        self._ptr = _lib.lru_cache_new(capacity)
+
+    def put(self, key, value):
+        """Insère une paire; True si une valeur a été remplacée"""
+        key, value = _bytes(key), _bytes(value)
+        return _lib.lru_cache_put(self._handle(), key, len(key), value, len(value)) == 1
+
+    def get(self, key, default=None):
+        """Valeur de `key` (bytes), marquée comme récente; `default` si absente"""
+        key = _bytes(key)
+        value = ctypes.POINTER(ctypes.c_uint8)()
+        length = ctypes.c_size_t()
+        found = _lib.lru_cache_get(
+            self._handle(), key, len(key), ctypes.byref(value), ctypes.byref(length)
+        )
+        if found != 1:
+            return default
+        try:
+            return ctypes.string_at(value, length.value)
+        finally:
+            _lib.lru_cache_bytes_free(value, length)
+
+    def remove(self, key):
+        """Retire une entrée; True si elle existait"""
+        key = _bytes(key)
+        return _lib.lru_cache_remove(self._handle(), key, len(key)) == 1
+
+    def close(self):
+        """Libère le cache; sans effet s'il l'est déjà"""
+        if self._ptr is not None:
+            _lib.lru_cache_free(self._ptr)
+            self._ptr = None
+
+    def __len__(self):
+        return _lib.lru_cache_len(self._handle())
+
+    def __contains__(self, key):
+        return self.get(key) is not None
+
+    def __getitem__(self, key):
+        value = self.get(key)
+        if value is None:
+            raise KeyError(key)
+        return value
+
+    def __setitem__(self, key, value):
+        self.put(key, value)
+
+    def __delitem__(self, key):
+        if not self.remove(key):
+            raise KeyError(key)
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc):
+        self.close()
+
+    def __del__(self):
+        self.close()
+
+    def _handle(self):
+        if self._ptr is None:
+            raise ValueError("cache fermé")
+        return self._ptr
+
+
+class PersistentLruCache(LruCache):
+    """Cache persistant sur `path`, au format de PersistentLruCache<String, String>
+
+    Le fichier est sauvegardé à la fermeture, selon les options par défaut.
+    """
+
+    def __init__(self, path, capacity):
+        self._ptr = None
+        ptr = _lib.lru_cache_open(os.fsencode(path), capacity)
+        if not ptr:
+            raise OSError("impossible d'ouvrir le cache {!r}".format(path))
+        self._ptr = ptr
+
+    def save(self):
+        """Sauvegarde immédiate du fichier"""
+        if _lib.lru_cache_save(self._handle()) != 0:
+            raise OSError("échec de la sauvegarde du cache")
@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::{pin, Pin};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use crate::maintenance::{Maintain, Watermarks};
+use crate::stats::CacheStats;
+use crate::sync::SyncLruCache;
+
+/// Cache LRU utilisable depuis du code asynchrone
+///
+/// Indépendant de tout runtime: le verrou interne n'est tenu que le temps
+/// d'une opération sur le cache et jamais pendant un `.await`, il ne bloque
+/// donc pas les threads du runtime. Les chargements passés à
+/// `get_or_insert_with` s'exécutent hors verrou.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::{block_on, AsyncLruCache};
+///
+/// let cache = AsyncLruCache::new(10);
+///
+/// block_on(async {
+///     cache.put(1, "un".to_string()).await;
+///     let value = cache
+///         .get_or_insert_with(2, || async { "deux".to_string() })
+///         .await;
+///
+///     assert_eq!(value, "deux");
+///     assert_eq!(cache.get(&1).await, Some("un".to_string()));
+/// });
+/// ```
+pub struct AsyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    inner: SyncLruCache<K, V>,
+    in_flight: Arc<Mutex<HashMap<K, Arc<Flight<V>>>>>,
+}
+
+impl<K, V> Clone for AsyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            in_flight: Arc::clone(&self.in_flight),
+        }
+    }
+}
+
+impl<K, V> AsyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: SyncLruCache::new(capacity),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub async fn put(&self, key: K, value: V) -> Option<Arc<V>> {
+        self.inner.put(key, value)
+    }
+
+    pub async fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.inner.get(key)
+    }
+
+    pub async fn get_arc(&self, key: &K) -> Option<Arc<V>> {
+        self.inner.get_arc(key)
+    }
+
+    pub async fn peek(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.inner.peek(key)
+    }
+
+    pub async fn contains_key(&self, key: &K) -> bool {
+        self.inner.contains_key(key)
+    }
+
+    /// Retourne la valeur en cache, ou attend `load` et insère son résultat
+    pub async fn get_or_insert_with<F, Fut>(&self, key: K, load: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+        V: Clone,
+    {
+        if let Some(value) = self.inner.get(&key) {
+            return value;
+        }
+
+        let value = load().await;
+        self.inner.put(key, value.clone());
+        value
+    }
+
+    /// Comme `get_or_insert_with`, mais les chargements concurrents d'une même
+    /// clé sont fusionnés
+    ///
+    /// Seul le premier appelant exécute sa future `init`; les autres attendent
+    /// son résultat sans solliciter le backend. Si cette future est abandonnée
+    /// avant la fin, l'un des appelants en attente prend le relais avec la
+    /// sienne.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::{block_on, AsyncLruCache};
+    ///
+    /// let cache = AsyncLruCache::new(10);
+    /// let value = block_on(cache.get_with("user:1", async { "Alice".to_string() }));
+    ///
+    /// assert_eq!(value, "Alice");
+    /// ```
+    pub async fn get_with<Fut>(&self, key: K, init: Fut) -> V
+    where
+        Fut: Future<Output = V>,
+        V: Clone,
+    {
+        loop {
+            if let Some(value) = self.inner.get(&key) {
+                return value;
+            }
+
+            let (flight, leader) = {
+                let mut in_flight = self.in_flight();
+                match in_flight.get(&key) {
+                    Some(flight) => (Arc::clone(flight), false),
+                    None => {
+                        let flight = Arc::new(Flight::new());
+                        in_flight.insert(key.clone(), Arc::clone(&flight));
+                        (flight, true)
+                    }
+                }
+            };
+
+            if leader {
+                let guard = FlightGuard {
+                    cache: self,
+                    key: &key,
+                    flight: &flight,
+                };
+
+                // Un chargement concurrent a pu se terminer entre-temps
+                let value = match self.inner.peek(&key) {
+                    Some(value) => value,
+                    None => {
+                        let value = init.await;
+                        self.inner.put(key.clone(), value.clone());
+                        value
+                    }
+                };
+
+                flight.finish(Some(value.clone()));
+                drop(guard);
+                return value;
+            }
+
+            if let Some(value) = flight.wait().await {
+                return value;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Cache synchrone partagé, pour les opérations sans attente
+    pub(crate) fn inner(&self) -> &SyncLruCache<K, V> {
+        &self.inner
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.inner.stats()
+    }
+
+    /// Copie instantanée des entrées, voir `SyncLruCache::snapshot`
+    pub fn snapshot(&self) -> Vec<(K, V)>
+    where
+        V: Clone,
+    {
+        self.inner.snapshot()
+    }
+
+    /// Flux des entrées, de la moins à la plus récente
+    ///
+    /// Seules les clés sont copiées au départ; chaque valeur est lue au moment
+    /// où le consommateur la demande, ce qui évite de tout mettre en mémoire
+    /// pour un export lent. Les entrées retirées entre-temps sont sautées.
+    ///
+    /// Le flux parcourt cette copie des clés, pas le cache lui-même: l'ordre
+    /// est celui du départ, une clé insérée ensuite n'apparaît pas, et une
+    /// valeur remplacée entre-temps est lue dans sa nouvelle version.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::{block_on, AsyncLruCache};
+    ///
+    /// let cache = AsyncLruCache::new(10);
+    /// block_on(async {
+    ///     cache.put("a", 1).await;
+    ///     cache.put("b", 2).await;
+    ///
+    ///     let mut stream = cache.stream();
+    ///     let mut total = 0;
+    ///     while let Some((_, value)) = stream.next().await {
+    ///         total += *value;
+    ///     }
+    ///     assert_eq!(total, 3);
+    /// });
+    /// ```
+    pub fn stream(&self) -> EntryStream<K, V> {
+        EntryStream {
+            cache: self.inner.clone(),
+            keys: self.inner.keys().into_iter(),
+        }
+    }
+
+    fn in_flight(&self) -> MutexGuard<'_, HashMap<K, Arc<Flight<V>>>> {
+        self.in_flight.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Flux d'entrées retourné par `AsyncLruCache::stream`
+///
+/// Parcourt une copie des clés prise à sa création, sans verrou tenu entre
+/// deux entrées: le cache reste modifiable pendant l'itération.
+///
+/// `poll_next` a la signature de `futures::Stream::poll_next`, ce qui permet
+/// de l'adapter directement à cette interface.
+pub struct EntryStream<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: SyncLruCache<K, V>,
+    keys: std::vec::IntoIter<K>,
+}
+
+// Aucun champ n'est épinglé: le flux peut être déplacé librement
+impl<K, V> Unpin for EntryStream<K, V> where K: Hash + Eq + Clone {}
+
+impl<K, V> EntryStream<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<(K, Arc<V>)>> {
+        let this = self.get_mut();
+        for key in this.keys.by_ref() {
+            if let Some(value) = this.cache.peek_arc(&key) {
+                return Poll::Ready(Some((key, value)));
+            }
+        }
+        Poll::Ready(None)
+    }
+
+    /// Prochaine entrée, `None` à la fin du flux
+    pub async fn next(&mut self) -> Option<(K, Arc<V>)> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+    }
+
+    /// Nombre maximal d'entrées restantes
+    pub fn remaining(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+/// Chargement en cours partagé par les appelants de `get_with`
+pub(crate) struct Flight<V> {
+    state: Mutex<FlightState<V>>,
+}
+
+struct FlightState<V> {
+    /// `None` tant que le chargement est en cours, `Some(None)` s'il a été
+    /// abandonné
+    result: Option<Option<V>>,
+    waiters: Vec<Waker>,
+}
+
+impl<V> Flight<V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Mutex::new(FlightState {
+                result: None,
+                waiters: Vec::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn finish(&self, result: Option<V>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.result.is_none() {
+            state.result = Some(result);
+        }
+        for waker in state.waiters.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Résultat du chargement, `None` s'il a été abandonné
+    pub(crate) fn wait(&self) -> FlightWait<'_, V> {
+        FlightWait { flight: self }
+    }
+}
+
+/// Retire le chargement de la table à la fin, même si la future est abandonnée
+struct FlightGuard<'a, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: &'a AsyncLruCache<K, V>,
+    key: &'a K,
+    flight: &'a Arc<Flight<V>>,
+}
+
+impl<K, V> Drop for FlightGuard<'_, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        let mut in_flight = self.cache.in_flight();
+        if in_flight
+            .get(self.key)
+            .is_some_and(|flight| Arc::ptr_eq(flight, self.flight))
+        {
+            in_flight.remove(self.key);
+        }
+        drop(in_flight);
+
+        self.flight.finish(None);
+    }
+}
+
+pub(crate) struct FlightWait<'a, V> {
+    flight: &'a Flight<V>,
+}
+
+impl<V: Clone> Future for FlightWait<'_, V> {
+    type Output = Option<V>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<V>> {
+        let mut state = self.flight.state.lock().unwrap_or_else(|e| e.into_inner());
+        match &state.result {
+            Some(result) => Poll::Ready(result.clone()),
+            None => {
+                state.waiters.push(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<K, V> Maintain for AsyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    fn run_maintenance(&self, watermarks: Option<Watermarks>) {
+        self.inner.run_maintenance(watermarks);
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Exécute une future jusqu'à son terme sur le thread courant
+///
+/// Exécuteur minimal pour utiliser les caches asynchrones depuis du code
+/// synchrone ou des tests, sans dépendre d'un runtime.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_or_insert_with_uses_cached_value() {
+        let cache = AsyncLruCache::new(2);
+
+        block_on(async {
+            cache.put("a", 1).await;
+            let value = cache
+                .get_or_insert_with("a", || async { panic!("ne doit pas charger") })
+                .await;
+
+            assert_eq!(value, 1);
+            assert_eq!(cache.get_or_insert_with("b", || async { 2 }).await, 2);
+            assert_eq!(cache.peek(&"b").await, Some(2));
+        });
+    }
+
+    #[test]
+    fn test_stream_skips_removed_entries() {
+        let cache = AsyncLruCache::new(10);
+        block_on(async {
+            for key in 1..=4 {
+                cache.put(key, key * 10).await;
+            }
+
+            let mut stream = cache.stream();
+            let first = std::future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await;
+            assert_eq!(first, Some((1, Arc::new(10))));
+            assert_eq!(stream.remaining(), 3);
+
+            // Pendant le parcours: 2 retirée, 3 remplacée, 5 ajoutée
+            cache.inner().remove(&2);
+            cache.put(3, 33).await;
+            cache.put(5, 50).await;
+
+            let mut rest = Vec::new();
+            while let Some((key, value)) = stream.next().await {
+                rest.push((key, *value));
+            }
+            assert_eq!(rest, vec![(3, 33), (4, 40)]);
+            assert_eq!(stream.remaining(), 0);
+            assert_eq!(stream.next().await, None);
+        });
+    }
+
+    #[test]
+    fn test_get_with_coalesces_concurrent_loads() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Barrier;
+        use std::time::Duration;
+
+        let cache = AsyncLruCache::new(10);
+        let loads = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(4));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                let loads = Arc::clone(&loads);
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    block_on(cache.get_with("clé", async move {
+                        loads.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(50));
+                        42
+                    }))
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 42);
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+    }
+}
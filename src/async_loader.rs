@@ -0,0 +1,424 @@
+//! Chargement asynchrone des clés absentes: fusion, délai et échecs retenus
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::future::{poll_fn, Future};
+use std::hash::Hash;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::async_cache::{AsyncLruCache, Flight};
+use crate::sync::SyncLruCache;
+
+/// Source asynchrone des valeurs d'un `AsyncLoadingCache`
+///
+/// Implémenté pour toute closure `Fn(&K) -> impl Future<Output = Result<V, E>>`
+/// dont la future ne garde pas la clé empruntée.
+pub trait AsyncCacheLoader<K, V> {
+    type Error;
+
+    fn load(&self, key: &K) -> impl Future<Output = Result<V, Self::Error>>;
+}
+
+impl<K, V, E, F, Fut> AsyncCacheLoader<K, V> for F
+where
+    F: Fn(&K) -> Fut,
+    Fut: Future<Output = Result<V, E>>,
+{
+    type Error = E;
+
+    fn load(&self, key: &K) -> impl Future<Output = Result<V, E>> {
+        self(key)
+    }
+}
+
+/// Échec d'un chargement asynchrone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError<E> {
+    /// Erreur renvoyée par le chargeur
+    Failed(E),
+    /// Le chargeur n'a pas répondu dans le délai de
+    /// `AsyncLoadingCache::timeout`
+    TimedOut,
+}
+
+impl<E: fmt::Display> fmt::Display for LoadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Failed(err) => write!(f, "échec du chargement: {}", err),
+            LoadError::TimedOut => write!(f, "délai de chargement dépassé"),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for LoadError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Failed(err) => Some(err),
+            LoadError::TimedOut => None,
+        }
+    }
+}
+
+type Outcome<V, E> = Result<V, LoadError<E>>;
+
+/// Chargements en cours, par clé
+type Flights<K, T> = Mutex<HashMap<K, Arc<Flight<T>>>>;
+
+/// Échecs retenus et leur durée de rétention
+type RetainedErrors<K, E> = (SyncLruCache<K, LoadError<E>>, Duration);
+
+/// Cache asynchrone qui charge lui-même les clés absentes
+///
+/// `get_or_load` réunit ce que chaque appelant réécrirait sinon:
+///
+/// - les appels concurrents pour une même clé n'appellent le chargeur
+///   qu'une fois et partagent son résultat, erreur comprise;
+/// - avec `timeout`, un chargement trop long échoue avec
+///   `LoadError::TimedOut` (la future du chargeur est abandonnée);
+/// - avec `cache_errors`, un échec est renvoyé tel quel pendant un délai
+///   sans rappeler le chargeur.
+///
+/// Les erreurs sont partagées entre appelants: `L::Error` doit être
+/// `Clone` (un `Arc` autour d'une erreur qui ne l'est pas suffit). Sans
+/// runtime imposé, le délai est compté par un thread qui dort jusqu'à
+/// l'échéance.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::{block_on, AsyncLoadingCache};
+/// use std::time::Duration;
+///
+/// let users = AsyncLoadingCache::new(100, |id: &u32| {
+///     let id = *id;
+///     async move { Ok::<_, String>(format!("user-{}", id)) }
+/// })
+/// .timeout(Duration::from_secs(1))
+/// .cache_errors(Duration::from_secs(5));
+///
+/// block_on(async {
+///     assert_eq!(users.get_or_load(&1).await, Ok("user-1".to_string()));
+///     assert_eq!(users.get_if_present(&1), Some("user-1".to_string()));
+/// });
+/// ```
+pub struct AsyncLoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    L: AsyncCacheLoader<K, V>,
+{
+    cache: AsyncLruCache<K, V>,
+    loader: Arc<L>,
+    in_flight: Arc<Flights<K, Outcome<V, L::Error>>>,
+    timeout: Option<Duration>,
+    errors: Option<RetainedErrors<K, L::Error>>,
+}
+
+impl<K, V, L> Clone for AsyncLoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    L: AsyncCacheLoader<K, V>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+            loader: Arc::clone(&self.loader),
+            in_flight: Arc::clone(&self.in_flight),
+            timeout: self.timeout,
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+impl<K, V, L> AsyncLoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    L: AsyncCacheLoader<K, V>,
+    L::Error: Clone,
+{
+    pub fn new(capacity: usize, loader: L) -> Self {
+        Self::with_cache(AsyncLruCache::new(capacity), loader)
+    }
+
+    /// Charge les clés absentes de `cache`, partagé avec ses autres clones
+    pub fn with_cache(cache: AsyncLruCache<K, V>, loader: L) -> Self {
+        Self {
+            cache,
+            loader: Arc::new(loader),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+            timeout: None,
+            errors: None,
+        }
+    }
+
+    /// Abandonne un chargement qui dure plus de `timeout`
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retient chaque échec (délai dépassé compris) pendant `ttl`
+    pub fn cache_errors(mut self, ttl: Duration) -> Self {
+        self.errors = Some((SyncLruCache::new(self.cache.capacity()), ttl));
+        self
+    }
+
+    /// Valeur en cache, échec retenu, ou résultat du chargeur
+    pub async fn get_or_load(&self, key: &K) -> Outcome<V, L::Error> {
+        loop {
+            if let Some(value) = self.cache.get(key).await {
+                return Ok(value);
+            }
+            if let Some((errors, _)) = &self.errors {
+                if let Some(err) = errors.peek(key) {
+                    return Err(err);
+                }
+            }
+
+            let (flight, leader) = {
+                let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+                match in_flight.get(key) {
+                    Some(flight) => (Arc::clone(flight), false),
+                    None => {
+                        let flight = Arc::new(Flight::new());
+                        in_flight.insert(key.clone(), Arc::clone(&flight));
+                        (flight, true)
+                    }
+                }
+            };
+
+            if !leader {
+                match flight.wait().await {
+                    Some(outcome) => return outcome,
+                    // Meneur abandonné: reprendre le chargement
+                    None => continue,
+                }
+            }
+
+            let guard = FlightGuard {
+                in_flight: &self.in_flight,
+                key,
+                flight: &flight,
+            };
+            let outcome = match self.cache.peek(key).await {
+                Some(value) => Ok(value),
+                None => self.load(key).await,
+            };
+            match &outcome {
+                Ok(value) => {
+                    self.cache.put(key.clone(), value.clone()).await;
+                }
+                Err(err) => {
+                    if let Some((errors, ttl)) = &self.errors {
+                        errors.put_with_ttl(key.clone(), err.clone(), *ttl);
+                    }
+                }
+            }
+            flight.finish(Some(outcome.clone()));
+            drop(guard);
+            return outcome;
+        }
+    }
+
+    /// Valeur en cache uniquement, sans appeler le chargeur
+    pub fn get_if_present(&self, key: &K) -> Option<V> {
+        self.cache.inner().get(key)
+    }
+
+    pub fn put(&self, key: K, value: V) {
+        self.forget_error(&key);
+        self.cache.inner().put(key, value);
+    }
+
+    /// Oublie une clé et son éventuel échec retenu
+    pub fn invalidate(&self, key: &K) {
+        self.forget_error(key);
+        self.cache.inner().remove(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Cache sous-jacent
+    pub fn cache(&self) -> &AsyncLruCache<K, V> {
+        &self.cache
+    }
+
+    async fn load(&self, key: &K) -> Outcome<V, L::Error> {
+        let load = self.loader.load(key);
+        let Some(timeout) = self.timeout else {
+            return load.await.map_err(LoadError::Failed);
+        };
+        let mut load = pin!(load);
+        let mut delay = Delay::new(timeout);
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = load.as_mut().poll(cx) {
+                return Poll::Ready(result.map_err(LoadError::Failed));
+            }
+            delay.poll(cx).map(|()| Err(LoadError::TimedOut))
+        })
+        .await
+    }
+
+    fn forget_error(&self, key: &K) {
+        if let Some((errors, _)) = &self.errors {
+            errors.remove(key);
+        }
+    }
+}
+
+/// Retire le chargement de la table à la fin, même si la future est abandonnée
+struct FlightGuard<'a, K, T>
+where
+    K: Hash + Eq,
+{
+    in_flight: &'a Flights<K, T>,
+    key: &'a K,
+    flight: &'a Arc<Flight<T>>,
+}
+
+impl<K, T> Drop for FlightGuard<'_, K, T>
+where
+    K: Hash + Eq,
+{
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if in_flight
+            .get(self.key)
+            .is_some_and(|flight| Arc::ptr_eq(flight, self.flight))
+        {
+            in_flight.remove(self.key);
+        }
+        drop(in_flight);
+
+        self.flight.finish(None);
+    }
+}
+
+/// Échéance sans runtime: un thread réveille la tâche à l'heure dite
+struct Delay {
+    deadline: Instant,
+    waker: Option<Arc<Mutex<Waker>>>,
+}
+
+impl Delay {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + timeout,
+            waker: None,
+        }
+    }
+
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+        match &self.waker {
+            Some(waker) => {
+                *waker.lock().unwrap_or_else(|e| e.into_inner()) = cx.waker().clone();
+            }
+            None => {
+                let waker = Arc::new(Mutex::new(cx.waker().clone()));
+                let shared = Arc::clone(&waker);
+                let deadline = self.deadline;
+                thread::spawn(move || {
+                    thread::sleep(deadline.saturating_duration_since(Instant::now()));
+                    shared
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .wake_by_ref();
+                });
+                self.waker = Some(waker);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_cache::block_on;
+    use std::future::pending;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_timeout_and_error_caching() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let cache = AsyncLoadingCache::new(10, move |key: &i32| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            let key = *key;
+            async move {
+                match key {
+                    0 => pending().await,
+                    1 => Err("absent"),
+                    _ => Ok(key * 10),
+                }
+            }
+        })
+        .timeout(Duration::from_millis(20))
+        .cache_errors(Duration::from_secs(60));
+
+        block_on(async {
+            assert_eq!(cache.get_or_load(&0).await, Err(LoadError::TimedOut));
+            assert_eq!(
+                cache.get_or_load(&1).await,
+                Err(LoadError::Failed("absent"))
+            );
+            assert_eq!(
+                cache.get_or_load(&1).await,
+                Err(LoadError::Failed("absent"))
+            );
+            assert_eq!(cache.get_or_load(&2).await, Ok(20));
+            assert_eq!(cache.get_or_load(&2).await, Ok(20));
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        cache.invalidate(&1);
+        let _ = block_on(cache.get_or_load(&1));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_concurrent_loads_share_errors() {
+        use std::sync::Barrier;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let cache = AsyncLoadingCache::new(10, move |_: &&str| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async {
+                thread::sleep(Duration::from_millis(50));
+                Err::<i32, _>("panne")
+            }
+        });
+        let barrier = Arc::new(Barrier::new(4));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                let barrier = Arc::clone(&barrier);
+                thread::spawn(move || {
+                    barrier.wait();
+                    block_on(cache.get_or_load(&"clé"))
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), Err(LoadError::Failed("panne")));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,280 @@
+//! Cache persistant pour le code asynchrone
+
+use std::borrow::Borrow;
+use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+use crate::codec::Persistable;
+use crate::error::CacheError;
+use crate::persistent::{PersistOptions, PersistentLruCache};
+use crate::stats::CacheStats;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// `PersistentLruCache` dont les accès disque n'occupent pas les threads du
+/// runtime
+///
+/// Chargement, écritures et sauvegardes s'exécutent dans l'ordre sur un
+/// thread dédié (l'équivalent de `spawn_blocking`, sans dépendre de tokio);
+/// les futures attendent leur résultat sans bloquer. Les lectures se font
+/// directement en mémoire, sauf pendant une sauvegarde où elles sont
+/// confiées au même thread.
+///
+/// La sauvegarde finale (`save_on_drop`) a lieu sur ce thread après la
+/// disparition du dernier clone; attendre `save()` avant l'arrêt du
+/// processus garantit qu'elle est terminée.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{block_on, AsyncPersistentLruCache, PersistOptions};
+///
+/// block_on(async {
+///     let cache: AsyncPersistentLruCache<String, String> =
+///         AsyncPersistentLruCache::open(100, "sessions.cache", PersistOptions::new())
+///             .await
+///             .unwrap();
+///
+///     cache.put("alice".to_string(), "jeton".to_string()).await.unwrap();
+///     assert_eq!(cache.get("alice").await, Some("jeton".to_string()));
+/// });
+/// ```
+pub struct AsyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    shared: Arc<Shared<K, V>>,
+}
+
+struct Shared<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    cache: Arc<Mutex<Option<PersistentLruCache<K, V>>>>,
+    jobs: Sender<Job>,
+}
+
+impl<K, V> Clone for AsyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<K, V> AsyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    /// Ouvre le cache, le fichier étant lu sur le thread de persistance
+    pub async fn open(
+        capacity: usize,
+        path: &str,
+        options: PersistOptions,
+    ) -> Result<Self, CacheError> {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        thread::spawn(move || {
+            for job in queue {
+                job();
+            }
+        });
+
+        let path = path.to_string();
+        let cache = run(&jobs, move || {
+            PersistentLruCache::with_options(capacity, &path, options)
+        })
+        .await?;
+
+        Ok(Self {
+            shared: Arc::new(Shared {
+                cache: Arc::new(Mutex::new(Some(cache))),
+                jobs,
+            }),
+        })
+    }
+
+    /// Insère une entrée; la future se termine une fois l'écriture faite
+    pub async fn put(&self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.write(move |cache| cache.try_put(key, value)).await
+    }
+
+    pub async fn remove(&self, key: K) -> Result<Option<V>, CacheError> {
+        self.write(move |cache| cache.try_remove(&key)).await
+    }
+
+    pub async fn save(&self) -> Result<(), CacheError> {
+        self.write(|cache| cache.save()).await
+    }
+
+    pub async fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ToOwned + ?Sized,
+        Q::Owned: Send + 'static,
+        V: Clone,
+    {
+        let owned = match self.shared.cache.try_lock() {
+            Ok(mut cache) => return live(&mut cache).get(key).cloned(),
+            Err(TryLockError::Poisoned(err)) => {
+                return live(&mut err.into_inner()).get(key).cloned()
+            }
+            // Sauvegarde en cours: attendre son tour sur le thread dédié
+            Err(TryLockError::WouldBlock) => key.to_owned(),
+        };
+        self.write(move |cache| cache.get(owned.borrow()).cloned())
+            .await
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        live(&mut self.lock()).stats()
+    }
+
+    async fn write<T, F>(&self, op: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut PersistentLruCache<K, V>) -> T + Send + 'static,
+    {
+        let cache = Arc::clone(&self.shared.cache);
+        run(&self.shared.jobs, move || {
+            let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+            op(live(&mut cache))
+        })
+        .await
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<PersistentLruCache<K, V>>> {
+        self.shared.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<K, V> Drop for Shared<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    fn drop(&mut self) {
+        // La sauvegarde de fermeture se fait sur le thread de persistance
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(cache) = cache {
+            let _ = self.jobs.send(Box::new(move || drop(cache)));
+        }
+    }
+}
+
+fn live<K, V>(cache: &mut Option<PersistentLruCache<K, V>>) -> &mut PersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    cache.as_mut().expect("cache fermé")
+}
+
+/// Exécute `job` sur le thread de persistance et attend son résultat
+fn run<T, F>(jobs: &Sender<Job>, job: F) -> Reply<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let slot = Arc::new(Mutex::new(Slot {
+        value: None,
+        done: false,
+        waker: None,
+    }));
+    let completer = Completer(Arc::clone(&slot));
+    let _ = jobs.send(Box::new(move || {
+        let value = job();
+        completer.complete(value);
+    }));
+    Reply(slot)
+}
+
+struct Slot<T> {
+    value: Option<T>,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+/// Termine la réponse, même si le travail panique
+struct Completer<T>(Arc<Mutex<Slot<T>>>);
+
+impl<T> Completer<T> {
+    fn complete(self, value: T) {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).value = Some(value);
+    }
+}
+
+impl<T> Drop for Completer<T> {
+    fn drop(&mut self) {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        slot.done = true;
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct Reply<T>(Arc<Mutex<Slot<T>>>);
+
+impl<T> Future for Reply<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut slot = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if !slot.done {
+            slot.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => panic!("le thread de persistance s'est arrêté"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_cache::block_on;
+    use crate::persistent::Autosave;
+    use std::fs;
+
+    #[test]
+    fn test_async_persistence_roundtrip() {
+        let path = "test_async_persistent.cache";
+        let options = PersistOptions::new().autosave(Autosave::Manual);
+
+        block_on(async {
+            let cache: AsyncPersistentLruCache<String, i32> =
+                AsyncPersistentLruCache::open(10, path, options.clone())
+                    .await
+                    .unwrap();
+            cache.put("a".to_string(), 1).await.unwrap();
+            cache.put("b".to_string(), 2).await.unwrap();
+            assert_eq!(cache.remove("b".to_string()).await.unwrap(), Some(2));
+            cache.save().await.unwrap();
+            assert_eq!(cache.get("a").await, Some(1));
+            drop(cache);
+
+            let cache: AsyncPersistentLruCache<String, i32> =
+                AsyncPersistentLruCache::open(10, path, options)
+                    .await
+                    .unwrap();
+            assert_eq!(cache.get("a").await, Some(1));
+            assert_eq!(cache.get("b").await, None);
+        });
+
+        fs::remove_file(path).ok();
+    }
+}
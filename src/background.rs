@@ -0,0 +1,153 @@
+//! Sauvegarde différée d'un `PersistentLruCache` sur un thread de fond
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::codec::Persistable;
+use crate::error::CacheError;
+use crate::persistent::PersistentLruCache;
+use crate::stats::CacheStats;
+
+/// Cache retourné par `PersistentLruCache::save_in_background`
+///
+/// Partageable entre threads par `clone`. Le thread de fond s'arrête avec
+/// le dernier clone, après une sauvegarde finale des écritures en attente.
+pub struct BackgroundPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    inner: Arc<Inner<K, V>>,
+}
+
+struct Inner<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    cache: Arc<Mutex<PersistentLruCache<K, V>>>,
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl<K, V> Clone for BackgroundPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> BackgroundPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    pub(crate) fn new(cache: PersistentLruCache<K, V>, interval: Duration) -> Self {
+        let cache = Arc::new(Mutex::new(cache));
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let shared = Arc::clone(&cache);
+        let thread = thread::spawn(move || {
+            let flush = || lock(&shared).flush_pending();
+            while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                flush();
+            }
+            flush();
+        });
+
+        Self {
+            inner: Arc::new(Inner {
+                cache,
+                stop: Some(stop),
+                thread: Some(thread),
+            }),
+        }
+    }
+
+    pub fn put(&self, key: K, value: V) -> Option<V> {
+        self.lock().put(key, value)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.lock().remove(key)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.lock().get(key).cloned()
+    }
+
+    /// Sauvegarde immédiatement, sans attendre le thread de fond
+    pub fn save(&self) -> Result<(), CacheError> {
+        self.lock().save()
+    }
+
+    /// Sauvegarde les écritures en attente sans attendre le thread de fond
+    pub fn flush(&self) -> Result<(), CacheError> {
+        self.lock().flush()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PersistentLruCache<K, V>> {
+        lock(&self.inner.cache)
+    }
+}
+
+impl<K, V> Drop for Inner<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    fn drop(&mut self) {
+        // Fermer le canal réveille le thread pour la sauvegarde finale
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_background_save_is_debounced() {
+        let path = "test_background_save.cache";
+        let cache: PersistentLruCache<String, i32> = PersistentLruCache::open(10, path).unwrap();
+        let cache = cache.save_in_background(Duration::from_secs(60));
+
+        cache.put("a".to_string(), 1);
+        cache.put("b".to_string(), 2);
+        assert!(!std::path::Path::new(path).exists());
+
+        drop(cache); // sauvegarde finale
+        let mut reopened: PersistentLruCache<String, i32> =
+            PersistentLruCache::open(10, path).unwrap();
+        assert_eq!(reopened.get("b"), Some(&2));
+
+        drop(reopened);
+        fs::remove_file(path).ok();
+    }
+}
@@ -1,3 +1,430 @@
-fn main() {
-    todo!();
+//! `cache-lru`: inspection et gestion des fichiers de cache (feature `cli`)
+//!
+//! ```text
+//! cargo run --features cli --bin cache-lru -- inspect cache.txt --keys
+//! ```
+
+use std::env;
+use std::fmt;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+use lru_cache::{Autosave, CacheError, Format, PersistOptions, PersistentLruCache};
+
+const USAGE: &str = "\
+usage: cache-lru <commande> [options]
+
+commandes:
+  inspect <fichier> [--keys]          capacité, nombre d'entrées, statistiques
+                                      (--keys: clés de la moins à la plus récente)
+  get <fichier> <clé>                 affiche la valeur, sans modifier le fichier
+  put <fichier> <clé> <valeur>        insère ou remplace une entrée
+  rm <fichier> <clé>                  retire une entrée
+  compact <fichier>                   réécrit le fichier avec les seules entrées présentes
+  convert <source> <cible> --to <f>   réécrit le fichier dans un autre format
+  verify <fichier>                    liste les enregistrements fautifs
+  repl <fichier>                      invite interactive (get, put, del, keys, stats)
+  memcached <adresse>                 cache en mémoire servi en protocole memcached
+                                      (feature `memcached`)
+  redis <adresse>                     cache en mémoire servi en protocole Redis
+                                      (feature `redis`)
+  http <adresse>                      cache en mémoire servi en HTTP (feature `http`)
+
+options:
+  --format <f>      format du fichier: text (défaut), json, json-pretty, binary, csv, msgpack, rdb
+  --log             fichier en mode journal (PersistOptions::write_ahead_log)
+  --capacity <n>    capacité (défaut: celle du fichier, sinon 1000)";
+
+const REPL_HELP: &str = "\
+get <clé>              valeur, marquée comme récente
+put <clé> <valeur>     insère ou remplace (la valeur va jusqu'à la fin de la ligne)
+del <clé>              retire une entrée
+keys                   clés, de la moins à la plus récente
+stats                  entrées, capacité, hits, misses, évictions
+save                   écrit le fichier sans attendre la sortie
+quit                   sauvegarde et quitte (aussi: exit, fin de l'entrée)";
+
+/// Capacité d'un fichier créé sans `--capacity`
+const DEFAULT_CAPACITY: usize = 1000;
+
+/// Erreur de la ligne de commande
+#[derive(Debug)]
+enum Error {
+    /// Arguments invalides: le message est suivi de l'usage
+    Usage(String),
+    Cache(CacheError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(message) => write!(f, "{}", message),
+            Error::Cache(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<CacheError> for Error {
+    fn from(err: CacheError) -> Self {
+        Error::Cache(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Cache(CacheError::Io(err))
+    }
+}
+
+/// Options communes aux commandes
+#[derive(Debug, Default)]
+struct Options {
+    format: Format,
+    log: bool,
+    capacity: Option<usize>,
+    keys: bool,
+    to: Option<Format>,
+}
+
+impl Options {
+    /// Ouvre `path`; en lecture seule, le fichier doit exister et n'est
+    /// jamais modifié
+    fn open(&self, path: &str, read_only: bool) -> Result<PersistentLruCache, CacheError> {
+        let capacity = match self.capacity {
+            Some(capacity) => capacity,
+            None if read_only || Path::new(path).exists() => usize::MAX,
+            None => DEFAULT_CAPACITY,
+        };
+        let mut options = PersistOptions::new()
+            .format(self.format)
+            .read_only(read_only)
+            .autosave(Autosave::Manual);
+        if self.log {
+            options = options.write_ahead_log(usize::MAX);
+        }
+        PersistentLruCache::with_options(capacity, path, options)
+    }
+
+    /// Écrit les modifications; en mode journal, elles sont déjà à la fin
+    /// du fichier et ne justifient pas de le compacter
+    fn flush(&self, cache: &mut PersistentLruCache) -> Result<(), CacheError> {
+        match self.log {
+            true => Ok(()),
+            false => cache.flush(),
+        }
+    }
+}
+
+/// Sépare les options des arguments positionnels
+fn parse(args: &[String]) -> Result<(Vec<&str>, Options), Error> {
+    let mut positional = Vec::new();
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .map(String::as_str)
+                .ok_or_else(|| Error::Usage(format!("valeur manquante pour {}", name)))
+        };
+        match arg.as_str() {
+            "--format" => options.format = parse_format(value("--format")?)?,
+            "--to" => options.to = Some(parse_format(value("--to")?)?),
+            "--capacity" => {
+                let capacity = value("--capacity")?;
+                options.capacity = Some(
+                    capacity
+                        .parse()
+                        .map_err(|_| Error::Usage(format!("capacité invalide: {}", capacity)))?,
+                );
+            }
+            "--log" => options.log = true,
+            "--keys" => options.keys = true,
+            option if option.starts_with("--") => {
+                return Err(Error::Usage(format!("option inconnue: {}", option)))
+            }
+            _ => positional.push(arg.as_str()),
+        }
+    }
+    Ok((positional, options))
+}
+
+fn parse_format(name: &str) -> Result<Format, Error> {
+    match name {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json { pretty: false }),
+        "json-pretty" => Ok(Format::Json { pretty: true }),
+        "binary" => Ok(Format::Binary),
+        "csv" => Ok(Format::Csv),
+        #[cfg(feature = "msgpack")]
+        "msgpack" => Ok(Format::MessagePack),
+        #[cfg(feature = "redis")]
+        "rdb" => Ok(Format::Rdb),
+        _ => Err(Error::Usage(format!("format inconnu: {}", name))),
+    }
+}
+
+/// Exécute une commande; `false` si elle a échoué sans erreur (clé
+/// absente, fichier avec des enregistrements fautifs)
+fn run(args: &[String], input: &mut dyn BufRead, out: &mut dyn Write) -> Result<bool, Error> {
+    let (positional, options) = parse(args)?;
+    match positional.as_slice() {
+        ["inspect", path] => {
+            let cache = options.open(path, true)?;
+            let stats = cache.stats();
+            writeln!(out, "fichier: {}", path)?;
+            match cache.capacity() {
+                usize::MAX => writeln!(out, "capacité: non enregistrée")?,
+                capacity => writeln!(out, "capacité: {}", capacity)?,
+            }
+            writeln!(out, "entrées: {}", cache.len())?;
+            writeln!(
+                out,
+                "hits: {}, misses: {}, évictions: {}",
+                stats.hits, stats.misses, stats.evictions
+            )?;
+            if options.keys {
+                for (key, _) in cache.iter() {
+                    writeln!(out, "{}", key)?;
+                }
+            }
+            Ok(true)
+        }
+        ["get", path, key] => {
+            let cache = options.open(path, true)?;
+            match cache.peek(*key) {
+                Some(value) => {
+                    writeln!(out, "{}", value)?;
+                    Ok(true)
+                }
+                None => Ok(false),
+            }
+        }
+        ["put", path, key, value] => {
+            let mut cache = options.open(path, false)?;
+            cache.try_put(key.to_string(), value.to_string())?;
+            options.flush(&mut cache)?;
+            Ok(true)
+        }
+        ["rm", path, key] => {
+            let mut cache = options.open(path, false)?;
+            let removed = cache.try_remove(&key.to_string())?.is_some();
+            options.flush(&mut cache)?;
+            Ok(removed)
+        }
+        ["compact", path] => {
+            options.open(path, false)?.compact()?;
+            Ok(true)
+        }
+        ["convert", source, target] => {
+            let to = options
+                .to
+                .ok_or_else(|| Error::Usage("convert: --to manquant".to_string()))?;
+            PersistentLruCache::<String, String>::convert(source, options.format, target, to)?;
+            Ok(true)
+        }
+        ["verify", path] => {
+            let report = options.open(path, true)?.verify()?;
+            for record in &report.skipped {
+                writeln!(out, "{}", record)?;
+            }
+            Ok(report.is_clean())
+        }
+        ["repl", path] => {
+            let mut cache = options.open(path, false)?;
+            repl(&mut cache, &options, input, out)?;
+            options.flush(&mut cache)?;
+            Ok(true)
+        }
+        #[cfg(feature = "memcached")]
+        ["memcached", addr] => {
+            let cache = lru_cache::SyncLruCache::new(options.capacity.unwrap_or(DEFAULT_CAPACITY));
+            let server = lru_cache::MemcachedServer::bind(addr, cache)?;
+            writeln!(out, "écoute sur {}", server.local_addr()?)?;
+            out.flush()?;
+            server.run();
+            Ok(true)
+        }
+        #[cfg(feature = "http")]
+        ["http", addr] => {
+            let cache = lru_cache::SyncLruCache::new(options.capacity.unwrap_or(DEFAULT_CAPACITY));
+            let server = lru_cache::HttpServer::bind(addr, cache)?;
+            writeln!(out, "écoute sur http://{}", server.local_addr()?)?;
+            out.flush()?;
+            server.run();
+            Ok(true)
+        }
+        #[cfg(feature = "redis")]
+        ["redis", addr] => {
+            let cache = lru_cache::SyncLruCache::new(options.capacity.unwrap_or(DEFAULT_CAPACITY));
+            let server = lru_cache::RedisServer::bind(addr, cache)?;
+            writeln!(out, "écoute sur {}", server.local_addr()?)?;
+            out.flush()?;
+            server.run();
+            Ok(true)
+        }
+        [] => Err(Error::Usage("commande manquante".to_string())),
+        [command, ..] => Err(Error::Usage(format!(
+            "commande inconnue ou arguments invalides: {}",
+            command
+        ))),
+    }
+}
+
+/// Lit les commandes de `input` une ligne à la fois jusqu'à `quit` ou la
+/// fin de l'entrée
+///
+/// Une erreur du cache est affichée et la session continue; seules les
+/// erreurs d'écriture sur `out` l'interrompent.
+fn repl(
+    cache: &mut PersistentLruCache,
+    options: &Options,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let prompt = io::stdin().is_terminal();
+    let mut line = String::new();
+    loop {
+        if prompt {
+            write!(out, "> ")?;
+            out.flush()?;
+        }
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let rest = rest.trim_start();
+        let result = match (command, rest.split_once(' ')) {
+            ("", _) => Ok(()),
+            ("get", None) if !rest.is_empty() => {
+                match cache.get(rest) {
+                    Some(value) => writeln!(out, "{}", value)?,
+                    None => writeln!(out, "(absente)")?,
+                }
+                Ok(())
+            }
+            ("put", Some((key, value))) => cache
+                .try_put(key.to_string(), value.trim_start().to_string())
+                .map(|_| ()),
+            ("del", None) if !rest.is_empty() => match cache.try_remove(&rest.to_string()) {
+                Ok(None) => Ok(writeln!(out, "(absente)")?),
+                result => result.map(|_| ()),
+            },
+            ("keys", _) => {
+                for (key, _) in cache.iter() {
+                    writeln!(out, "{}", key)?;
+                }
+                Ok(())
+            }
+            ("stats", _) => {
+                let stats = cache.stats();
+                writeln!(
+                    out,
+                    "entrées: {}/{}, hits: {}, misses: {}, évictions: {}",
+                    cache.len(),
+                    cache.capacity(),
+                    stats.hits,
+                    stats.misses,
+                    stats.evictions
+                )?;
+                Ok(())
+            }
+            ("save", _) => options.flush(cache),
+            ("quit" | "exit", _) => return Ok(()),
+            ("help", _) => Ok(writeln!(out, "{}", REPL_HELP)?),
+            _ => Ok(writeln!(out, "commande invalide: {} (voir help)", line)?),
+        };
+        if let Err(err) = result {
+            writeln!(out, "erreur: {}", err)?;
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if matches!(args.first().map(String::as_str), Some("-h" | "--help")) {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match run(&args, &mut io::stdin().lock(), &mut io::stdout().lock()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err @ Error::Usage(_)) => {
+            eprintln!("cache-lru: {}\n\n{}", err, USAGE);
+            ExitCode::from(2)
+        }
+        Err(err) => {
+            eprintln!("cache-lru: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn run_ok(args: &str) -> (bool, String) {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        let mut out = Vec::new();
+        let success = run(&args, &mut io::empty(), &mut out).unwrap();
+        (success, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_commands() {
+        let path = "test_cli.txt";
+        run_ok(&format!("put {} a 1 --capacity 2", path));
+        run_ok(&format!("put {} b 2", path));
+        assert_eq!(
+            run_ok(&format!("get {} a", path)),
+            (true, "1\n".to_string())
+        );
+
+        let (_, inspect) = run_ok(&format!("inspect {} --keys", path));
+        assert!(inspect.contains("capacité: 2\nentrées: 2\n"));
+        assert!(inspect.ends_with("a\nb\n"));
+
+        assert!(run_ok(&format!("rm {} a", path)).0);
+        assert!(!run_ok(&format!("get {} a", path)).0);
+        assert!(run_ok(&format!("verify {}", path)).0);
+
+        run_ok(&format!("convert {} test_cli.json --to json", path));
+        let (_, value) = run_ok("get test_cli.json b --format json");
+        assert_eq!(value, "2\n");
+
+        let args = vec!["put".to_string(), path.to_string()];
+        assert!(matches!(
+            run(&args, &mut io::empty(), &mut Vec::new()),
+            Err(Error::Usage(_))
+        ));
+        fs::remove_file(path).ok();
+        fs::remove_file("test_cli.txt.lock").ok();
+        fs::remove_file("test_cli.json").ok();
+    }
+
+    #[test]
+    fn test_repl() {
+        let path = "test_cli_repl.txt";
+        let args: Vec<String> = vec!["repl".into(), path.into(), "--capacity".into(), "2".into()];
+        let mut input = "put a un deux\nput b 2\nget a\nput c 3\nget b\ndel zz\nkeys\nstats\nfoo\nquit\nput d 4\n".as_bytes();
+        let mut out = Vec::new();
+        assert!(run(&args, &mut input, &mut out).unwrap());
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("un deux\n(absente)\n(absente)\na\nc\n"));
+        assert!(out.contains("entrées: 2/2, hits: 1, misses: 1, évictions: 1"));
+        assert!(out.contains("commande invalide: foo"));
+        assert_eq!(
+            run_ok(&format!("get {} c", path)),
+            (true, "3\n".to_string())
+        );
+        assert!(!run_ok(&format!("get {} d", path)).0);
+
+        fs::remove_file(path).ok();
+        fs::remove_file("test_cli_repl.txt.lock").ok();
+    }
 }
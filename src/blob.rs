@@ -0,0 +1,443 @@
+//! Cache de gros fichiers, écrits et relus en flux
+//!
+//! ```text
+//! cache/
+//! ├── index        - enregistrements du journal: clé -> « numéro taille »
+//! └── blobs/
+//!     ├── 0        - octets de la valeur, tels qu'écrits
+//!     ├── 1
+//!     └── ...
+//! ```
+//!
+//! Les valeurs ne passent jamais en entier par la mémoire: elles sont
+//! copiées par morceaux depuis un `Read` (ou vers un `Write`) et seuls leurs
+//! chemins et tailles sont gardés par le cache. La limite porte sur la
+//! somme des tailles, pas sur le nombre d'entrées.
+
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::cache::LruCache;
+use crate::codec::Persistable;
+use crate::error::CacheError;
+use crate::journal::{self, Record};
+use crate::persistent::{sync_parent, write_atomic};
+use crate::report::Recovery;
+
+/// Fichier d'une entrée
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Blob {
+    id: u64,
+    size: u64,
+}
+
+/// Cache LRU de valeurs stockées chacune dans son fichier, bornée en octets
+///
+/// `put_reader` copie un flux dans un fichier temporaire puis le renomme:
+/// une écriture interrompue ne laisse jamais d'entrée à moitié écrite.
+/// `writer` donne un `Write` pour produire la valeur directement. `get`
+/// ouvre le fichier de l'entrée, à lire comme n'importe quel `File`.
+///
+/// L'index est réécrit à chaque insertion ou suppression; l'ordre LRU des
+/// lectures n'est sauvegardé qu'à la destruction ou par `flush`.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::BlobCache;
+/// use std::fs::File;
+/// use std::io;
+///
+/// let mut cache: BlobCache = BlobCache::open("artefacts", 10 << 30).unwrap();
+/// let size = cache
+///     .put_reader("build-42".to_string(), File::open("build.tar").unwrap())
+///     .unwrap();
+///
+/// if let Some(mut file) = cache.get(&"build-42".to_string()).unwrap() {
+///     io::copy(&mut file, &mut io::stdout()).unwrap();
+/// }
+/// # let _ = size;
+/// ```
+pub struct BlobCache<K = String>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    root: PathBuf,
+    entries: LruCache<K, Blob>,
+    max_bytes: u64,
+    total: u64,
+    next_id: u64,
+}
+
+impl<K> BlobCache<K>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    /// Ouvre (ou crée) le répertoire `root`, limité à `max_bytes` octets
+    ///
+    /// Les entrées dont le fichier a disparu sont oubliées, les fichiers
+    /// qui ne figurent pas dans l'index sont supprimés.
+    pub fn open(root: impl AsRef<Path>, max_bytes: u64) -> Result<Self, CacheError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join("blobs"))?;
+
+        let mut cache = Self {
+            root,
+            entries: LruCache::new(usize::MAX),
+            max_bytes,
+            total: 0,
+            next_id: 0,
+        };
+        let bytes = match fs::read(cache.index_path()) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        for record in journal::decode(&bytes, &mut Recovery::strict())? {
+            let Record::Put(key, blob) = record else {
+                continue;
+            };
+            let key =
+                K::from_bytes(&key).ok_or_else(|| CacheError::corrupted("index: clé illisible"))?;
+            let blob = parse_blob(&blob)?;
+            cache.next_id = cache.next_id.max(blob.id + 1);
+            if cache.blob_path(blob.id).is_file() {
+                cache.total += blob.size;
+                cache.entries.put(key, blob);
+            }
+        }
+        cache.remove_orphans()?;
+        cache.evict(None)?;
+        Ok(cache)
+    }
+
+    /// Copie `reader` jusqu'à sa fin dans le fichier de `key` et renvoie le
+    /// nombre d'octets écrits
+    ///
+    /// Une valeur plus grande que la limite est refusée
+    /// (`CacheError::FileTooLarge`) sans toucher à l'entrée existante.
+    pub fn put_reader<R: Read>(&mut self, key: K, mut reader: R) -> Result<u64, CacheError> {
+        let mut writer = self.writer(key)?;
+        io::copy(&mut reader, &mut writer)?;
+        writer.finish()
+    }
+
+    /// `Write` vers le fichier de `key`; la valeur ne remplace l'ancienne
+    /// qu'à l'appel de `BlobWriter::finish`, et est abandonnée sinon
+    pub fn writer(&mut self, key: K) -> Result<BlobWriter<'_, K>, CacheError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let tmp = self.root.join("blobs").join(format!("{}.tmp", id));
+        let file = File::create(&tmp)?;
+        Ok(BlobWriter {
+            cache: self,
+            key: Some(key),
+            id,
+            tmp,
+            file: BufWriter::new(file),
+            written: 0,
+        })
+    }
+
+    /// Fichier de l'entrée, ouvert en lecture; marque l'entrée récente
+    pub fn get(&mut self, key: &K) -> Result<Option<File>, CacheError> {
+        let Some(blob) = self.entries.get(key).copied() else {
+            return Ok(None);
+        };
+        match File::open(self.blob_path(blob.id)) {
+            Ok(file) => Ok(Some(file)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                // Supprimé hors du cache: l'entrée n'existe plus
+                self.forget(key)?;
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Copie la valeur dans `out` et renvoie sa taille, `None` si absente
+    pub fn copy_to<W: Write>(&mut self, key: &K, out: &mut W) -> Result<Option<u64>, CacheError> {
+        match self.get(key)? {
+            Some(mut file) => Ok(Some(io::copy(&mut file, out)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Chemin du fichier de l'entrée, sans changer l'ordre LRU
+    pub fn path(&self, key: &K) -> Option<PathBuf> {
+        self.entries.peek(key).map(|blob| self.blob_path(blob.id))
+    }
+
+    /// Taille de la valeur en octets
+    pub fn size(&self, key: &K) -> Option<u64> {
+        self.entries.peek(key).map(|blob| blob.size)
+    }
+
+    /// Supprime l'entrée et son fichier; `true` si elle existait
+    pub fn remove(&mut self, key: &K) -> Result<bool, CacheError> {
+        let removed = self.forget(key)?;
+        if removed {
+            self.write_index()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Somme des tailles des valeurs
+    pub fn total_size(&self) -> u64 {
+        self.total
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Réécrit l'index, avec l'ordre LRU courant
+    pub fn flush(&mut self) -> Result<(), CacheError> {
+        self.write_index()
+    }
+
+    fn commit(&mut self, key: K, id: u64, tmp: &Path, size: u64) -> Result<u64, CacheError> {
+        if size > self.max_bytes {
+            let _ = fs::remove_file(tmp);
+            return Err(CacheError::FileTooLarge {
+                path: self.blob_path(id),
+                size,
+                limit: self.max_bytes,
+            });
+        }
+        let path = self.blob_path(id);
+        fs::rename(tmp, &path)?;
+        sync_parent(&path)?;
+        self.forget(&key)?;
+        self.total += size;
+        self.entries.put(key.clone(), Blob { id, size });
+        self.evict(Some(&key))?;
+        self.write_index()?;
+        Ok(size)
+    }
+
+    /// Retire l'entrée de l'index et supprime son fichier
+    fn forget(&mut self, key: &K) -> Result<bool, CacheError> {
+        let Some(blob) = self.entries.remove(key) else {
+            return Ok(false);
+        };
+        self.total -= blob.size;
+        match fs::remove_file(self.blob_path(blob.id)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(true),
+        }
+    }
+
+    /// Supprime les entrées les moins récentes tant que la limite est dépassée
+    fn evict(&mut self, keep: Option<&K>) -> Result<(), CacheError> {
+        while self.total > self.max_bytes {
+            let lru = self
+                .entries
+                .iter()
+                .map(|(key, _)| key)
+                .find(|key| Some(*key) != keep)
+                .cloned();
+            match lru {
+                Some(key) => self.forget(&key)?,
+                None => break,
+            };
+        }
+        Ok(())
+    }
+
+    fn write_index(&self) -> Result<(), CacheError> {
+        let mut bytes = Vec::new();
+        for (key, blob) in self.entries.iter() {
+            let value = format!("{} {}", blob.id, blob.size);
+            journal::encode(&Record::Put(key.to_bytes(), value.into_bytes()), &mut bytes);
+        }
+        write_atomic(&self.index_path(), &bytes, false, true)?;
+        Ok(())
+    }
+
+    fn remove_orphans(&self) -> io::Result<()> {
+        let live: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(_, blob)| blob.id.to_string())
+            .collect();
+        for file in fs::read_dir(self.root.join("blobs"))? {
+            let file = file?;
+            if !file
+                .file_name()
+                .to_str()
+                .is_some_and(|name| live.iter().any(|id| id == name))
+            {
+                fs::remove_file(file.path())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.root.join("index")
+    }
+
+    fn blob_path(&self, id: u64) -> PathBuf {
+        self.root.join("blobs").join(id.to_string())
+    }
+}
+
+impl<K> Drop for BlobCache<K>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        let _ = self.write_index();
+    }
+}
+
+fn parse_blob(bytes: &[u8]) -> Result<Blob, CacheError> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|text| text.split_once(' '))
+        .and_then(|(id, size)| {
+            Some(Blob {
+                id: id.parse().ok()?,
+                size: size.parse().ok()?,
+            })
+        })
+        .ok_or_else(|| CacheError::corrupted("index: fichier de valeur invalide"))
+}
+
+/// Écriture en cours d'une valeur de `BlobCache`
+///
+/// Les octets vont dans un fichier temporaire; `finish` le met en place.
+/// Abandonné sans `finish`, le fichier temporaire est supprimé.
+pub struct BlobWriter<'a, K>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    cache: &'a mut BlobCache<K>,
+    key: Option<K>,
+    id: u64,
+    tmp: PathBuf,
+    file: BufWriter<File>,
+    written: u64,
+}
+
+impl<K> BlobWriter<'_, K>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    /// Octets écrits jusqu'ici
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    /// Synchronise le fichier et remplace la valeur de la clé; renvoie sa
+    /// taille
+    pub fn finish(mut self) -> Result<u64, CacheError> {
+        self.file.flush()?;
+        self.file.get_ref().sync_all()?;
+        let key = self.key.take().expect("écriture déjà terminée");
+        let tmp = self.tmp.clone();
+        self.cache.commit(key, self.id, &tmp, self.written)
+    }
+}
+
+impl<K> Write for BlobWriter<'_, K>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl<K> Drop for BlobWriter<'_, K>
+where
+    K: Persistable + Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        if self.key.is_some() {
+            let _ = fs::remove_file(&self.tmp);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_stream_evict_and_reopen() {
+        let root = "test_blobs";
+        let _ = fs::remove_dir_all(root);
+        let key = |name: &str| name.to_string();
+
+        let mut cache: BlobCache = BlobCache::open(root, 10).unwrap();
+        assert_eq!(cache.put_reader(key("a"), Cursor::new(b"1234")).unwrap(), 4);
+        let mut writer = cache.writer(key("b")).unwrap();
+        writer.write_all(b"5678").unwrap();
+        assert_eq!(writer.finish().unwrap(), 4);
+
+        // Une écriture abandonnée ne laisse rien
+        let mut writer = cache.writer(key("c")).unwrap();
+        writer.write_all(b"x").unwrap();
+        drop(writer);
+        assert!(!cache.contains_key(&key("c")));
+
+        let mut out = Vec::new();
+        assert_eq!(cache.copy_to(&key("a"), &mut out).unwrap(), Some(4));
+        assert_eq!(out, b"1234");
+
+        // "b" est la moins récente et sort pour faire de la place
+        cache.put_reader(key("c"), Cursor::new(b"abcde")).unwrap();
+        assert!(!cache.contains_key(&key("b")));
+        assert_eq!(cache.total_size(), 9);
+        assert!(matches!(
+            cache.put_reader(key("d"), Cursor::new(vec![0; 11])),
+            Err(CacheError::FileTooLarge { size: 11, .. })
+        ));
+
+        let path = cache.path(&key("c")).unwrap();
+        drop(cache);
+        assert_eq!(fs::read(&path).unwrap(), b"abcde");
+
+        let mut reopened: BlobCache = BlobCache::open(root, 10).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.size(&key("a")), Some(4));
+        let mut out = String::new();
+        reopened
+            .get(&key("c"))
+            .unwrap()
+            .unwrap()
+            .read_to_string(&mut out)
+            .unwrap();
+        assert_eq!(out, "abcde");
+        assert_eq!(
+            fs::read_dir(Path::new(root).join("blobs")).unwrap().count(),
+            2
+        );
+
+        drop(reopened);
+        fs::remove_dir_all(root).ok();
+    }
+}
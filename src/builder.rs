@@ -0,0 +1,84 @@
+use std::hash::Hash;
+
+use crate::loader::{CacheLoader, LoadingCache};
+use crate::sampled::{SampledLruCache, DEFAULT_SAMPLE_SIZE};
+use crate::sharded::ShardedLruCache;
+use crate::sync::SyncLruCache;
+
+/// Configuration commune des caches concurrents
+///
+/// La méthode `build_*` choisit l'implémentation:
+///
+/// - `build_sync`: LRU exact sous un seul verrou
+/// - `build_sharded`: LRU exact par segment, pour les écritures concurrentes
+/// - `build_sampled`: LRU approché, lectures sans verrou exclusif
+/// - `build_loading`: LRU exact qui charge lui-même les clés absentes
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::CacheBuilder;
+///
+/// let cache = CacheBuilder::new(10_000).sample_size(16).build_sampled();
+/// cache.put(1, "un");
+///
+/// assert_eq!(cache.get(&1), Some("un"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct CacheBuilder {
+    capacity: usize,
+    shards: usize,
+    sample_size: usize,
+}
+
+impl CacheBuilder {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            shards: 16,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+        }
+    }
+
+    /// Nombre de segments pour `build_sharded` (16 par défaut)
+    pub fn shards(mut self, shards: usize) -> Self {
+        self.shards = shards;
+        self
+    }
+
+    /// Taille de l'échantillon d'éviction pour `build_sampled`
+    pub fn sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size;
+        self
+    }
+
+    pub fn build_sync<K, V>(&self) -> SyncLruCache<K, V>
+    where
+        K: Hash + Eq + Clone,
+    {
+        SyncLruCache::new(self.capacity)
+    }
+
+    pub fn build_sharded<K, V>(&self) -> ShardedLruCache<K, V>
+    where
+        K: Hash + Eq + Clone,
+    {
+        ShardedLruCache::new(self.capacity, self.shards)
+    }
+
+    pub fn build_sampled<K, V>(&self) -> SampledLruCache<K, V>
+    where
+        K: Hash + Eq + Clone,
+    {
+        SampledLruCache::new(self.capacity, self.sample_size)
+    }
+
+    pub fn build_loading<K, V, L>(&self, loader: L) -> LoadingCache<K, V, L>
+    where
+        K: Hash + Eq + Clone,
+        V: Clone,
+        L: CacheLoader<K, V>,
+    {
+        LoadingCache::new(self.capacity, loader)
+    }
+}
@@ -1,5 +1,62 @@
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::hash::Hash;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::snapshot::{CacheSnapshot, EntryState, SnapshotState};
+use crate::stats::CacheStats;
+
+/// Métadonnées d'une entrée, voir `LruCache::get_with_meta`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryInfo {
+    /// Version de la valeur, à comparer comme un ETag
+    pub version: u64,
+    /// `get` réussis depuis l'insertion
+    pub hits: u64,
+    /// Temps restant avant expiration
+    pub ttl: Option<Duration>,
+}
+
+/// `put_if_version` refusé: l'entrée n'est plus à la version attendue
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionConflict<V> {
+    /// Version courante (`None`: clé absente)
+    pub current: Option<u64>,
+    /// Valeur refusée, rendue à l'appelant
+    pub rejected: V,
+}
+
+impl<V> fmt::Display for VersionConflict<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.current {
+            Some(version) => write!(f, "entrée à la version {}", version),
+            None => write!(f, "entrée absente"),
+        }
+    }
+}
+
+impl<V: fmt::Debug> std::error::Error for VersionConflict<V> {}
+
+/// Conversion refusée: plus de clés distinctes que la capacité demandée
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityExceeded {
+    pub capacity: usize,
+    /// Clés distinctes fournies
+    pub len: usize,
+}
+
+impl fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entrées pour une capacité de {}",
+            self.len, self.capacity
+        )
+    }
+}
+
+impl std::error::Error for CapacityExceeded {}
 
 /// Cache LRU générique K → V
 ///
@@ -29,6 +86,16 @@ where
     capacity: usize,
     items: HashMap<K, V>,
     usage: Vec<K>,
+    expiry: HashMap<K, Instant>,
+    /// Hits de chaque entrée depuis son insertion
+    hit_counts: HashMap<K, u64>,
+    /// Étiquettes de chaque entrée, et entrées de chaque étiquette
+    tags: HashMap<K, Vec<String>>,
+    tagged: HashMap<String, HashSet<K>>,
+    /// Version de chaque entrée, tirée d'un compteur propre au cache
+    versions: HashMap<K, u64>,
+    next_version: u64,
+    stats: CacheStats,
 }
 
 impl<K, V> LruCache<K, V>
@@ -50,6 +117,13 @@ where
             capacity,
             items: HashMap::new(),
             usage: Vec::new(),
+            expiry: HashMap::new(),
+            hit_counts: HashMap::new(),
+            tags: HashMap::new(),
+            tagged: HashMap::new(),
+            versions: HashMap::new(),
+            next_version: 1,
+            stats: CacheStats::default(),
         }
     }
 
@@ -71,6 +145,10 @@ where
             return None;
         }
 
+        self.expiry.remove(&key);
+        self.untag(&key);
+        self.bump_version(&key);
+
         // Mise à jour si existe
         if let Some(old_value) = self.items.insert(key.clone(), value) {
             self.move_to_recent(&key);
@@ -79,16 +157,314 @@ where
 
         // Éviction si plein
         if self.items.len() > self.capacity {
-            if let Some(lru_key) = self.usage.first().cloned() {
-                self.items.remove(&lru_key);
-                self.usage.retain(|k| k != &lru_key);
-            }
+            self.evict_lru();
         }
 
+        self.hit_counts.insert(key.clone(), 0);
         self.usage.push(key);
         None
     }
 
+    /// Insère un lot d'entrées et renvoie celles évincées
+    ///
+    /// Équivaut à un `put` par paire, mais l'éviction n'a lieu qu'une fois,
+    /// à la fin: une entrée du lot peut donc être évincée si le lot dépasse
+    /// la capacité. Les valeurs remplacées ne sont pas renvoyées.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    ///
+    /// let evicted = cache.put_many([(3, "c"), (1, "A"), (4, "d")]);
+    /// assert_eq!(evicted, vec![(2, "b")]);
+    /// assert_eq!(cache.get(&1), Some(&"A"));
+    /// ```
+    pub fn put_many<I>(&mut self, entries: I) -> Vec<(K, V)>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        if self.capacity == 0 {
+            return Vec::new();
+        }
+
+        for (key, value) in entries {
+            self.expiry.remove(&key);
+            self.untag(&key);
+            self.bump_version(&key);
+            if self.items.insert(key.clone(), value).is_some() {
+                self.move_to_recent(&key);
+            } else {
+                self.hit_counts.insert(key.clone(), 0);
+                self.usage.push(key);
+            }
+        }
+
+        let excess = self.items.len().saturating_sub(self.capacity);
+        let lru: Vec<K> = self.usage.drain(..excess).collect();
+        self.stats.evictions += excess as u64;
+        lru.into_iter()
+            .filter_map(|key| {
+                self.expiry.remove(&key);
+                self.hit_counts.remove(&key);
+                self.untag(&key);
+                self.versions.remove(&key);
+                let value = self.items.remove(&key)?;
+                Some((key, value))
+            })
+            .collect()
+    }
+
+    /// Insère une entrée qui expire après `ttl`
+    ///
+    /// Une entrée expirée n'est plus visible par `get`, `peek` ou
+    /// `contains_key`; elle occupe sa place jusqu'au prochain `get` sur la clé
+    /// ou jusqu'à `purge_expired`.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put_with_ttl("session", 42, Duration::ZERO);
+    ///
+    /// assert_eq!(cache.get(&"session"), None);
+    /// ```
+    pub fn put_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let old_value = self.put(key.clone(), value);
+        // Une durée trop grande pour l'horloge vaut une entrée permanente
+        let deadline = Instant::now().checked_add(ttl);
+        if let (true, Some(deadline)) = (self.items.contains_key(&key), deadline) {
+            self.expiry.insert(key, deadline);
+        }
+        old_value
+    }
+
+    /// Insère une entrée portant des étiquettes, pour la retirer avec
+    /// toutes celles qui partagent une étiquette (`invalidate_tag`)
+    ///
+    /// Les étiquettes remplacent celles de l'ancienne valeur; un `put`
+    /// simple les retire. Elles ne sont pas sauvegardées.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put_with_tags("profil:42", "Ada", ["user:42"]);
+    /// cache.put_with_tags("commandes:42", "[]", ["user:42", "tenant:7"]);
+    /// cache.put("accueil", "...");
+    ///
+    /// assert_eq!(cache.invalidate_tag("user:42").len(), 2);
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn put_with_tags<I, T>(&mut self, key: K, value: V, tags: I) -> Option<V>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let old_value = self.put(key.clone(), value);
+        if self.items.contains_key(&key) {
+            let mut tags: Vec<String> = tags.into_iter().map(Into::into).collect();
+            tags.sort();
+            tags.dedup();
+            for tag in &tags {
+                self.tagged
+                    .entry(tag.clone())
+                    .or_default()
+                    .insert(key.clone());
+            }
+            self.tags.insert(key, tags);
+        }
+        old_value
+    }
+
+    /// Retire toutes les entrées portant `tag` et les renvoie, de la moins
+    /// à la plus récente
+    pub fn invalidate_tag(&mut self, tag: &str) -> Vec<(K, V)> {
+        let Some(tagged) = self.tagged.remove(tag) else {
+            return Vec::new();
+        };
+        let keys: Vec<K> = self
+            .usage
+            .iter()
+            .filter(|key| tagged.contains(*key))
+            .cloned()
+            .collect();
+        self.remove_many(keys)
+    }
+
+    /// Étiquettes d'une entrée (vide si elle n'en porte pas)
+    pub fn tags<Q>(&self, key: &Q) -> &[String]
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.tags.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Retire une entrée et retourne sa valeur
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put(1, "a");
+    ///
+    /// assert_eq!(cache.remove(&1), Some("a"));
+    /// assert_eq!(cache.remove(&1), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key)
+    }
+
+    /// Retire un lot de clés et renvoie les entrées retirées
+    ///
+    /// Équivaut à un `remove` par clé, mais l'ordre LRU n'est parcouru
+    /// qu'une fois.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    ///
+    /// assert_eq!(cache.remove_many([3, 4, 1]), vec![(3, "c"), (1, "a")]);
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn remove_many<I>(&mut self, keys: I) -> Vec<(K, V)>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let mut removed = Vec::new();
+        for key in keys {
+            if let Some(value) = self.items.remove(&key) {
+                self.expiry.remove(&key);
+                self.hit_counts.remove(&key);
+                self.untag(&key);
+                self.versions.remove(&key);
+                removed.push((key, value));
+            }
+        }
+        if !removed.is_empty() {
+            let keys: HashSet<&K> = removed.iter().map(|(key, _)| key).collect();
+            self.usage.retain(|key| !keys.contains(key));
+        }
+        removed
+    }
+
+    /// Retire toutes les entrées, sans les compter comme évincées
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.usage.clear();
+        self.expiry.clear();
+        self.hit_counts.clear();
+        self.tags.clear();
+        self.tagged.clear();
+        self.versions.clear();
+    }
+
+    /// Retire toutes les entrées expirées, retourne leur nombre
+    pub fn purge_expired(&mut self) -> usize {
+        let now = Instant::now();
+        let expired: Vec<K> = self
+            .expiry
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(k, _)| k.clone())
+            .collect();
+
+        for key in &expired {
+            self.remove_entry(key);
+        }
+        expired.len()
+    }
+
+    /// Évince les entrées les moins récentes jusqu'à n'en garder que `len`
+    ///
+    /// Retourne le nombre d'entrées évincées.
+    pub fn shrink_to(&mut self, len: usize) -> usize {
+        let mut evicted = 0;
+        while self.items.len() > len && self.evict_lru() {
+            evicted += 1;
+        }
+        evicted
+    }
+
+    fn evict_lru(&mut self) -> bool {
+        match self.usage.first().cloned() {
+            Some(lru_key) => {
+                self.remove_entry(&lru_key);
+                self.stats.evictions += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let value = self.items.remove(key)?;
+        self.usage.retain(|k| k.borrow() != key);
+        self.expiry.remove(key);
+        self.hit_counts.remove(key);
+        self.untag(key);
+        self.versions.remove(key);
+        Some(value)
+    }
+
+    fn bump_version(&mut self, key: &K) -> u64 {
+        let version = self.next_version;
+        self.next_version += 1;
+        self.versions.insert(key.clone(), version);
+        version
+    }
+
+    fn untag<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        for tag in self.tags.remove(key).unwrap_or_default() {
+            if let Some(keys) = self.tagged.get_mut(&tag) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.tagged.remove(&tag);
+                }
+            }
+        }
+    }
+
+    fn is_expired<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.expiry
+            .get(key)
+            .is_some_and(|deadline| *deadline <= Instant::now())
+    }
+
     /// Récupère une valeur et marque la clé comme récemment utilisée
     ///
     /// # Exemples
@@ -102,18 +478,276 @@ where
     /// assert_eq!(cache.get(&"key".to_string()), Some(&"value".to_string()));
     /// assert_eq!(cache.get(&"missing".to_string()), None);
     /// ```
-    pub fn get(&mut self, key: &K) -> Option<&V> {
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.is_expired(key) {
+            self.remove_entry(key);
+        }
+
         if self.items.contains_key(key) {
+            self.stats.hits += 1;
+            if let Some(count) = self.hit_counts.get_mut(key) {
+                *count += 1;
+            }
             self.move_to_recent(key);
             self.items.get(key)
         } else {
+            self.stats.misses += 1;
             None
         }
     }
 
-    fn move_to_recent(&mut self, key: &K) {
-        self.usage.retain(|k| k != key);
-        self.usage.push(key.clone());
+    /// Récupère plusieurs valeurs, dans l'ordre des clés demandées
+    ///
+    /// Équivaut à un `get` par clé, mais l'ordre LRU n'est parcouru qu'une
+    /// fois: les clés trouvées passent ensemble en tête, dans l'ordre de
+    /// leur dernière demande; les clés absentes ne changent rien à l'ordre.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.put(3, "c");
+    ///
+    /// assert_eq!(cache.get_many([2, 4, 1]), vec![Some(&"b"), None, Some(&"a")]);
+    /// cache.put(5, "e"); // 3 est maintenant le moins récent
+    /// assert!(!cache.contains_key(&3));
+    /// ```
+    pub fn get_many<I>(&mut self, keys: I) -> Vec<Option<&V>>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        for key in &keys {
+            if self.is_expired(key) {
+                self.remove_entry(key);
+            }
+            if self.items.contains_key(key) {
+                self.stats.hits += 1;
+                if let Some(count) = self.hit_counts.get_mut(key) {
+                    *count += 1;
+                }
+            } else {
+                self.stats.misses += 1;
+            }
+        }
+
+        // Clés trouvées, dans l'ordre de leur dernière demande
+        let mut seen = HashSet::new();
+        let mut found: Vec<&K> = keys
+            .iter()
+            .rev()
+            .filter(|key| self.items.contains_key(*key) && seen.insert(*key))
+            .collect();
+        found.reverse();
+        if !found.is_empty() {
+            self.usage.retain(|key| !seen.contains(key));
+            self.usage.extend(found.into_iter().cloned());
+        }
+
+        keys.iter().map(|key| self.items.get(key)).collect()
+    }
+
+    /// Comme `get`, avec la version de l'entrée, ses hits et son TTL
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put("page", "v1");
+    /// let (_, info) = cache.get_with_meta(&"page").unwrap();
+    ///
+    /// // Modifiée entre-temps: la version lue n'est plus la bonne
+    /// cache.put("page", "v2");
+    /// assert!(cache.put_if_version("page", "v3", Some(info.version)).is_err());
+    /// ```
+    pub fn get_with_meta<Q>(&mut self, key: &Q) -> Option<(&V, EntryInfo)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key)?;
+        let info = EntryInfo {
+            version: self.versions.get(key).copied().unwrap_or(0),
+            hits: self.hit_counts.get(key).copied().unwrap_or(0),
+            ttl: self.time_to_live(key),
+        };
+        Some((self.items.get(key)?, info))
+    }
+
+    /// Version courante de l'entrée, sans modifier l'ordre LRU
+    ///
+    /// Chaque écriture donne à l'entrée une version plus grande que toutes
+    /// celles déjà attribuées par ce cache: une version n'est jamais
+    /// réutilisée, même après éviction et réinsertion de la clé.
+    pub fn version<Q>(&self, key: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        self.versions.get(key).copied()
+    }
+
+    /// Écrit seulement si l'entrée est toujours à la version `expected`
+    /// (`None`: seulement si la clé est absente); retourne la nouvelle
+    /// version
+    pub fn put_if_version(
+        &mut self,
+        key: K,
+        value: V,
+        expected: Option<u64>,
+    ) -> Result<u64, VersionConflict<V>> {
+        let current = self.version(&key);
+        if current != expected || self.capacity == 0 {
+            return Err(VersionConflict {
+                current,
+                rejected: value,
+            });
+        }
+        self.put(key.clone(), value);
+        Ok(self.versions[&key])
+    }
+
+    /// Récupère une valeur sans modifier l'ordre LRU ni les statistiques
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    ///
+    /// assert_eq!(cache.peek(&1), Some(&"a"));
+    /// cache.put(3, "c"); // 1 reste le moins récent: il est évincé
+    /// assert!(!cache.contains_key(&1));
+    /// ```
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.is_expired(key) {
+            return None;
+        }
+        self.items.get(key)
+    }
+
+    /// Indique si la clé est présente, sans modifier l'ordre LRU
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.items.contains_key(key) && !self.is_expired(key)
+    }
+
+    /// Parcourt les entrées de la moins à la plus récemment utilisée
+    ///
+    /// Les entrées expirées sont ignorées; l'ordre LRU n'est pas modifié.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "a");
+    /// cache.put(2, "b");
+    /// cache.get(&1);
+    ///
+    /// let entries: Vec<_> = cache.iter().collect();
+    /// assert_eq!(entries, vec![(&2, &"b"), (&1, &"a")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.usage
+            .iter()
+            .filter(|key| !self.is_expired(key))
+            .filter_map(|key| self.items.get(key).map(|value| (key, value)))
+    }
+
+    fn move_to_recent<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        if let Some(pos) = self.usage.iter().position(|k| k.borrow() == key) {
+            let key = self.usage.remove(pos);
+            self.usage.push(key);
+        }
+    }
+
+    /// Nombre de `get` réussis sur l'entrée depuis son insertion
+    pub fn hit_count<Q>(&self, key: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return None;
+        }
+        Some(self.hit_counts.get(key).copied().unwrap_or(0))
+    }
+
+    /// Durée de vie restante d'une entrée insérée avec `put_with_ttl`
+    pub fn time_to_live<Q>(&self, key: &Q) -> Option<Duration>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let deadline = self.expiry.get(key)?;
+        self.contains_key(key)
+            .then(|| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Change la durée de vie d'une entrée présente sans la marquer comme
+    /// récente; `None` la rend permanente
+    ///
+    /// Retourne `false` si la clé est absente ou déjà expirée.
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    /// use std::time::Duration;
+    ///
+    /// let mut cache = LruCache::new(2);
+    /// cache.put("session", 42);
+    /// assert!(cache.set_ttl(&"session", Some(Duration::from_secs(60))));
+    /// assert!(cache.time_to_live(&"session").is_some());
+    ///
+    /// cache.set_ttl(&"session", None);
+    /// assert_eq!(cache.time_to_live(&"session"), None);
+    /// ```
+    pub fn set_ttl<Q>(&mut self, key: &Q, ttl: Option<Duration>) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if !self.contains_key(key) {
+            return false;
+        }
+        match ttl.and_then(|ttl| Instant::now().checked_add(ttl)) {
+            Some(deadline) => {
+                let (key, _) = self.items.get_key_value(key).expect("clé présente");
+                self.expiry.insert(key.clone(), deadline);
+            }
+            None => {
+                self.expiry.remove(key);
+            }
+        }
+        true
     }
 
     pub fn len(&self) -> usize {
@@ -123,6 +757,428 @@ where
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
     }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Compteurs de hits, misses et évictions depuis la création
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    pub(crate) fn set_stats(&mut self, stats: CacheStats) {
+        self.stats = stats;
+    }
+
+    /// Modifie la valeur en place et la marque comme récente, sans compter
+    /// de hit; `None` si la clé est absente ou expirée
+    pub(crate) fn modify<R>(&mut self, key: &K, f: impl FnOnce(&mut V) -> R) -> Option<R> {
+        if self.is_expired(key) {
+            self.remove_entry(key);
+        }
+        let result = f(self.items.get_mut(key)?);
+        self.move_to_recent(key);
+        self.bump_version(key);
+        Some(result)
+    }
+
+    /// Clé présente, même expirée: `put` la remplace alors sans évincer
+    pub(crate) fn holds(&self, key: &K) -> bool {
+        self.items.contains_key(key)
+    }
+
+    /// Clé que `put` évincerait, même expirée (contrairement à `iter`)
+    pub(crate) fn lru_key(&self) -> Option<&K> {
+        self.usage.first()
+    }
+
+    /// Ajoute une entrée en position la plus récente
+    ///
+    /// Utilisé au chargement d'un fichier persistant: au-delà de la capacité,
+    /// les entrées les moins récentes sont écartées sans compter d'éviction.
+    pub(crate) fn restore_entry(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.items.insert(key.clone(), value).is_some() {
+            self.usage.retain(|k| k != &key);
+        }
+        self.hit_counts.insert(key.clone(), 0);
+        self.bump_version(&key);
+        self.usage.push(key);
+        self.discard_lru(self.capacity);
+    }
+
+    /// Retire les entrées les moins récentes jusqu'à n'en garder que `len`,
+    /// sans compter d'éviction
+    pub(crate) fn discard_lru(&mut self, len: usize) {
+        while self.items.len() > len {
+            let lru_key = self.usage[0].clone();
+            self.remove_entry(&lru_key);
+        }
+    }
+
+    /// Échéance (horloge système) et hits d'une entrée, pour la sauvegarde
+    ///
+    /// Une échéance que l'horloge ne sait pas représenter vaut une entrée
+    /// permanente, comme dans `put_with_ttl`.
+    pub(crate) fn entry_metadata(&self, key: &K) -> (Option<SystemTime>, u64) {
+        let expires_at = self
+            .time_to_live(key)
+            .and_then(|remaining| SystemTime::now().checked_add(remaining));
+        (expires_at, self.hit_count(key).unwrap_or(0))
+    }
+
+    /// Clés expirées mais pas encore retirées
+    pub(crate) fn expired_keys(&self) -> Vec<K> {
+        let now = Instant::now();
+        self.expiry
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Rétablit les métadonnées d'une entrée restaurée
+    pub(crate) fn restore_metadata(&mut self, key: &K, expires_at: Option<SystemTime>, hits: u64) {
+        if !self.items.contains_key(key) {
+            return;
+        }
+        if let Some(expires_at) = expires_at {
+            let remaining = expires_at
+                .duration_since(SystemTime::now())
+                .unwrap_or(Duration::ZERO);
+            if let Some(deadline) = Instant::now().checked_add(remaining) {
+                self.expiry.insert(key.clone(), deadline);
+            }
+        }
+        self.hit_counts.insert(key.clone(), hits);
+    }
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// Copie les entrées non expirées, la capacité et les statistiques
+    pub fn export(&self) -> CacheSnapshot<K, V> {
+        CacheSnapshot::new(
+            self.capacity,
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            self.stats,
+        )
+    }
+
+    /// Copie l'état complet du cache, à rétablir avec `restore`
+    ///
+    /// En plus de ce que garde `export`: les échéances, les hits, les
+    /// étiquettes et les versions de chaque entrée.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(10);
+    /// cache.put("prix", 10);
+    /// let before = cache.snapshot();
+    ///
+    /// cache.put("prix", 1000); // publication erronée
+    /// cache.restore(before);
+    /// assert_eq!(cache.get(&"prix"), Some(&10));
+    /// ```
+    pub fn snapshot(&self) -> CacheSnapshot<K, V> {
+        let live: Vec<&K> = self
+            .usage
+            .iter()
+            .filter(|key| !self.is_expired(*key))
+            .collect();
+        let entries = live
+            .iter()
+            .map(|key| ((*key).clone(), self.items[*key].clone()))
+            .collect();
+        let state = live
+            .iter()
+            .map(|key| {
+                let entry = EntryState {
+                    expires_at: self.expiry.get(*key).copied(),
+                    hits: self.hit_counts.get(*key).copied().unwrap_or(0),
+                    tags: self.tags.get(*key).cloned().unwrap_or_default(),
+                    version: self.versions.get(*key).copied().unwrap_or(0),
+                };
+                ((*key).clone(), entry)
+            })
+            .collect();
+        CacheSnapshot {
+            state: Some(SnapshotState {
+                entries: state,
+                next_version: self.next_version,
+            }),
+            ..CacheSnapshot::new(self.capacity, entries, self.stats)
+        }
+    }
+
+    /// Remplace tout le contenu par celui d'un instantané
+    ///
+    /// Au-delà de la capacité de l'instantané, les entrées les moins
+    /// récentes sont écartées. Les entrées importées reçoivent de nouvelles
+    /// versions: aucune version déjà attribuée par ce cache ne revient.
+    pub fn import(&mut self, snapshot: CacheSnapshot<K, V>) {
+        let next_version = self.next_version;
+        *self = LruCache::new(snapshot.capacity);
+        self.next_version = next_version;
+        for (key, value) in snapshot.entries {
+            self.restore_entry(key, value);
+        }
+        self.stats = snapshot.stats;
+    }
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Remplace tout le contenu par celui d'un instantané de `snapshot`
+    /// (ou de `export`, comme `import`)
+    ///
+    /// Les versions reprennent celles de l'instantané, mais les prochaines
+    /// écritures ne réutilisent aucune version déjà attribuée par ce cache.
+    /// Les échéances restent celles d'origine: le temps écoulé depuis
+    /// l'instantané compte. Une entrée ajoutée à `entries` après coup n'a
+    /// ni TTL ni étiquettes et reçoit une nouvelle version; une clé répétée
+    /// garde sa dernière valeur et sa dernière position.
+    pub fn restore(&mut self, snapshot: CacheSnapshot<K, V>) {
+        let next_version = self.next_version;
+        *self = LruCache::new(snapshot.capacity);
+        self.stats = snapshot.stats;
+        let Some(state) = snapshot.state else {
+            for (key, value) in snapshot.entries {
+                self.restore_entry(key, value);
+            }
+            self.next_version = self.next_version.max(next_version);
+            return;
+        };
+
+        self.next_version = next_version.max(state.next_version);
+        let metadata: HashMap<K, EntryState> = state.entries.into_iter().collect();
+        for (key, value) in snapshot.entries {
+            self.remove_entry(&key);
+            match metadata.get(&key) {
+                Some(entry) => {
+                    if let Some(deadline) = entry.expires_at {
+                        self.expiry.insert(key.clone(), deadline);
+                    }
+                    for tag in &entry.tags {
+                        self.tagged
+                            .entry(tag.clone())
+                            .or_default()
+                            .insert(key.clone());
+                    }
+                    if !entry.tags.is_empty() {
+                        self.tags.insert(key.clone(), entry.tags.clone());
+                    }
+                    self.hit_counts.insert(key.clone(), entry.hits);
+                    self.versions.insert(key.clone(), entry.version);
+                }
+                None => {
+                    self.hit_counts.insert(key.clone(), 0);
+                    self.bump_version(&key);
+                }
+            }
+            self.items.insert(key.clone(), value);
+            self.usage.push(key);
+        }
+        self.discard_lru(self.capacity);
+    }
+}
+
+/// Entrées à plusieurs valeurs: les derniers éléments de chaque clé
+impl<K, T> LruCache<K, VecDeque<T>>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Ajoute `item` à la liste de `key`, en retirant les plus anciens
+    /// au-delà de `max_items`; retourne la longueur de la liste
+    ///
+    /// L'entrée entière compte comme une seule dans l'ordre LRU et la
+    /// capacité. Une clé absente est insérée comme par `put`; une entrée
+    /// existante garde son TTL.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut events: LruCache<u32, VecDeque<&str>> = LruCache::new(1000);
+    /// for event in ["connexion", "achat", "déconnexion"] {
+    ///     events.append(42, event, 2);
+    /// }
+    ///
+    /// assert_eq!(events.peek(&42), Some(&VecDeque::from(["achat", "déconnexion"])));
+    /// ```
+    pub fn append(&mut self, key: K, item: T, max_items: usize) -> usize {
+        if self.capacity == 0 || max_items == 0 {
+            return 0;
+        }
+        let mut item = Some(item);
+        let appended = self.modify(&key, |items| {
+            items.extend(item.take());
+            items.drain(..items.len().saturating_sub(max_items));
+            items.len()
+        });
+        match (appended, item) {
+            (Some(len), _) => len,
+            (None, item) => {
+                self.put(key, item.into_iter().collect());
+                1
+            }
+        }
+    }
+}
+
+/// Opérations sur les clés textuelles partageant un préfixe
+/// (`"session:"`, `"user:42:"`...)
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone + AsRef<str>,
+{
+    /// Entrées dont la clé commence par `prefix`, de la moins à la plus
+    /// récente, sans modifier l'ordre LRU
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    ///
+    /// let mut cache = LruCache::new(3);
+    /// cache.put("session:a", 1);
+    /// cache.put("user:a", 2);
+    /// cache.put("session:b", 3);
+    ///
+    /// let sessions: Vec<_> = cache.iter_prefix("session:").map(|(_, v)| *v).collect();
+    /// assert_eq!(sessions, vec![1, 3]);
+    ///
+    /// assert_eq!(cache.remove_prefix("session:").len(), 2);
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn iter_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a K, &'a V)> {
+        self.iter()
+            .filter(move |(key, _)| key.as_ref().starts_with(prefix))
+    }
+
+    /// Retire les entrées dont la clé commence par `prefix` et les renvoie
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(K, V)> {
+        let keys: Vec<K> = self
+            .usage
+            .iter()
+            .filter(|key| key.as_ref().starts_with(prefix))
+            .cloned()
+            .collect();
+        self.remove_many(keys)
+    }
+}
+
+impl<K, V> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Cache de capacité `capacity` rempli dans l'ordre de `entries`, sans
+    /// éviction
+    ///
+    /// Le dernier élément devient le plus récent; une clé répétée garde sa
+    /// dernière valeur et la position de sa dernière occurrence. Échoue
+    /// plutôt que d'écarter des entrées si les clés distinctes dépassent
+    /// la capacité.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LruCache;
+    /// use std::collections::HashMap;
+    ///
+    /// let map = HashMap::from([("a", 1), ("b", 2)]);
+    /// let cache = LruCache::try_from_iter(10, map).unwrap();
+    /// assert_eq!(cache.len(), 2);
+    ///
+    /// assert!(LruCache::try_from_iter(1, [("a", 1), ("b", 2)]).is_err());
+    /// ```
+    pub fn try_from_iter<I>(capacity: usize, entries: I) -> Result<Self, CapacityExceeded>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut cache = LruCache::new(usize::MAX);
+        for (key, value) in entries {
+            cache.restore_entry(key, value);
+        }
+        if cache.len() > capacity {
+            return Err(CapacityExceeded {
+                capacity,
+                len: cache.len(),
+            });
+        }
+        cache.capacity = capacity;
+        Ok(cache)
+    }
+
+    /// Entrées non expirées, de la moins à la plus récemment utilisée
+    fn into_entries(mut self) -> impl Iterator<Item = (K, V)> {
+        let now = Instant::now();
+        let expiry = self.expiry;
+        self.usage.into_iter().filter_map(move |key| {
+            let value = self.items.remove(&key)?;
+            let expired = expiry.get(&key).is_some_and(|deadline| *deadline <= now);
+            (!expired).then_some((key, value))
+        })
+    }
+}
+
+/// Voir `LruCache::try_from_iter`: le dernier élément devient le plus récent
+///
+/// ```
+/// use lru_cache::LruCache;
+///
+/// let cache = LruCache::try_from((2, vec![(1, "a"), (2, "b")])).unwrap();
+/// assert_eq!(Vec::from(cache), vec![(1, "a"), (2, "b")]);
+/// ```
+impl<K, V> TryFrom<(usize, Vec<(K, V)>)> for LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    type Error = CapacityExceeded;
+
+    fn try_from((capacity, entries): (usize, Vec<(K, V)>)) -> Result<Self, Self::Error> {
+        LruCache::try_from_iter(capacity, entries)
+    }
+}
+
+/// Entrées non expirées, de la moins à la plus récemment utilisée (l'ordre
+/// de `iter`)
+impl<K, V> From<LruCache<K, V>> for Vec<(K, V)>
+where
+    K: Hash + Eq + Clone,
+{
+    fn from(cache: LruCache<K, V>) -> Self {
+        cache.into_entries().collect()
+    }
+}
+
+/// Entrées non expirées; l'ordre LRU est perdu
+impl<K, V> From<LruCache<K, V>> for HashMap<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn from(cache: LruCache<K, V>) -> Self {
+        cache.into_entries().collect()
+    }
 }
 
 #[cfg(test)]
@@ -165,4 +1221,282 @@ mod tests {
         assert_eq!(cache.get(&1), None);
         assert_eq!(cache.get(&2), Some(&"b"));
     }
+
+    #[test]
+    fn test_stats() {
+        let mut cache = LruCache::new(2);
+        cache.put(1, "a");
+        cache.get(&1);
+        cache.get(&5);
+        cache.put(2, "b");
+        cache.put(3, "c"); // évince 1
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.evictions, 1);
+
+        cache.reset_stats();
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+
+    #[test]
+    fn test_ttl_and_purge() {
+        let mut cache = LruCache::new(3);
+        cache.put_with_ttl(1, "a", Duration::ZERO);
+        cache.put_with_ttl(2, "b", Duration::from_secs(60));
+        cache.put(3, "c");
+
+        assert!(!cache.contains_key(&1));
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.purge_expired(), 1);
+        assert_eq!(cache.len(), 2);
+
+        // Un put sans TTL retire l'expiration
+        cache.put_with_ttl(3, "c", Duration::ZERO);
+        cache.put(3, "c");
+        assert_eq!(cache.get(&3), Some(&"c"));
+
+        // Une durée hors de portée de l'horloge ne périme jamais
+        cache.put_with_ttl(4, "d", Duration::MAX);
+        assert_eq!(cache.time_to_live(&4), None);
+        assert!(cache.set_ttl(&3, Some(Duration::MAX)));
+        assert_eq!(cache.time_to_live(&3), None);
+    }
+
+    #[test]
+    fn test_entry_metadata() {
+        let mut cache = LruCache::new(2);
+        cache.put_with_ttl("a", 1, Duration::from_secs(60));
+        cache.get(&"a");
+        cache.get(&"a");
+
+        assert_eq!(cache.hit_count(&"a"), Some(2));
+        assert!(cache.time_to_live(&"a").unwrap() > Duration::from_secs(59));
+
+        cache.put("a", 2); // la mise à jour garde les hits, pas le TTL
+        assert_eq!(cache.hit_count(&"a"), Some(2));
+        assert_eq!(cache.time_to_live(&"a"), None);
+        assert_eq!(cache.hit_count(&"b"), None);
+    }
+
+    #[test]
+    fn test_put_many() {
+        let mut cache = LruCache::new(3);
+        cache.put_with_ttl(1, "a", Duration::ZERO);
+        cache.put(2, "b");
+
+        let evicted = cache.put_many([(1, "A"), (3, "c"), (4, "d"), (5, "e")]);
+        assert_eq!(evicted, vec![(2, "b"), (1, "A")]);
+        let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![3, 4, 5]);
+        assert_eq!(cache.stats().evictions, 2);
+        assert_eq!(cache.hit_count(&3), Some(0));
+    }
+
+    #[test]
+    fn test_remove_many() {
+        let mut cache = LruCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i);
+        }
+        cache.put_with_ttl(3, 3, Duration::ZERO);
+
+        assert_eq!(cache.remove_many([1, 3, 1, 7]), vec![(1, 1), (3, 3)]);
+        let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![0, 2]);
+        assert_eq!(cache.hit_count(&1), None);
+        assert_eq!(cache.stats().evictions, 0);
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut cache = LruCache::new(3);
+        cache.put_with_tags(1, "a", ["x", "y"]);
+        cache.put_with_tags(2, "b", ["y"]);
+        cache.put_with_tags(3, "c", ["z"]);
+        cache.put(3, "C"); // un put simple retire les étiquettes
+        cache.put_with_tags(4, "d", ["x"]); // évince 1
+
+        assert_eq!(cache.tags(&1), &[] as &[String]);
+        assert_eq!(cache.invalidate_tag("x"), vec![(4, "d")]);
+        assert!(cache.invalidate_tag("z").is_empty());
+        assert_eq!(cache.invalidate_tag("y"), vec![(2, "b")]);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.tagged.is_empty());
+    }
+
+    #[test]
+    fn test_prefix() {
+        let mut cache = LruCache::new(4);
+        for key in ["a:1", "b:1", "a:2", "ab"] {
+            cache.put(key.to_string(), key.len());
+        }
+        cache.put_with_ttl("a:3".to_string(), 3, Duration::ZERO); // évince "a:1"
+
+        assert_eq!(cache.iter_prefix("a:").count(), 1);
+        let removed = cache.remove_prefix("a:");
+        assert_eq!(
+            removed,
+            vec![("a:2".to_string(), 3), ("a:3".to_string(), 3)]
+        );
+        let keys: Vec<_> = cache.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["b:1", "ab"]);
+    }
+
+    #[test]
+    fn test_versions() {
+        let mut cache = LruCache::new(1);
+        cache.put("a", 1);
+        let (_, info) = cache.get_with_meta(&"a").unwrap();
+        assert_eq!((info.version, info.hits), (1, 1));
+
+        assert_eq!(cache.put_if_version("a", 2, Some(1)), Ok(2));
+        let conflict = cache.put_if_version("a", 3, Some(1)).unwrap_err();
+        assert_eq!((conflict.current, conflict.rejected), (Some(2), 3));
+
+        // Évincée puis réinsérée: nouvelle version, jamais une ancienne
+        cache.put("b", 1);
+        assert!(cache.put_if_version("a", 4, Some(2)).is_err());
+        assert_eq!(cache.put_if_version("a", 4, None), Ok(4));
+        assert_eq!(cache.version(&"b"), None);
+    }
+
+    #[test]
+    fn test_append_bounded() {
+        let mut cache: LruCache<&str, VecDeque<i32>> = LruCache::new(2);
+        for i in 0..5 {
+            cache.append("a", i, 3);
+        }
+        cache.put("b", VecDeque::new());
+        assert_eq!(cache.append("a", 5, 3), 3);
+        assert_eq!(cache.peek(&"a"), Some(&VecDeque::from([3, 4, 5])));
+        assert_eq!(cache.version(&"a"), Some(7));
+
+        // "a" vient d'être modifiée: "b" est la moins récente
+        cache.append("c", 0, 3);
+        assert!(!cache.contains_key(&"b"));
+        assert_eq!(cache.stats().hits, 0);
+    }
+
+    #[test]
+    fn test_get_many() {
+        let mut cache = LruCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i * 10);
+        }
+        cache.put_with_ttl(9, 90, Duration::ZERO); // évince 0
+
+        assert_eq!(
+            cache.get_many([3, 1, 9, 0, 1]),
+            vec![Some(&30), Some(&10), None, None, Some(&10)]
+        );
+        let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![2, 3, 1]);
+        assert_eq!(cache.stats().hits, 3);
+        assert_eq!(cache.stats().misses, 2);
+        assert_eq!(cache.hit_count(&1), Some(2));
+    }
+
+    #[test]
+    fn test_shrink_to() {
+        let mut cache = LruCache::new(4);
+        for i in 0..4 {
+            cache.put(i, i);
+        }
+        cache.get(&0);
+
+        assert_eq!(cache.shrink_to(2), 2);
+        assert!(cache.contains_key(&0));
+        assert!(cache.contains_key(&3));
+        assert_eq!(cache.stats().evictions, 2);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut cache = LruCache::new(3);
+        cache.put_with_tags(1, "a", ["t"]);
+        cache.put_with_ttl(2, "b", Duration::from_secs(60));
+        cache.get(&1);
+        let snapshot = cache.snapshot();
+
+        cache.put(2, "B");
+        cache.put(3, "c");
+        let latest = cache.version(&3).unwrap();
+        cache.restore(snapshot.clone());
+
+        let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![2, 1]);
+        assert_eq!(cache.hit_count(&1), Some(1));
+        assert!(cache.time_to_live(&2).is_some());
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(cache.snapshot().entries, snapshot.entries);
+
+        // Aucune version déjà vue n'est réattribuée
+        cache.put(4, "d");
+        assert!(cache.version(&4).unwrap() > latest);
+        assert_eq!(cache.invalidate_tag("t"), vec![(1, "a")]);
+
+        // Entrées réordonnées, répétées ou ajoutées: chacune garde les
+        // métadonnées de sa clé
+        let mut edited = snapshot;
+        edited.entries.reverse();
+        edited.entries.push((5, "e"));
+        edited.entries.push((2, "b"));
+        cache.restore(edited);
+        assert_eq!(cache.len(), 3);
+        let keys: Vec<_> = cache.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![1, 5, 2]);
+        assert_eq!(cache.hit_count(&1), Some(1));
+        assert_eq!(cache.time_to_live(&1), None);
+        assert!(cache.time_to_live(&2).is_some());
+        assert_eq!(cache.hit_count(&5), Some(0));
+        assert_eq!(cache.invalidate_tag("t"), vec![(1, "a")]);
+    }
+
+    #[test]
+    fn test_export_import() {
+        let mut cache = LruCache::new(3);
+        cache.put(1, "a");
+        cache.put(2, "b");
+        cache.put_with_ttl(3, "c", Duration::ZERO);
+        cache.get(&1);
+
+        let snapshot = cache.export();
+        assert_eq!(snapshot.entries, vec![(2, "b"), (1, "a")]);
+
+        let mut copy = LruCache::new(10);
+        copy.import(snapshot);
+        assert_eq!(copy.capacity(), 3);
+        assert_eq!(copy.stats().hits, 1);
+        copy.put(4, "d");
+        copy.put(5, "e"); // 2 reste le moins récent
+        assert!(!copy.contains_key(&2));
+        assert!(copy.contains_key(&1));
+
+        // Une version antérieure à l'import n'est plus acceptée
+        let stale = copy.version(&1).unwrap();
+        let checkpoint = copy.export();
+        copy.import(checkpoint);
+        assert!(copy.version(&1).unwrap() > stale);
+        assert!(copy.put_if_version(1, "A", Some(stale)).is_err());
+    }
+
+    #[test]
+    fn test_std_conversions() {
+        let cache = LruCache::try_from_iter(2, [(1, "a"), (2, "b"), (1, "c")]).unwrap();
+        assert_eq!(Vec::from(cache), vec![(2, "b"), (1, "c")]);
+        assert_eq!(
+            LruCache::try_from((1, vec![(1, "a"), (2, "b")])).err(),
+            Some(CapacityExceeded {
+                capacity: 1,
+                len: 2
+            })
+        );
+
+        let mut cache = LruCache::new(3);
+        cache.put(1, "a");
+        cache.put_with_ttl(2, "b", Duration::ZERO);
+        assert_eq!(HashMap::from(cache), HashMap::from([(1, "a")]));
+    }
 }
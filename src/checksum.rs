@@ -0,0 +1,83 @@
+//! CRC-32 et enveloppe vérifiée des fichiers de cache
+
+use crate::error::CacheError;
+
+const MAGIC: &[u8; 4] = b"LRUC";
+pub(crate) const ENVELOPE_VERSION: u8 = 1;
+
+/// CRC-32 IEEE (celui de gzip et zip)
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0xEDB8_8320
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// `LRUC`, version, CRC-32 du contenu (petit-boutiste), puis le contenu
+pub(crate) fn seal(payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 9);
+    out.extend_from_slice(MAGIC);
+    out.push(ENVELOPE_VERSION);
+    out.extend_from_slice(&crc32(payload).to_le_bytes());
+    out.extend_from_slice(payload);
+    out
+}
+
+pub(crate) fn open(bytes: &[u8]) -> Result<&[u8], CacheError> {
+    if bytes.len() < 9 || &bytes[..4] != MAGIC {
+        return Err(CacheError::corrupted("en-tête de somme de contrôle absent"));
+    }
+    if bytes[4] != ENVELOPE_VERSION {
+        return Err(CacheError::VersionMismatch {
+            found: bytes[4],
+            expected: ENVELOPE_VERSION,
+        });
+    }
+
+    let expected = u32::from_le_bytes(bytes[5..9].try_into().unwrap());
+    let payload = &bytes[9..];
+    if crc32(payload) != expected {
+        return Err(CacheError::corrupted("somme de contrôle invalide"));
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let mut sealed = seal(b"contenu");
+        assert_eq!(open(&sealed).unwrap(), b"contenu");
+
+        sealed[10] ^= 1;
+        assert!(matches!(open(&sealed), Err(CacheError::Corrupted(_))));
+        sealed[4] = 9;
+        assert!(matches!(
+            open(&sealed),
+            Err(CacheError::VersionMismatch { found: 9, .. })
+        ));
+    }
+}
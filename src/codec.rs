@@ -0,0 +1,82 @@
+/// Représentation persistée d'une clé ou d'une valeur
+///
+/// Implémenté pour `String`, `Vec<u8>`, les entiers, `bool` et `char`. Pour
+/// un type structuré, il suffit de choisir un encodage, par exemple:
+///
+/// ```
+/// use lru_cache::Persistable;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// impl Persistable for Point {
+///     fn to_bytes(&self) -> Vec<u8> {
+///         format!("{},{}", self.x, self.y).into_bytes()
+///     }
+///
+///     fn from_bytes(bytes: &[u8]) -> Option<Self> {
+///         let text = std::str::from_utf8(bytes).ok()?;
+///         let (x, y) = text.split_once(',')?;
+///         Some(Point {
+///             x: x.parse().ok()?,
+///             y: y.parse().ok()?,
+///         })
+///     }
+/// }
+///
+/// let p = Point { x: 1, y: -2 };
+/// assert_eq!(Point::from_bytes(&p.to_bytes()), Some(p));
+/// ```
+pub trait Persistable: Sized {
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// `None` si les octets ne représentent pas une valeur valide
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl Persistable for String {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+/// Octets quelconques (données compressées, protobuf...), persistés tels
+/// quels; le format JSON les écrit en base64 s'ils ne sont pas UTF-8
+impl Persistable for Vec<u8> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(bytes.to_vec())
+    }
+}
+
+// Les types scalaires sont persistés sous forme textuelle, ce qui garde les
+// fichiers lisibles
+macro_rules! persistable_via_str {
+    ($($ty:ty),*) => {
+        $(
+            impl Persistable for $ty {
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_string().into_bytes()
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                    std::str::from_utf8(bytes).ok()?.parse().ok()
+                }
+            }
+        )*
+    };
+}
+
+persistable_via_str!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool, char
+);
@@ -0,0 +1,145 @@
+//! Stockage d'un fichier par entrée pour les grosses valeurs
+//!
+//! ```text
+//! cache/
+//! ├── index        - journal de `Put(clé, numéro)` dans l'ordre LRU
+//! └── values/
+//!     ├── 0        - octets de la valeur n° 0
+//!     └── 1
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::CacheError;
+use crate::journal::Record;
+use crate::persistent::write_atomic;
+
+pub(crate) struct EntryDirectory {
+    root: PathBuf,
+    /// Numéro du fichier de chaque clé (octets persistés)
+    ids: HashMap<Vec<u8>, u64>,
+    next_id: u64,
+}
+
+impl EntryDirectory {
+    pub(crate) fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            ids: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub(crate) fn index_path(root: &Path) -> PathBuf {
+        root.join("index")
+    }
+
+    /// Même numérotation, autre dossier (`save_as`)
+    pub(crate) fn moved_to(&self, root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            ids: self.ids.clone(),
+            next_id: self.next_id,
+        }
+    }
+
+    fn value_path(&self, id: u64) -> PathBuf {
+        self.root.join("values").join(id.to_string())
+    }
+
+    pub(crate) fn write_value(&mut self, key: &[u8], bytes: &[u8], sync: bool) -> io::Result<()> {
+        let id = match self.ids.get(key) {
+            Some(&id) => id,
+            None => {
+                let id = self.next_id;
+                self.next_id += 1;
+                self.ids.insert(key.to_vec(), id);
+                id
+            }
+        };
+        fs::create_dir_all(self.root.join("values"))?;
+        write_atomic(&self.value_path(id), bytes, false, sync)
+    }
+
+    pub(crate) fn remove_value(&mut self, key: &[u8]) -> io::Result<()> {
+        match self.ids.remove(key) {
+            Some(id) => match fs::remove_file(self.value_path(id)) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Enregistrements de l'index pour ces clés, dans l'ordre donné
+    pub(crate) fn index_records(&self, keys: impl Iterator<Item = Vec<u8>>) -> Vec<Record> {
+        keys.filter_map(|key| {
+            let id = *self.ids.get(&key)?;
+            Some(Record::Put(key, id.to_string().into_bytes()))
+        })
+        .collect()
+    }
+
+    /// Enregistre les numéros de fichier de l'index et renvoie ses clés dans
+    /// l'ordre LRU; les autres enregistrements (statistiques) sont renvoyés
+    /// tels quels
+    pub(crate) fn read_index(
+        &mut self,
+        records: Vec<Record>,
+    ) -> Result<(Vec<Vec<u8>>, Vec<Record>), CacheError> {
+        let mut keys = Vec::new();
+        let mut others = Vec::new();
+
+        for record in records {
+            let Record::Put(key, id) = record else {
+                others.push(record);
+                continue;
+            };
+            let id: u64 = std::str::from_utf8(&id)
+                .ok()
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| CacheError::corrupted("index: numéro de fichier invalide"))?;
+
+            self.next_id = self.next_id.max(id + 1);
+            self.ids.insert(key.clone(), id);
+            keys.push(key);
+        }
+        Ok((keys, others))
+    }
+
+    /// Octets (transformés) de la valeur d'une clé de l'index
+    pub(crate) fn read_value(&self, key: &[u8]) -> Result<Vec<u8>, CacheError> {
+        let id = *self
+            .ids
+            .get(key)
+            .ok_or_else(|| CacheError::corrupted("index: clé inconnue"))?;
+        fs::read(self.value_path(id)).map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => {
+                CacheError::corrupted(format!("index: valeur n° {} absente", id))
+            }
+            _ => CacheError::Io(err),
+        })
+    }
+
+    /// Supprime les fichiers de valeur qui ne sont plus référencés
+    pub(crate) fn remove_orphans(&self) -> io::Result<()> {
+        let Ok(files) = fs::read_dir(self.root.join("values")) else {
+            return Ok(());
+        };
+        let live: HashSet<String> = self.ids.values().map(u64::to_string).collect();
+        for file in files {
+            let file = file?;
+            if !file
+                .file_name()
+                .to_str()
+                .is_some_and(|name| live.contains(name))
+            {
+                fs::remove_file(file.path())?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,91 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::report::InvalidRecord;
+
+/// Erreur de persistance d'un `PersistentLruCache`
+#[derive(Debug)]
+pub enum CacheError {
+    /// Erreur d'entrée/sortie du système
+    Io(io::Error),
+    /// Le fichier à relire n'existe pas
+    NotFound(PathBuf),
+    /// Contenu illisible ou somme de contrôle invalide
+    Corrupted(String),
+    /// Enregistrement illisible, avec sa position (chargement strict)
+    InvalidRecord(InvalidRecord),
+    /// Fichier écrit dans une version de format inconnue
+    VersionMismatch { found: u8, expected: u8 },
+    /// Fichier verrouillé par un autre processus
+    Locked(PathBuf),
+    /// Écriture refusée: cache ouvert en lecture seule
+    ReadOnly,
+    /// Fichier au-delà de `PersistOptions::max_file_size` même compacté
+    FileTooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+}
+
+impl CacheError {
+    pub(crate) fn corrupted(message: impl Into<String>) -> Self {
+        CacheError::Corrupted(message.into())
+    }
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Io(err) => write!(f, "erreur d'entrée/sortie: {}", err),
+            CacheError::NotFound(path) => write!(f, "fichier absent: {}", path.display()),
+            CacheError::Corrupted(message) => write!(f, "fichier corrompu: {}", message),
+            CacheError::InvalidRecord(record) => write!(f, "fichier corrompu: {}", record),
+            CacheError::VersionMismatch { found, expected } => write!(
+                f,
+                "version de format {} non supportée (attendue: {})",
+                found, expected
+            ),
+            CacheError::Locked(path) => write!(f, "fichier verrouillé: {}", path.display()),
+            CacheError::ReadOnly => write!(f, "cache ouvert en lecture seule"),
+            CacheError::FileTooLarge { path, size, limit } => write!(
+                f,
+                "fichier trop volumineux: {} ({} octets, limite: {})",
+                path.display(),
+                size,
+                limit
+            ),
+        }
+    }
+}
+
+impl Error for CacheError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CacheError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CacheError {
+    fn from(err: io::Error) -> Self {
+        CacheError::Io(err)
+    }
+}
+
+impl From<CacheError> for io::Error {
+    fn from(err: CacheError) -> Self {
+        match err {
+            CacheError::Io(err) => err,
+            CacheError::NotFound(_) => io::Error::new(io::ErrorKind::NotFound, err.to_string()),
+            CacheError::Locked(_) => io::Error::new(io::ErrorKind::WouldBlock, err.to_string()),
+            CacheError::ReadOnly => {
+                io::Error::new(io::ErrorKind::PermissionDenied, err.to_string())
+            }
+            _ => io::Error::new(io::ErrorKind::InvalidData, err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! Interface C (feature `ffi`)
+//!
+//! Clés et valeurs sont des suites d'octets. Le cache est un
+//! `SyncLruCache` (`lru_cache_new`) ou un `SyncPersistentLruCache`
+//! (`lru_cache_open`): un même pointeur peut être utilisé depuis plusieurs
+//! threads. Les déclarations C sont dans `include/lru_cache.h`; le module
+//! Python `python/lru_cache.py` s'appuie sur cette interface.
+//!
+//! Aucune panique ne traverse la frontière C (ce serait un comportement
+//! indéfini): elle est rattrapée et rendue comme une erreur, `-1` ou
+//! `NULL` selon la fonction.
+//!
+//! ```c
+//! lru_cache_t *cache = lru_cache_new(1000);
+//! lru_cache_put(cache, (const uint8_t *)"a", 1, (const uint8_t *)"1", 1);
+//!
+//! uint8_t *value;
+//! size_t len;
+//! if (lru_cache_get(cache, (const uint8_t *)"a", 1, &value, &len) == 1) {
+//!     /* ... */
+//!     lru_cache_bytes_free(value, len);
+//! }
+//! lru_cache_free(cache);
+//! ```
+
+use std::ffi::{c_char, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use crate::persistent::PersistOptions;
+use crate::sync::SyncLruCache;
+use crate::sync_persistent::SyncPersistentLruCache;
+
+/// Cache manipulé par le code C, toujours derrière un pointeur
+pub enum FfiCache {
+    Memory(SyncLruCache<Vec<u8>, Vec<u8>>),
+    Persistent(SyncPersistentLruCache<Vec<u8>, Vec<u8>>),
+}
+
+impl FfiCache {
+    /// `true` si une valeur a été remplacée
+    fn put(&self, key: Vec<u8>, value: Vec<u8>) -> bool {
+        match self {
+            FfiCache::Memory(cache) => cache.put(key, value).is_some(),
+            FfiCache::Persistent(cache) => cache.put(key, value).is_some(),
+        }
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            FfiCache::Memory(cache) => cache.get(&key.to_vec()),
+            FfiCache::Persistent(cache) => cache.get(key),
+        }
+    }
+
+    fn remove(&self, key: &[u8]) -> bool {
+        match self {
+            FfiCache::Memory(cache) => cache.remove(&key.to_vec()).is_some(),
+            FfiCache::Persistent(cache) => cache.remove(&key.to_vec()).is_some(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            FfiCache::Memory(cache) => cache.len(),
+            FfiCache::Persistent(cache) => cache.with(|cache| cache.len()),
+        }
+    }
+}
+
+/// Nouveau cache de `capacity` entrées, à libérer avec `lru_cache_free`
+#[no_mangle]
+pub extern "C" fn lru_cache_new(capacity: usize) -> *mut FfiCache {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(FfiCache::Memory(SyncLruCache::new(capacity))))
+    })
+}
+
+/// Ouvre (ou crée) le fichier de cache `path` avec les options par défaut;
+/// `NULL` en cas d'erreur
+///
+/// Les clés et valeurs sont persistées telles quelles: un fichier écrit
+/// par un `PersistentLruCache<String, String>` se relit ici, et
+/// inversement pour des octets UTF-8.
+///
+/// # Safety
+///
+/// `path` est une chaîne C terminée par un octet nul.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_open(path: *const c_char, capacity: usize) -> *mut FfiCache {
+    guard(ptr::null_mut(), || {
+        if path.is_null() {
+            return ptr::null_mut();
+        }
+        let Ok(path) = CStr::from_ptr(path).to_str() else {
+            return ptr::null_mut();
+        };
+        match SyncPersistentLruCache::open(capacity, path, PersistOptions::default()) {
+            Ok(cache) => Box::into_raw(Box::new(FfiCache::Persistent(cache))),
+            Err(_) => ptr::null_mut(),
+        }
+    })
+}
+
+/// Sauvegarde un cache ouvert par `lru_cache_open`; retourne 0 si la
+/// sauvegarde a réussi (ou si le cache est en mémoire), -1 sinon
+///
+/// # Safety
+///
+/// `cache` vient de `lru_cache_new` ou `lru_cache_open`.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_save(cache: *const FfiCache) -> i32 {
+    guard(-1, || match cache.as_ref() {
+        Some(FfiCache::Persistent(cache)) => match cache.save() {
+            Ok(()) => 0,
+            Err(_) => -1,
+        },
+        Some(FfiCache::Memory(_)) => 0,
+        None => -1,
+    })
+}
+
+/// Libère un cache créé par `lru_cache_new` ou `lru_cache_open` (qui est
+/// alors sauvegardé selon ses options); sans effet sur `NULL`
+///
+/// # Safety
+///
+/// `cache` vient de `lru_cache_new` ou `lru_cache_open` et n'est plus
+/// utilisé ensuite.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_free(cache: *mut FfiCache) {
+    guard((), || {
+        if !cache.is_null() {
+            drop(Box::from_raw(cache));
+        }
+    })
+}
+
+/// Insère une paire (copiée); retourne 1 si une valeur a été remplacée,
+/// 0 sinon, -1 si un pointeur est nul
+///
+/// # Safety
+///
+/// `cache` vient de `lru_cache_new` ou `lru_cache_open`; `key` et `value`
+/// pointent sur `key_len` et `value_len` octets lisibles (ou sont nuls si
+/// la longueur est 0).
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_put(
+    cache: *const FfiCache,
+    key: *const u8,
+    key_len: usize,
+    value: *const u8,
+    value_len: usize,
+) -> i32 {
+    guard(-1, || {
+        let (Some(cache), Some(key), Some(value)) =
+            (cache.as_ref(), bytes(key, key_len), bytes(value, value_len))
+        else {
+            return -1;
+        };
+        cache.put(key.to_vec(), value.to_vec()) as i32
+    })
+}
+
+/// Copie la valeur de `key` dans un tampon alloué par la bibliothèque;
+/// retourne 1 si trouvée, 0 si absente, -1 si un pointeur est nul
+///
+/// Le tampon (`*value`, `*value_len`) se libère avec
+/// `lru_cache_bytes_free`. La clé est marquée comme récente.
+///
+/// # Safety
+///
+/// `cache` vient de `lru_cache_new` ou `lru_cache_open`; `key` pointe sur
+/// `key_len` octets lisibles; `value` et `value_len` sont des pointeurs
+/// valides en écriture.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_get(
+    cache: *const FfiCache,
+    key: *const u8,
+    key_len: usize,
+    value: *mut *mut u8,
+    value_len: *mut usize,
+) -> i32 {
+    guard(-1, || {
+        let (Some(cache), Some(key)) = (cache.as_ref(), bytes(key, key_len)) else {
+            return -1;
+        };
+        if value.is_null() || value_len.is_null() {
+            return -1;
+        }
+        let Some(found) = cache.get(key) else {
+            *value = ptr::null_mut();
+            *value_len = 0;
+            return 0;
+        };
+        let buffer = found.into_boxed_slice();
+        *value_len = buffer.len();
+        *value = Box::into_raw(buffer).cast::<u8>();
+        1
+    })
+}
+
+/// Retire une entrée; retourne 1 si elle existait, 0 sinon, -1 si un
+/// pointeur est nul
+///
+/// # Safety
+///
+/// Comme pour `lru_cache_put`.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_remove(
+    cache: *const FfiCache,
+    key: *const u8,
+    key_len: usize,
+) -> i32 {
+    guard(-1, || {
+        let (Some(cache), Some(key)) = (cache.as_ref(), bytes(key, key_len)) else {
+            return -1;
+        };
+        cache.remove(key) as i32
+    })
+}
+
+/// Nombre d'entrées (0 pour `NULL`)
+///
+/// # Safety
+///
+/// `cache` vient de `lru_cache_new` ou `lru_cache_open`, ou est nul.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_len(cache: *const FfiCache) -> usize {
+    guard(0, || cache.as_ref().map_or(0, FfiCache::len))
+}
+
+/// Libère un tampon rendu par `lru_cache_get`; sans effet sur `NULL`
+///
+/// # Safety
+///
+/// `value` et `len` sont ceux rendus par `lru_cache_get`, une seule fois.
+#[no_mangle]
+pub unsafe extern "C" fn lru_cache_bytes_free(value: *mut u8, len: usize) {
+    guard((), || {
+        if !value.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(value, len)));
+        }
+    })
+}
+
+/// Exécute `f`, ou rend `on_panic` si `f` panique
+///
+/// Le cache ne reste jamais incohérent après une panique (les verrous
+/// empoisonnés sont ignorés), il reste donc utilisable ensuite.
+fn guard<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(on_panic)
+}
+
+/// Octets pointés, `None` si le pointeur est nul avec une longueur non nulle
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    match (data.is_null(), len) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(data, len)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cache = lru_cache_new(1);
+        let (mut value, mut len) = (ptr::null_mut(), 0);
+        unsafe {
+            assert_eq!(lru_cache_put(cache, b"a".as_ptr(), 1, b"12".as_ptr(), 2), 0);
+            assert_eq!(
+                lru_cache_get(cache, b"a".as_ptr(), 1, &mut value, &mut len),
+                1
+            );
+            assert_eq!(slice::from_raw_parts(value, len), b"12");
+            lru_cache_bytes_free(value, len);
+
+            // Valeur vide, puis éviction de "a"
+            assert_eq!(lru_cache_put(cache, b"b".as_ptr(), 1, ptr::null(), 0), 0);
+            assert_eq!(
+                lru_cache_get(cache, b"a".as_ptr(), 1, &mut value, &mut len),
+                0
+            );
+            assert_eq!(
+                lru_cache_get(cache, b"b".as_ptr(), 1, &mut value, &mut len),
+                1
+            );
+            assert_eq!(len, 0);
+            lru_cache_bytes_free(value, len);
+
+            assert_eq!(lru_cache_put(cache, ptr::null(), 1, ptr::null(), 0), -1);
+            assert_eq!(lru_cache_remove(cache, b"b".as_ptr(), 1), 1);
+            assert_eq!(lru_cache_len(cache), 0);
+            lru_cache_free(cache);
+        }
+    }
+
+    #[test]
+    fn test_panic_becomes_error() {
+        assert_eq!(guard(-1, || panic!("panique dans le cache")), -1);
+        assert!(guard(ptr::null_mut::<FfiCache>(), || panic!()).is_null());
+        assert_eq!(guard(-1, || 1), 1);
+    }
+
+    #[test]
+    fn test_open_shares_string_files() {
+        let path = "test_ffi.cache";
+        let mut cache: crate::PersistentLruCache =
+            crate::PersistentLruCache::open(10, path).unwrap();
+        cache.put("a".to_string(), "1".to_string());
+        drop(cache);
+
+        let c_path = std::ffi::CString::new(path).unwrap();
+        let (mut value, mut len) = (ptr::null_mut(), 0);
+        unsafe {
+            let cache = lru_cache_open(c_path.as_ptr(), 10);
+            assert!(!cache.is_null());
+            assert_eq!(
+                lru_cache_get(cache, b"a".as_ptr(), 1, &mut value, &mut len),
+                1
+            );
+            assert_eq!(slice::from_raw_parts(value, len), b"1");
+            lru_cache_bytes_free(value, len);
+            lru_cache_put(cache, b"b".as_ptr(), 1, b"2".as_ptr(), 1);
+            assert_eq!(lru_cache_save(cache), 0);
+            lru_cache_free(cache);
+        }
+
+        let mut cache: crate::PersistentLruCache =
+            crate::PersistentLruCache::open(10, path).unwrap();
+        assert_eq!(cache.get("b"), Some(&"2".to_string()));
+        drop(cache);
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file("test_ffi.cache.lock").ok();
+    }
+}
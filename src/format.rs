@@ -0,0 +1,871 @@
+use std::io;
+
+use crate::base64;
+use crate::error::CacheError;
+use crate::json::{self, Json};
+#[cfg(feature = "msgpack")]
+use crate::msgpack;
+#[cfg(feature = "redis")]
+use crate::rdb;
+use crate::report::{InvalidRecord, Recovery};
+use crate::stats::CacheStats;
+
+/// Format du fichier d'un `PersistentLruCache`
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{Format, PersistOptions, PersistentLruCache};
+///
+/// let options = PersistOptions::new().format(Format::Json { pretty: true });
+/// let cache: PersistentLruCache =
+///     PersistentLruCache::with_options(3, "cache.json", options).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Une ligne d'en-tête puis une ligne `clé:valeur` par entrée
+    ///
+    /// `\`, `:` et les fins de ligne sont échappés par `\`. Avec les
+    /// métadonnées, chaque ligne devient `clé:valeur:échéance:hits`
+    /// (échéance `-` sans TTL).
+    #[default]
+    Text,
+    /// Document JSON, lisible et modifiable à la main
+    ///
+    /// ```json
+    /// {"capacity": 3, "entries": [{"key": "a", "value": "1"}]}
+    /// ```
+    ///
+    /// Une clé ou une valeur qui n'est pas UTF-8 est écrite en base64 sous
+    /// `key_base64` ou `value_base64`.
+    Json { pretty: bool },
+    /// Binaire compact: longueurs préfixées, aucun échappement
+    ///
+    /// Adapté aux gros caches, plus rapide à écrire et à relire que le texte.
+    Binary,
+    /// CSV à deux colonnes `key,value`, guillemets selon la RFC 4180
+    ///
+    /// Clés et valeurs doivent être UTF-8.
+    /// Seules les entrées sont sauvegardées: la capacité, les statistiques
+    /// et les métadonnées restent celles passées à la construction.
+    Csv,
+    /// MessagePack, lisible depuis d'autres langages (feature `msgpack`)
+    ///
+    /// Même structure que le format JSON: une table `capacity`, `stats`,
+    /// `entries`, chaque entrée étant une table `key`/`value`.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    /// Dump RDB de Redis limité aux chaînes (feature `redis`)
+    ///
+    /// Le fichier se charge dans Redis (`dump.rdb`, version 5 et suivantes)
+    /// avec les TTL des entrées; un dump écrit par Redis se relit ici. Seules
+    /// les chaînes de la base 0 sont importées: les autres valeurs sont des
+    /// enregistrements illisibles, à ignorer avec `PersistOptions::lenient`.
+    /// L'ordre des entrées n'est conservé qu'entre deux fichiers de ce crate.
+    #[cfg(feature = "redis")]
+    Rdb,
+}
+
+/// Contenu d'un fichier de cache, indépendamment de son format
+///
+/// Les entrées sont rangées de la moins à la plus récemment utilisée.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Snapshot {
+    pub(crate) capacity: Option<usize>,
+    pub(crate) stats: Option<CacheStats>,
+    pub(crate) entries: Vec<Entry>,
+    /// Vide si non sauvegardées, sinon une par entrée
+    pub(crate) metadata: Vec<EntryMeta>,
+}
+
+/// Clé et valeur en octets
+type Entry = (Vec<u8>, Vec<u8>);
+
+/// Échéance et compteur d'une entrée
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct EntryMeta {
+    /// Millisecondes depuis l'époque Unix, `None` sans TTL
+    pub(crate) expires_at: Option<u64>,
+    pub(crate) hits: u64,
+}
+
+/// Les métadonnées ne sont retenues que si chaque entrée en a
+fn collect_metadata(metadata: Vec<Option<EntryMeta>>) -> Vec<EntryMeta> {
+    metadata
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+impl Format {
+    pub(crate) fn encode(&self, snapshot: &Snapshot) -> io::Result<Vec<u8>> {
+        match self {
+            Format::Text => Ok(encode_text(snapshot)),
+            Format::Json { pretty } => encode_json(snapshot, *pretty),
+            Format::Binary => Ok(encode_binary(snapshot)),
+            Format::Csv => encode_csv(snapshot),
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack => Ok(encode_msgpack(snapshot)),
+            #[cfg(feature = "redis")]
+            Format::Rdb => Ok(rdb::encode(snapshot)),
+        }
+    }
+
+    pub(crate) fn decode(&self, bytes: &[u8]) -> Result<Snapshot, CacheError> {
+        self.decode_with(bytes, &mut Recovery::strict())
+    }
+
+    /// Décode en confiant les entrées illisibles à `recovery`
+    pub(crate) fn decode_with(
+        &self,
+        bytes: &[u8],
+        recovery: &mut Recovery,
+    ) -> Result<Snapshot, CacheError> {
+        match self {
+            Format::Text => decode_text(bytes, recovery),
+            Format::Json { .. } => decode_json(bytes, recovery),
+            Format::Binary => decode_binary(bytes, recovery),
+            Format::Csv => decode_csv(bytes, recovery),
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack => decode_msgpack(bytes, recovery),
+            #[cfg(feature = "redis")]
+            Format::Rdb => rdb::decode(bytes, recovery),
+        }
+    }
+}
+
+fn invalid_data(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+fn encode_text(snapshot: &Snapshot) -> Vec<u8> {
+    let mut out = Vec::new();
+    let capacity = snapshot.capacity.unwrap_or(0);
+    let header = match snapshot.stats {
+        Some(stats) => format!(
+            "{} {} {} {}\n",
+            capacity, stats.hits, stats.misses, stats.evictions
+        ),
+        None => format!("{}\n", capacity),
+    };
+    out.extend_from_slice(header.as_bytes());
+
+    for (i, (key, value)) in snapshot.entries.iter().enumerate() {
+        escape_text(&mut out, key);
+        out.push(b':');
+        escape_text(&mut out, value);
+        if let Some(meta) = snapshot.metadata.get(i) {
+            let expires_at = meta.expires_at.map_or("-".to_string(), |t| t.to_string());
+            out.extend_from_slice(format!(":{}:{}", expires_at, meta.hits).as_bytes());
+        }
+        out.push(b'\n');
+    }
+    out
+}
+
+/// Échappe `\`, `:` et les fins de ligne pour qu'une entrée tienne sur une
+/// ligne et que le premier `:` non échappé sépare toujours clé et valeur
+fn escape_text(out: &mut Vec<u8>, bytes: &[u8]) {
+    for &b in bytes {
+        match b {
+            b'\\' => out.extend_from_slice(b"\\\\"),
+            b':' => out.extend_from_slice(b"\\:"),
+            b'\n' => out.extend_from_slice(b"\\n"),
+            b'\r' => out.extend_from_slice(b"\\r"),
+            b => out.push(b),
+        }
+    }
+}
+
+/// Découpe une ligne aux `:` non échappés et retire les échappements
+///
+/// Deux champs au-delà de la valeur sont lus comme métadonnées s'ils en
+/// ont la forme; sinon ils restent dans la valeur.
+fn split_text_entry(line: &[u8]) -> Option<(Entry, Option<EntryMeta>)> {
+    let mut fields = vec![Vec::new()];
+    let mut bytes = line.iter();
+
+    while let Some(&b) = bytes.next() {
+        let current = fields.last_mut().unwrap();
+        match b {
+            b'\\' => match bytes.next() {
+                Some(b'n') => current.push(b'\n'),
+                Some(b'r') => current.push(b'\r'),
+                Some(&other) => current.push(other),
+                None => current.push(b'\\'),
+            },
+            b':' => fields.push(Vec::new()),
+            b => current.push(b),
+        }
+    }
+
+    let meta = match &fields[..] {
+        [_, _, expires_at, hits] => parse_text_meta(expires_at, hits),
+        _ => None,
+    };
+    if meta.is_some() {
+        fields.truncate(2);
+    }
+
+    let mut fields = fields.into_iter();
+    let key = fields.next()?;
+    let mut value = fields.next()?;
+    for field in fields {
+        value.push(b':');
+        value.extend_from_slice(&field);
+    }
+    Some(((key, value), meta))
+}
+
+fn parse_text_meta(expires_at: &[u8], hits: &[u8]) -> Option<EntryMeta> {
+    let number = |field: &[u8]| std::str::from_utf8(field).ok()?.parse().ok();
+    let expires_at = match expires_at {
+        b"-" => None,
+        field => Some(number(field)?),
+    };
+    Some(EntryMeta {
+        expires_at,
+        hits: number(hits)?,
+    })
+}
+
+/// En-tête: `capacité [hits misses évictions]`; les lignes vides sont ignorées
+fn decode_text(bytes: &[u8], recovery: &mut Recovery) -> Result<Snapshot, CacheError> {
+    let mut snapshot = Snapshot::default();
+    let mut offset = 0;
+    let mut lines = bytes.split(|&b| b == b'\n').enumerate().map(|(i, line)| {
+        let start = offset;
+        offset += line.len() + 1;
+        (i + 1, start, line.strip_suffix(b"\r").unwrap_or(line))
+    });
+
+    if let Some((_, _, header)) = lines.next() {
+        let header = String::from_utf8_lossy(header);
+        let mut fields = header.split_whitespace();
+        snapshot.capacity = fields.next().and_then(|f| f.parse().ok());
+
+        let counters: Vec<u64> = fields.filter_map(|f| f.parse().ok()).collect();
+        if let [hits, misses, evictions] = counters[..] {
+            snapshot.stats = Some(CacheStats {
+                hits,
+                misses,
+                evictions,
+            });
+        }
+    }
+
+    let mut metadata = Vec::new();
+    let entries = lines.filter(|(_, _, line)| !line.is_empty());
+    for (index, (number, start, line)) in entries.enumerate() {
+        match split_text_entry(line) {
+            Some((entry, meta)) => {
+                snapshot.entries.push(entry);
+                metadata.push(meta);
+            }
+            None => recovery.skip(
+                InvalidRecord::new(index, "texte: ligne sans séparateur \":\"")
+                    .on_line(number)
+                    .at(start),
+            )?,
+        }
+    }
+    snapshot.metadata = collect_metadata(metadata);
+    Ok(snapshot)
+}
+
+fn encode_json(snapshot: &Snapshot, pretty: bool) -> io::Result<Vec<u8>> {
+    let (indent, newline, space) = if pretty {
+        ("  ", "\n", " ")
+    } else {
+        ("", "", "")
+    };
+    let mut out = String::new();
+
+    out.push('{');
+    out.push_str(newline);
+    out.push_str(&format!(
+        "{indent}\"capacity\":{space}{}",
+        snapshot.capacity.unwrap_or(0)
+    ));
+
+    if let Some(stats) = snapshot.stats {
+        out.push_str(&format!(
+            ",{newline}{indent}\"stats\":{space}{{\"hits\":{space}{},{space}\"misses\":{space}{},{space}\"evictions\":{space}{}}}",
+            stats.hits, stats.misses, stats.evictions
+        ));
+    }
+
+    out.push_str(&format!(",{newline}{indent}\"entries\":{space}["));
+    for (i, (key, value)) in snapshot.entries.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(newline);
+        out.push_str(indent);
+        out.push_str(indent);
+        out.push('{');
+        write_json_bytes(&mut out, "key", key, space);
+        out.push(',');
+        out.push_str(space);
+        write_json_bytes(&mut out, "value", value, space);
+        if let Some(meta) = snapshot.metadata.get(i) {
+            if let Some(expires_at) = meta.expires_at {
+                out.push_str(&format!(",{space}\"expires_at\":{space}{}", expires_at));
+            }
+            out.push_str(&format!(",{space}\"hits\":{space}{}", meta.hits));
+        }
+        out.push('}');
+    }
+    if !snapshot.entries.is_empty() {
+        out.push_str(newline);
+        out.push_str(indent);
+    }
+    out.push(']');
+    out.push_str(newline);
+    out.push('}');
+    out.push_str(newline);
+
+    Ok(out.into_bytes())
+}
+
+/// `"champ": "texte"`, ou `"champ_base64": "..."` pour des octets non UTF-8
+fn write_json_bytes(out: &mut String, field: &str, bytes: &[u8], space: &str) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => {
+            out.push_str(&format!("\"{}\":{}", field, space));
+            json::write_string(out, text);
+        }
+        Err(_) => {
+            out.push_str(&format!("\"{}_base64\":{}", field, space));
+            json::write_string(out, &base64::encode(bytes));
+        }
+    }
+}
+
+/// Lit `champ` ou, à défaut, `champ_base64`
+fn read_json_bytes(entry: &Json, field: &str) -> Option<Vec<u8>> {
+    if let Some(text) = entry.get(field).and_then(Json::as_str) {
+        return Some(text.as_bytes().to_vec());
+    }
+    let encoded = entry.get(&format!("{}_base64", field))?.as_str()?;
+    base64::decode(encoded)
+}
+
+fn utf8(bytes: &[u8]) -> io::Result<&str> {
+    std::str::from_utf8(bytes).map_err(|_| invalid_data("le format CSV exige des données UTF-8"))
+}
+
+fn decode_json(bytes: &[u8], recovery: &mut Recovery) -> Result<Snapshot, CacheError> {
+    let text = std::str::from_utf8(bytes).map_err(|_| CacheError::corrupted("JSON non UTF-8"))?;
+    let document = json::parse(text).map_err(CacheError::corrupted)?;
+
+    let mut snapshot = Snapshot {
+        capacity: document
+            .get("capacity")
+            .and_then(Json::as_u64)
+            .map(|c| c as usize),
+        ..Snapshot::default()
+    };
+
+    if let Some(stats) = document.get("stats") {
+        let counter = |name| stats.get(name).and_then(Json::as_u64).unwrap_or(0);
+        snapshot.stats = Some(CacheStats {
+            hits: counter("hits"),
+            misses: counter("misses"),
+            evictions: counter("evictions"),
+        });
+    }
+
+    let entries = document
+        .get("entries")
+        .and_then(Json::as_array)
+        .ok_or_else(|| CacheError::corrupted("champ \"entries\" manquant"))?;
+
+    let mut metadata = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let key = read_json_bytes(entry, "key");
+        let value = read_json_bytes(entry, "value");
+        match (key, value) {
+            (Some(key), Some(value)) => snapshot.entries.push((key, value)),
+            _ => {
+                recovery.skip(InvalidRecord::new(
+                    index,
+                    "entrée sans \"key\" ou \"value\"",
+                ))?;
+                continue;
+            }
+        }
+        metadata.push(
+            entry
+                .get("hits")
+                .and_then(Json::as_u64)
+                .map(|hits| EntryMeta {
+                    expires_at: entry.get("expires_at").and_then(Json::as_u64),
+                    hits,
+                }),
+        );
+    }
+    snapshot.metadata = collect_metadata(metadata);
+    Ok(snapshot)
+}
+
+const BINARY_MAGIC: &[u8; 4] = b"LRUB";
+const BINARY_VERSION: u8 = 1;
+const BINARY_STATS: u8 = 1;
+const BINARY_METADATA: u8 = 2;
+/// Échéance enregistrée pour une entrée sans TTL
+const NO_EXPIRY: u64 = u64::MAX;
+
+/// `LRUB`, version, capacité, drapeaux, [stats], nombre d'entrées, puis pour
+/// chaque entrée la clé et la valeur préfixées par leur longueur et
+/// [échéance, hits] (entiers petit-boutistes)
+fn encode_binary(snapshot: &Snapshot) -> Vec<u8> {
+    let payload: usize = snapshot
+        .entries
+        .iter()
+        .map(|(k, v)| 8 + k.len() + v.len())
+        .sum();
+    let mut out = Vec::with_capacity(48 + payload);
+
+    out.extend_from_slice(BINARY_MAGIC);
+    out.push(BINARY_VERSION);
+    out.extend_from_slice(&(snapshot.capacity.unwrap_or(0) as u64).to_le_bytes());
+    let with_metadata = !snapshot.metadata.is_empty();
+    let mut flags = 0;
+    if snapshot.stats.is_some() {
+        flags |= BINARY_STATS;
+    }
+    if with_metadata {
+        flags |= BINARY_METADATA;
+    }
+    out.push(flags);
+    if let Some(stats) = snapshot.stats {
+        for counter in [stats.hits, stats.misses, stats.evictions] {
+            out.extend_from_slice(&counter.to_le_bytes());
+        }
+    }
+
+    out.extend_from_slice(&(snapshot.entries.len() as u64).to_le_bytes());
+    for (i, (key, value)) in snapshot.entries.iter().enumerate() {
+        out.extend_from_slice(&(key.len() as u32).to_le_bytes());
+        out.extend_from_slice(key);
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(value);
+        if with_metadata {
+            let meta = snapshot.metadata.get(i).copied().unwrap_or_default();
+            let expires_at = meta.expires_at.unwrap_or(NO_EXPIRY);
+            out.extend_from_slice(&expires_at.to_le_bytes());
+            out.extend_from_slice(&meta.hits.to_le_bytes());
+        }
+    }
+    out
+}
+
+fn decode_binary(bytes: &[u8], recovery: &mut Recovery) -> Result<Snapshot, CacheError> {
+    let mut reader = Reader { bytes };
+
+    if reader.take(4)? != BINARY_MAGIC {
+        return Err(CacheError::corrupted("fichier binaire: signature absente"));
+    }
+    let version = reader.take(1)?[0];
+    if version != BINARY_VERSION {
+        return Err(CacheError::VersionMismatch {
+            found: version,
+            expected: BINARY_VERSION,
+        });
+    }
+
+    let mut snapshot = Snapshot {
+        capacity: Some(reader.u64()? as usize),
+        ..Snapshot::default()
+    };
+    let flags = reader.take(1)?[0];
+    if flags & BINARY_STATS != 0 {
+        snapshot.stats = Some(CacheStats {
+            hits: reader.u64()?,
+            misses: reader.u64()?,
+            evictions: reader.u64()?,
+        });
+    }
+
+    let count = reader.u64()? as usize;
+    // Le nombre annoncé n'est pas digne de confiance pour préallouer
+    snapshot.entries.reserve(count.min(bytes.len() / 8));
+    for index in 0..count {
+        let start = bytes.len() - reader.bytes.len();
+        let mut read_entry = || -> Result<_, CacheError> {
+            let key = reader.chunk()?.to_vec();
+            let value = reader.chunk()?.to_vec();
+            let meta = if flags & BINARY_METADATA != 0 {
+                let expires_at = reader.u64()?;
+                Some(EntryMeta {
+                    expires_at: (expires_at != NO_EXPIRY).then_some(expires_at),
+                    hits: reader.u64()?,
+                })
+            } else {
+                None
+            };
+            Ok(((key, value), meta))
+        };
+        match read_entry() {
+            Ok((entry, meta)) => {
+                snapshot.entries.push(entry);
+                snapshot.metadata.extend(meta);
+            }
+            // Les longueurs ne sont plus fiables: la suite est perdue
+            Err(CacheError::Corrupted(reason)) => {
+                recovery.skip(InvalidRecord::new(index, reason).at(start))?;
+                break;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(snapshot)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CacheError> {
+        if self.bytes.len() < n {
+            return Err(CacheError::corrupted("fichier binaire tronqué"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> Result<u64, CacheError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn chunk(&mut self) -> Result<&'a [u8], CacheError> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+        self.take(len as usize)
+    }
+}
+
+const CSV_HEADER: [&str; 2] = ["key", "value"];
+
+fn encode_csv(snapshot: &Snapshot) -> io::Result<Vec<u8>> {
+    let mut out = String::from("key,value\r\n");
+    for (key, value) in &snapshot.entries {
+        write_csv_field(&mut out, utf8(key)?);
+        out.push(',');
+        write_csv_field(&mut out, utf8(value)?);
+        out.push_str("\r\n");
+    }
+    Ok(out.into_bytes())
+}
+
+fn write_csv_field(out: &mut String, field: &str) {
+    if field.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+fn decode_csv(bytes: &[u8], recovery: &mut Recovery) -> Result<Snapshot, CacheError> {
+    let text = std::str::from_utf8(bytes).map_err(|_| CacheError::corrupted("CSV non UTF-8"))?;
+    let mut snapshot = Snapshot::default();
+
+    for (line, record) in parse_csv(text)?.into_iter().enumerate() {
+        match <[String; 2]>::try_from(record) {
+            Ok(fields) if line == 0 && fields == CSV_HEADER => {}
+            Ok([key, value]) => snapshot
+                .entries
+                .push((key.into_bytes(), value.into_bytes())),
+            Err(_) => recovery.skip(InvalidRecord::new(
+                line,
+                format!("CSV: l'enregistrement {} n'a pas deux colonnes", line + 1),
+            ))?,
+        }
+    }
+    Ok(snapshot)
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, CacheError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() => quoted = true,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(CacheError::corrupted("CSV: guillemet non fermé"));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(feature = "msgpack")]
+fn encode_msgpack(snapshot: &Snapshot) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    msgpack::write_map_len(&mut out, if snapshot.stats.is_some() { 3 } else { 2 });
+    msgpack::write_bytes(&mut out, b"capacity");
+    msgpack::write_uint(&mut out, snapshot.capacity.unwrap_or(0) as u64);
+
+    if let Some(stats) = snapshot.stats {
+        msgpack::write_bytes(&mut out, b"stats");
+        msgpack::write_map_len(&mut out, 3);
+        for (name, counter) in [
+            ("hits", stats.hits),
+            ("misses", stats.misses),
+            ("evictions", stats.evictions),
+        ] {
+            msgpack::write_bytes(&mut out, name.as_bytes());
+            msgpack::write_uint(&mut out, counter);
+        }
+    }
+
+    msgpack::write_bytes(&mut out, b"entries");
+    msgpack::write_array_len(&mut out, snapshot.entries.len());
+    for (i, (key, value)) in snapshot.entries.iter().enumerate() {
+        let meta = snapshot.metadata.get(i);
+        let expires_at = meta.and_then(|meta| meta.expires_at);
+        let fields = 2 + usize::from(meta.is_some()) + usize::from(expires_at.is_some());
+        msgpack::write_map_len(&mut out, fields);
+        msgpack::write_bytes(&mut out, b"key");
+        msgpack::write_bytes(&mut out, key);
+        msgpack::write_bytes(&mut out, b"value");
+        msgpack::write_bytes(&mut out, value);
+        if let Some(expires_at) = expires_at {
+            msgpack::write_bytes(&mut out, b"expires_at");
+            msgpack::write_uint(&mut out, expires_at);
+        }
+        if let Some(meta) = meta {
+            msgpack::write_bytes(&mut out, b"hits");
+            msgpack::write_uint(&mut out, meta.hits);
+        }
+    }
+    out
+}
+
+#[cfg(feature = "msgpack")]
+fn decode_msgpack(bytes: &[u8], recovery: &mut Recovery) -> Result<Snapshot, CacheError> {
+    use crate::msgpack::Value;
+
+    let document = msgpack::parse(bytes).map_err(CacheError::corrupted)?;
+
+    let mut snapshot = Snapshot {
+        capacity: document
+            .get("capacity")
+            .and_then(Value::as_u64)
+            .map(|c| c as usize),
+        ..Snapshot::default()
+    };
+
+    if let Some(stats) = document.get("stats") {
+        let counter = |name| stats.get(name).and_then(Value::as_u64).unwrap_or(0);
+        snapshot.stats = Some(CacheStats {
+            hits: counter("hits"),
+            misses: counter("misses"),
+            evictions: counter("evictions"),
+        });
+    }
+
+    let entries = document
+        .get("entries")
+        .and_then(Value::as_array)
+        .ok_or_else(|| CacheError::corrupted("champ \"entries\" manquant"))?;
+
+    let mut metadata = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let key = entry.get("key").and_then(Value::as_bytes);
+        let value = entry.get("value").and_then(Value::as_bytes);
+        match (key, value) {
+            (Some(key), Some(value)) => snapshot.entries.push((key.to_vec(), value.to_vec())),
+            _ => {
+                recovery.skip(InvalidRecord::new(
+                    index,
+                    "entrée sans \"key\" ou \"value\"",
+                ))?;
+                continue;
+            }
+        }
+        metadata.push(
+            entry
+                .get("hits")
+                .and_then(Value::as_u64)
+                .map(|hits| EntryMeta {
+                    expires_at: entry.get("expires_at").and_then(Value::as_u64),
+                    hits,
+                }),
+        );
+    }
+    snapshot.metadata = collect_metadata(metadata);
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Snapshot {
+        Snapshot {
+            capacity: Some(3),
+            stats: Some(CacheStats {
+                hits: 1,
+                misses: 2,
+                evictions: 3,
+            }),
+            entries: vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"cl\xc3\xa9 \"q\"".to_vec(), b"ligne\nsuivante".to_vec()),
+            ],
+            metadata: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_text_escaping() {
+        let mut snapshot = sample();
+        snapshot
+            .entries
+            .push((b"a:b\\".to_vec(), b"x:\r\ny\\n".to_vec()));
+
+        let bytes = Format::Text.encode(&snapshot).unwrap();
+        assert_eq!(bytes.iter().filter(|&&b| b == b'\n').count(), 4);
+        assert_eq!(Format::Text.decode(&bytes).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let mut snapshot = sample();
+        snapshot.metadata = vec![
+            EntryMeta {
+                expires_at: Some(1_700_000_000_000),
+                hits: 4,
+            },
+            EntryMeta {
+                expires_at: None,
+                hits: 0,
+            },
+        ];
+
+        let formats = [
+            Format::Text,
+            Format::Json { pretty: false },
+            Format::Binary,
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack,
+        ];
+        for format in formats {
+            let bytes = format.encode(&snapshot).unwrap();
+            assert_eq!(format.decode(&bytes).unwrap(), snapshot, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        for pretty in [false, true] {
+            let format = Format::Json { pretty };
+            let bytes = format.encode(&sample()).unwrap();
+            assert_eq!(format.decode(&bytes).unwrap(), sample());
+        }
+    }
+
+    #[test]
+    fn test_binary_safe_values() {
+        let mut snapshot = sample();
+        snapshot.entries.push((
+            b"\xff\x00cl\xe9".to_vec(),
+            vec![0x1f, 0x8b, 0x08, 0x00, 0xff, b':', b'\n'],
+        ));
+
+        for format in [Format::Text, Format::Json { pretty: false }, Format::Binary] {
+            let bytes = format.encode(&snapshot).unwrap();
+            assert_eq!(format.decode(&bytes).unwrap(), snapshot);
+        }
+        let json = Format::Json { pretty: true }.encode(&snapshot).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert!(json.contains("\"value_base64\": \"H4sIAP86Cg==\""));
+        assert!(Format::Csv.encode(&snapshot).is_err());
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let bytes = Format::Binary.encode(&sample()).unwrap();
+        assert_eq!(Format::Binary.decode(&bytes).unwrap(), sample());
+
+        let truncated = Format::Binary.decode(&bytes[..bytes.len() - 1]);
+        assert!(matches!(truncated, Err(CacheError::InvalidRecord(_))));
+    }
+
+    #[test]
+    fn test_lenient_decoding() {
+        let mut recovery = Recovery::new(true);
+        let text = Format::Text.decode_with(b"3\na:1\nsans_separateur\n\nb:2\n", &mut recovery);
+        assert_eq!(text.unwrap().entries.len(), 2);
+
+        let bytes = Format::Binary.encode(&sample()).unwrap();
+        let binary = Format::Binary.decode_with(&bytes[..bytes.len() - 1], &mut recovery);
+        assert_eq!(binary.unwrap().entries, sample().entries[..1]);
+
+        let skipped: Vec<_> = recovery
+            .into_report()
+            .skipped
+            .iter()
+            .map(|record| (record.index, record.line, record.offset))
+            .collect();
+        assert_eq!(skipped, vec![(1, Some(3), Some(6)), (1, None, Some(56))]);
+        assert!(Format::Text.decode(b"3\nsans_separateur\n").is_err());
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        let mut snapshot = sample();
+        snapshot.capacity = None;
+        snapshot.stats = None;
+
+        let bytes = Format::Csv.encode(&snapshot).unwrap();
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert_eq!(
+            text,
+            "key,value\r\na,1\r\n\"clé \"\"q\"\"\",\"ligne\nsuivante\"\r\n"
+        );
+        assert_eq!(Format::Csv.decode(&bytes).unwrap(), snapshot);
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn test_msgpack_round_trip() {
+        let mut snapshot = sample();
+        snapshot.entries.push((vec![0xff, 0x00], vec![b'x'; 300]));
+
+        let bytes = Format::MessagePack.encode(&snapshot).unwrap();
+        assert_eq!(bytes[0], 0x83); // table de 3 champs
+        assert_eq!(Format::MessagePack.decode(&bytes).unwrap(), snapshot);
+    }
+}
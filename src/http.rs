@@ -0,0 +1,420 @@
+//! Interface HTTP minimale devant un `SyncLruCache` (feature `http`)
+
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use crate::server::{self, ServerHandle};
+use crate::sync::SyncLruCache;
+
+/// Taille maximale du corps d'une requête
+const MAX_BODY_LEN: usize = 16 * 1024 * 1024;
+
+/// Longueur maximale de la ligne de requête et de chaque en-tête
+const MAX_LINE_LEN: usize = 8 * 1024;
+
+type Cache = SyncLruCache<Vec<u8>, Vec<u8>>;
+
+/// Réponse de `HttpEndpoint::handle`, à recopier dans celle du service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn new(status: u16, content_type: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    fn text(status: u16, message: &str) -> Self {
+        Self::new(
+            status,
+            "text/plain; charset=utf-8",
+            format!("{}\n", message),
+        )
+    }
+
+    fn empty(status: u16) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", Vec::new())
+    }
+}
+
+/// Routes HTTP d'un `SyncLruCache`, à monter dans un service existant
+///
+/// - `GET /cache/{clé}`: la valeur (200) ou 404;
+/// - `PUT /cache/{clé}`: le corps devient la valeur, `?ttl=<secondes>`
+///   pour une durée de vie; 201 pour une nouvelle clé, 204 sinon;
+/// - `DELETE /cache/{clé}`: 204 ou 404;
+/// - `GET /stats`: entrées, capacité, hits, misses et évictions en JSON.
+///
+/// La clé est décodée des `%XX` du chemin. `handle` ne dépend d'aucun
+/// framework: le service lui passe la méthode, le chemin relatif au point
+/// de montage et le corps. `HttpServer` sert les mêmes routes seul.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::{HttpEndpoint, SyncLruCache};
+///
+/// let cache = SyncLruCache::new(100);
+/// let endpoint = HttpEndpoint::new(cache.clone());
+///
+/// assert_eq!(endpoint.handle("PUT", "/cache/user%3A1", b"Ada").status, 201);
+/// assert_eq!(cache.get(&b"user:1".to_vec()), Some(b"Ada".to_vec()));
+/// assert_eq!(endpoint.handle("GET", "/cache/user%3A1", b"").body, b"Ada");
+/// assert_eq!(endpoint.handle("GET", "/cache/absente", b"").status, 404);
+/// ```
+#[derive(Clone)]
+pub struct HttpEndpoint {
+    cache: Cache,
+}
+
+impl HttpEndpoint {
+    pub fn new(cache: Cache) -> Self {
+        Self { cache }
+    }
+
+    /// Traite une requête; `path` est relatif au point de montage et peut
+    /// porter une chaîne de requête
+    pub fn handle(&self, method: &str, path: &str, body: &[u8]) -> HttpResponse {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        if path == "/stats" {
+            return match method {
+                "GET" => self.stats(),
+                _ => HttpResponse::text(405, "méthode non autorisée"),
+            };
+        }
+        let Some(key) = path.strip_prefix("/cache/") else {
+            return HttpResponse::text(404, "route inconnue");
+        };
+        let Some(key) = percent_decode(key).filter(|key| !key.is_empty()) else {
+            return HttpResponse::text(400, "clé invalide");
+        };
+
+        match method {
+            "GET" => match self.cache.get(&key) {
+                Some(value) => HttpResponse::new(200, "application/octet-stream", value),
+                None => HttpResponse::text(404, "clé absente"),
+            },
+            "PUT" => {
+                let ttl = match query_param(query, "ttl").map(str::parse::<u64>) {
+                    None => None,
+                    Some(Ok(secs)) => Some(Duration::from_secs(secs)),
+                    Some(Err(_)) => return HttpResponse::text(400, "ttl invalide"),
+                };
+                let previous = match ttl {
+                    Some(ttl) => self.cache.put_with_ttl(key, body.to_vec(), ttl),
+                    None => self.cache.put(key, body.to_vec()),
+                };
+                HttpResponse::empty(if previous.is_some() { 204 } else { 201 })
+            }
+            "DELETE" => match self.cache.remove(&key) {
+                Some(_) => HttpResponse::empty(204),
+                None => HttpResponse::text(404, "clé absente"),
+            },
+            _ => HttpResponse::text(405, "méthode non autorisée"),
+        }
+    }
+
+    fn stats(&self) -> HttpResponse {
+        let stats = self.cache.stats();
+        let body = format!(
+            "{{\"entries\": {}, \"capacity\": {}, \"hits\": {}, \"misses\": {}, \"evictions\": {}}}\n",
+            self.cache.len(),
+            self.cache.capacity(),
+            stats.hits,
+            stats.misses,
+            stats.evictions
+        );
+        HttpResponse::new(200, "application/json", body)
+    }
+}
+
+/// Serveur HTTP/1.1 minimal pour les routes de `HttpEndpoint`
+///
+/// Connexions persistantes, corps annoncés par `Content-Length` (pas
+/// d'encodage `chunked`), une connexion par thread. Pour un outil annexe
+/// ou un tableau de bord, pas pour être exposé sur Internet.
+///
+/// ```no_run
+/// use lru_cache::{HttpServer, SyncLruCache};
+///
+/// let handle = HttpServer::bind("127.0.0.1:8080", SyncLruCache::new(1000))
+///     .unwrap()
+///     .spawn()
+///     .unwrap();
+/// // curl -X PUT --data 1 'http://127.0.0.1:8080/cache/a?ttl=60'
+/// // curl http://127.0.0.1:8080/stats
+/// handle.stop();
+/// ```
+pub struct HttpServer {
+    listener: TcpListener,
+    endpoint: HttpEndpoint,
+}
+
+impl HttpServer {
+    /// Écoute sur `addr` (port 0: port libre, voir `local_addr`)
+    pub fn bind(addr: impl ToSocketAddrs, cache: Cache) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            endpoint: HttpEndpoint::new(cache),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Sert les connexions sur le thread appelant, sans fin
+    pub fn run(self) {
+        let endpoint = self.endpoint;
+        server::serve(&self.listener, &AtomicBool::new(false), move |stream| {
+            connection(&endpoint, stream)
+        });
+    }
+
+    /// Sert les connexions sur un thread de fond, jusqu'à l'abandon du
+    /// `ServerHandle`
+    pub fn spawn(self) -> io::Result<ServerHandle> {
+        let endpoint = self.endpoint;
+        server::spawn(self.listener, move |stream| connection(&endpoint, stream))
+    }
+}
+
+fn connection(endpoint: &HttpEndpoint, stream: TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    handle(endpoint, reader, BufWriter::new(stream))
+}
+
+/// Sert les requêtes d'une connexion jusqu'à sa fermeture
+fn handle(
+    endpoint: &HttpEndpoint,
+    mut reader: impl BufRead,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    loop {
+        let Some(request_line) = read_line(&mut reader)? else {
+            return Ok(());
+        };
+        let mut parts = request_line.split(' ');
+        let (Some(method), Some(target), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return respond(
+                &mut writer,
+                &HttpResponse::text(400, "requête invalide"),
+                true,
+            );
+        };
+
+        let mut close = version == "HTTP/1.0";
+        let mut content_length = 0;
+        let mut chunked = false;
+        let mut expect_continue = false;
+        loop {
+            let Some(header) = read_line(&mut reader)? else {
+                return Ok(());
+            };
+            if header.is_empty() {
+                break;
+            }
+            let Some((name, value)) = header.split_once(':') else {
+                return respond(
+                    &mut writer,
+                    &HttpResponse::text(400, "en-tête invalide"),
+                    true,
+                );
+            };
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => match value.parse() {
+                    Ok(len) => content_length = len,
+                    Err(_) => {
+                        let response = HttpResponse::text(400, "Content-Length invalide");
+                        return respond(&mut writer, &response, true);
+                    }
+                },
+                "transfer-encoding" => chunked = true,
+                "connection" => close = value.eq_ignore_ascii_case("close"),
+                "expect" => expect_continue = value.eq_ignore_ascii_case("100-continue"),
+                _ => {}
+            }
+        }
+
+        if chunked {
+            return respond(
+                &mut writer,
+                &HttpResponse::text(411, "Content-Length requis"),
+                true,
+            );
+        }
+        if content_length > MAX_BODY_LEN {
+            return respond(
+                &mut writer,
+                &HttpResponse::text(413, "corps trop volumineux"),
+                true,
+            );
+        }
+        if expect_continue {
+            writer.write_all(b"HTTP/1.1 100 Continue\r\n\r\n")?;
+            writer.flush()?;
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        let response = endpoint.handle(method, target, &body);
+        respond(&mut writer, &response, close)?;
+        if close {
+            return Ok(());
+        }
+    }
+}
+
+fn respond(writer: &mut impl Write, response: &HttpResponse, close: bool) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len()
+    )?;
+    if close {
+        writer.write_all(b"Connection: close\r\n")?;
+    }
+    writer.write_all(b"\r\n")?;
+    writer.write_all(&response.body)?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        _ => "",
+    }
+}
+
+/// Ligne sans sa fin (`\r\n` ou `\n`); `None` à la fermeture de la
+/// connexion, erreur au-delà de `MAX_LINE_LEN`
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = Vec::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE_LEN as u64)
+        .read_until(b'\n', &mut line)?;
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let Some(line) = line.strip_suffix(b"\n") else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "ligne HTTP trop longue ou incomplète",
+        ));
+    };
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    Ok(Some(String::from_utf8_lossy(line).into_owned()))
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
+/// Décode les `%XX`; `None` si une séquence est invalide
+fn percent_decode(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = text.bytes();
+    let mut decoded = Vec::with_capacity(text.len());
+    while let Some(byte) = bytes.next() {
+        if byte != b'%' {
+            decoded.push(byte);
+            continue;
+        }
+        let hex = [bytes.next()?, bytes.next()?];
+        let hex = std::str::from_utf8(&hex).ok()?;
+        decoded.push(u8::from_str_radix(hex, 16).ok()?);
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        let cache = SyncLruCache::new(10);
+        let endpoint = HttpEndpoint::new(cache.clone());
+
+        assert_eq!(endpoint.handle("PUT", "/cache/a?ttl=60", b"1").status, 201);
+        assert!(cache.time_to_live(&b"a".to_vec()).is_some());
+        assert_eq!(endpoint.handle("PUT", "/cache/a", b"2").status, 204);
+        assert_eq!(endpoint.handle("PUT", "/cache/a?ttl=x", b"3").status, 400);
+        assert_eq!(endpoint.handle("GET", "/cache/a", b"").body, b"2");
+        assert_eq!(endpoint.handle("POST", "/cache/a", b"").status, 405);
+        assert_eq!(endpoint.handle("GET", "/cache/%zz", b"").status, 400);
+        assert_eq!(endpoint.handle("GET", "/autre", b"").status, 404);
+        assert_eq!(endpoint.handle("DELETE", "/cache/a", b"").status, 204);
+        assert_eq!(endpoint.handle("DELETE", "/cache/a", b"").status, 404);
+
+        let stats = endpoint.handle("GET", "/stats", b"");
+        assert_eq!(stats.content_type, "application/json");
+        assert_eq!(
+            String::from_utf8(stats.body).unwrap(),
+            "{\"entries\": 0, \"capacity\": 10, \"hits\": 1, \"misses\": 0, \"evictions\": 0}\n"
+        );
+    }
+
+    #[test]
+    fn test_http_connection() {
+        let endpoint = HttpEndpoint::new(SyncLruCache::new(10));
+        let requests: &[u8] = b"PUT /cache/k HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello\
+            GET /cache/k HTTP/1.1\r\nHost: x\r\n\r\n\
+            GET /cache/k HTTP/1.1\r\nConnection: close\r\n\r\n\
+            GET /cache/k HTTP/1.1\r\n\r\n";
+        let mut replies = Vec::new();
+        handle(&endpoint, requests, &mut replies).unwrap();
+
+        let replies = String::from_utf8(replies).unwrap();
+        let responses: Vec<&str> = replies.split("HTTP/1.1 ").skip(1).collect();
+        assert_eq!(responses.len(), 3);
+        assert!(responses[0].starts_with("201 Created\r\n"));
+        assert!(responses[1].ends_with("Content-Length: 5\r\n\r\nhello"));
+        assert!(responses[2].contains("Connection: close\r\n"));
+    }
+
+    #[test]
+    fn test_serves_http() {
+        let cache = SyncLruCache::new(10);
+        cache.put(b"a".to_vec(), b"1".to_vec());
+        let handle = HttpServer::bind("127.0.0.1:0", cache)
+            .unwrap()
+            .spawn()
+            .unwrap();
+
+        let mut stream = TcpStream::connect(handle.local_addr()).unwrap();
+        stream.write_all(b"GET /cache/a HTTP/1.0\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\n1"));
+
+        handle.stop();
+    }
+}
@@ -0,0 +1,429 @@
+//! Cache HTTP côté client selon Cache-Control, Expires et les validateurs
+//! (RFC 9111)
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::cache::LruCache;
+use crate::stats::CacheStats;
+
+/// Statuts qu'un cache peut garder sans durée de vie explicite
+/// (RFC 9110, section 15.1)
+const HEURISTIC_STATUSES: [u16; 12] = [200, 203, 204, 206, 300, 301, 308, 404, 405, 410, 414, 501];
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Réponse HTTP telle que la voit `HttpCache`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub status: u16,
+    /// Noms tels que reçus; la recherche ignore la casse
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    pub fn new(status: u16, headers: Vec<(String, String)>, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers,
+            body: body.into(),
+        }
+    }
+
+    /// Première valeur de l'en-tête `name`, sans tenir compte de la casse
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+struct Entry {
+    response: CachedResponse,
+    stored_at: Instant,
+    /// Durée de fraîcheur calculée à la réception
+    lifetime: Duration,
+    /// Âge de la réponse à la réception (`Age`, décalage de `Date`)
+    initial_age: Duration,
+}
+
+impl Entry {
+    fn new(response: CachedResponse) -> Self {
+        let now = SystemTime::now();
+        Self {
+            lifetime: freshness_lifetime(&response, now),
+            initial_age: initial_age(&response, now),
+            stored_at: Instant::now(),
+            response,
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.lifetime > self.initial_age + self.stored_at.elapsed()
+    }
+
+    /// En-têtes de la requête conditionnelle de revalidation
+    fn conditional_headers(&self) -> Vec<(String, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = self.response.header("ETag") {
+            headers.push(("If-None-Match".to_string(), etag.to_string()));
+        }
+        if let Some(modified) = self.response.header("Last-Modified") {
+            headers.push(("If-Modified-Since".to_string(), modified.to_string()));
+        }
+        headers
+    }
+}
+
+/// Cache de réponses HTTP côté client, fraîcheur calculée selon la RFC 9111
+///
+/// La durée de fraîcheur vient de `Cache-Control: max-age`, sinon de
+/// `Expires` (relatif à `Date`), sinon d'une heuristique: 10 % de
+/// l'ancienneté de `Last-Modified` pour les statuts qui la permettent.
+/// L'âge tient compte de `Age` et du décalage de `Date`. Une réponse
+/// `no-store` ou portant `Vary` n'est pas gardée; `no-cache` est gardée
+/// mais revalidée à chaque utilisation.
+///
+/// Une réponse périmée qui porte un validateur (`ETag`, `Last-Modified`)
+/// est revalidée: `fetch` reçoit `If-None-Match`/`If-Modified-Since`, et
+/// un `304 Not Modified` met à jour ses en-têtes sans retransférer le
+/// corps. Une réponse périmée n'est jamais servie sans revalidation: si
+/// `fetch` échoue, l'erreur est renvoyée. Seules les requêtes `GET` sont
+/// concernées; les clones partagent le même contenu.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::{CachedResponse, HttpCache};
+///
+/// let cache = HttpCache::new(100);
+/// let fetch = |conditional: &[(String, String)]| -> Result<_, String> {
+///     assert!(conditional.is_empty());
+///     Ok(CachedResponse::new(
+///         200,
+///         vec![("Cache-Control".into(), "max-age=60".into())],
+///         "contenu",
+///     ))
+/// };
+///
+/// let first = cache.fetch("https://example.com/a", fetch).unwrap();
+/// let second = cache
+///     .fetch("https://example.com/a", |_| Err("pas d'appel réseau".to_string()))
+///     .unwrap();
+/// assert_eq!(first, second);
+/// ```
+#[derive(Clone)]
+pub struct HttpCache {
+    inner: Arc<Mutex<LruCache<String, Entry>>>,
+}
+
+impl HttpCache {
+    /// `capacity` réponses au plus
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Réponse en cache pour `url` si elle est encore fraîche
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        let mut cache = self.lock();
+        let entry = cache.get(url)?;
+        entry.is_fresh().then(|| entry.response.clone())
+    }
+
+    /// Réponse fraîche en cache, sinon celle de `fetch`
+    ///
+    /// `fetch` reçoit les en-têtes conditionnels à ajouter à la requête
+    /// (vides sans réponse à revalider). Le verrou n'est pas tenu pendant
+    /// `fetch`.
+    pub fn fetch<E, F>(&self, url: &str, fetch: F) -> Result<CachedResponse, E>
+    where
+        F: FnOnce(&[(String, String)]) -> Result<CachedResponse, E>,
+    {
+        let conditional = {
+            let mut cache = self.lock();
+            match cache.get(url) {
+                Some(entry) if entry.is_fresh() => return Ok(entry.response.clone()),
+                Some(entry) => entry.conditional_headers(),
+                None => Vec::new(),
+            }
+        };
+
+        let response = fetch(&conditional)?;
+        if response.status != 304 {
+            self.store(url, response.clone());
+            return Ok(response);
+        }
+
+        let mut cache = self.lock();
+        match cache.remove(url) {
+            Some(entry) => {
+                let updated = revalidated(entry.response, response);
+                self.insert(&mut cache, url, updated.clone());
+                Ok(updated)
+            }
+            // Évincée pendant la revalidation: le 304 est tout ce qu'on a
+            None => Ok(response),
+        }
+    }
+
+    /// Garde `response` pour `url` si elle peut l'être; retourne `true`
+    /// dans ce cas
+    pub fn store(&self, url: &str, response: CachedResponse) -> bool {
+        if !storable(&response) {
+            self.lock().remove(url);
+            return false;
+        }
+        self.insert(&mut self.lock(), url, response);
+        true
+    }
+
+    /// Retire la réponse de `url`, par exemple après un `POST` sur la
+    /// ressource
+    pub fn invalidate(&self, url: &str) -> bool {
+        self.lock().remove(url).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats()
+    }
+
+    /// Sans validateur, une réponse périmée ne sert plus à rien: elle
+    /// expire avec sa fraîcheur
+    fn insert(&self, cache: &mut LruCache<String, Entry>, url: &str, response: CachedResponse) {
+        let entry = Entry::new(response);
+        let validators = !entry.conditional_headers().is_empty();
+        let remaining = entry.lifetime.saturating_sub(entry.initial_age);
+        match validators {
+            true => cache.put(url.to_string(), entry),
+            false => cache.put_with_ttl(url.to_string(), entry, remaining),
+        };
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<String, Entry>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Réponse gardée mise à jour par les en-têtes d'un `304 Not Modified`
+fn revalidated(mut stored: CachedResponse, not_modified: CachedResponse) -> CachedResponse {
+    for (name, value) in not_modified.headers {
+        stored
+            .headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(&name));
+        stored.headers.push((name, value));
+    }
+    stored
+}
+
+fn storable(response: &CachedResponse) -> bool {
+    let directives = cache_control(response);
+    if has_directive(&directives, "no-store") || response.header("Vary").is_some() {
+        return false;
+    }
+    has_directive(&directives, "max-age")
+        || response.header("Expires").is_some()
+        || HEURISTIC_STATUSES.contains(&response.status)
+}
+
+/// Durée de fraîcheur (RFC 9111, section 4.2.1)
+fn freshness_lifetime(response: &CachedResponse, now: SystemTime) -> Duration {
+    let directives = cache_control(response);
+    if has_directive(&directives, "no-cache") {
+        return Duration::ZERO;
+    }
+    let max_age = directives
+        .iter()
+        .find(|(name, _)| name == "max-age")
+        .and_then(|(_, value)| value.as_deref()?.parse::<u64>().ok());
+    if let Some(max_age) = max_age {
+        return Duration::from_secs(max_age);
+    }
+
+    let date = response
+        .header("Date")
+        .and_then(parse_http_date)
+        .unwrap_or(now);
+    if let Some(expires) = response.header("Expires") {
+        // Une date invalide vaut une date passée
+        return parse_http_date(expires)
+            .and_then(|expires| expires.duration_since(date).ok())
+            .unwrap_or(Duration::ZERO);
+    }
+    let last_modified = response.header("Last-Modified").and_then(parse_http_date);
+    match last_modified {
+        Some(modified) if HEURISTIC_STATUSES.contains(&response.status) => {
+            date.duration_since(modified).unwrap_or(Duration::ZERO) / 10
+        }
+        _ => Duration::ZERO,
+    }
+}
+
+/// Âge à la réception (RFC 9111, section 4.2.3)
+fn initial_age(response: &CachedResponse, now: SystemTime) -> Duration {
+    let age = response
+        .header("Age")
+        .and_then(|age| age.trim().parse::<u64>().ok())
+        .map_or(Duration::ZERO, Duration::from_secs);
+    let apparent_age = response
+        .header("Date")
+        .and_then(parse_http_date)
+        .and_then(|date| now.duration_since(date).ok())
+        .unwrap_or(Duration::ZERO);
+    age.max(apparent_age)
+}
+
+/// Directives `Cache-Control`, noms en minuscules, valeurs sans guillemets
+fn cache_control(response: &CachedResponse) -> Vec<(String, Option<String>)> {
+    response
+        .headers
+        .iter()
+        .filter(|(name, _)| name.eq_ignore_ascii_case("Cache-Control"))
+        .flat_map(|(_, value)| value.split(','))
+        .map(str::trim)
+        .filter(|directive| !directive.is_empty())
+        .map(|directive| match directive.split_once('=') {
+            Some((name, value)) => (
+                name.trim().to_ascii_lowercase(),
+                Some(value.trim().trim_matches('"').to_string()),
+            ),
+            None => (directive.to_ascii_lowercase(), None),
+        })
+        .collect()
+}
+
+fn has_directive(directives: &[(String, Option<String>)], name: &str) -> bool {
+    directives.iter().any(|(directive, _)| directive == name)
+}
+
+/// Date HTTP au format IMF-fixdate (`Sun, 06 Nov 1994 08:49:37 GMT`)
+fn parse_http_date(text: &str) -> Option<SystemTime> {
+    let fields: Vec<&str> = text.split_ascii_whitespace().collect();
+    let [_, day, month, year, time, "GMT"] = fields.as_slice() else {
+        return None;
+    };
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|name| name == month)? as u64 + 1;
+    let year: i64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (clock.next()??, clock.next()??, clock.next()??);
+    if !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    let secs = days * 86_400 + hours * 3600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Jours depuis le 1er janvier 1970 du calendrier grégorien
+fn days_from_civil(year: i64, month: u64, day: u64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year as i64;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, headers: &[(&str, &str)], body: &str) -> CachedResponse {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        CachedResponse::new(status, headers, body)
+    }
+
+    #[test]
+    fn test_revalidation() {
+        let cache = HttpCache::new(10);
+        let url = "https://example.com/a";
+        let stale = response(
+            200,
+            &[("Cache-Control", "no-cache"), ("ETag", "\"v1\"")],
+            "corps",
+        );
+        cache.fetch(url, |_| Ok::<_, ()>(stale.clone())).unwrap();
+        assert!(cache.get(url).is_none());
+
+        let revalidated = cache
+            .fetch(url, |conditional| {
+                assert_eq!(
+                    conditional,
+                    [("If-None-Match".to_string(), "\"v1\"".to_string())]
+                );
+                Ok::<_, ()>(response(304, &[("cache-control", "max-age=60")], ""))
+            })
+            .unwrap();
+        assert_eq!(revalidated.status, 200);
+        assert_eq!(revalidated.body, b"corps");
+        assert_eq!(revalidated.header("Cache-Control"), Some("max-age=60"));
+        assert_eq!(cache.get(url), Some(revalidated));
+    }
+
+    #[test]
+    fn test_storable() {
+        let cache = HttpCache::new(10);
+        assert!(!cache.store("a", response(200, &[("Cache-Control", "no-store")], "")));
+        assert!(!cache.store("a", response(200, &[("Vary", "Accept")], "")));
+        assert!(!cache.store("a", response(500, &[], "")));
+        assert!(cache.store("a", response(500, &[("Cache-Control", "max-age=5")], "")));
+        // Ni fraîcheur ni validateur: expire aussitôt
+        assert!(cache.store("b", response(200, &[], "")));
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_freshness_lifetime() {
+        let now = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+        assert_eq!(
+            now.duration_since(UNIX_EPOCH).unwrap().as_secs(),
+            784_111_777
+        );
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+
+        let expires = response(
+            200,
+            &[
+                ("Date", "Sun, 06 Nov 1994 08:49:37 GMT"),
+                ("Expires", "Sun, 06 Nov 1994 09:49:37 GMT"),
+            ],
+            "",
+        );
+        assert_eq!(freshness_lifetime(&expires, now), Duration::from_secs(3600));
+        let invalid = response(200, &[("Expires", "0")], "");
+        assert_eq!(freshness_lifetime(&invalid, now), Duration::ZERO);
+
+        let heuristic = response(
+            200,
+            &[("Last-Modified", "Sun, 06 Nov 1994 07:49:37 GMT")],
+            "",
+        );
+        assert_eq!(
+            freshness_lifetime(&heuristic, now),
+            Duration::from_secs(360)
+        );
+
+        let aged = response(200, &[("Age", "30"), ("Cache-Control", "max-age=20")], "");
+        assert!(!Entry::new(aged).is_fresh());
+    }
+}
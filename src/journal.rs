@@ -0,0 +1,159 @@
+//! Enregistrements du journal d'écritures d'un `PersistentLruCache`
+
+use crate::checksum::crc32;
+use crate::error::CacheError;
+use crate::report::{InvalidRecord, Recovery};
+use crate::stats::CacheStats;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Record {
+    Put(Vec<u8>, Vec<u8>),
+    Remove(Vec<u8>),
+    Stats(CacheStats),
+}
+
+const PUT: u8 = b'P';
+const REMOVE: u8 = b'R';
+const STATS: u8 = b'S';
+
+/// Étiquette d'un octet, champs préfixés par leur longueur, puis CRC-32 de
+/// l'enregistrement (entiers petit-boutistes)
+pub(crate) fn encode(record: &Record, out: &mut Vec<u8>) {
+    let start = out.len();
+    match record {
+        Record::Put(key, value) => {
+            out.push(PUT);
+            write_chunk(out, key);
+            write_chunk(out, value);
+        }
+        Record::Remove(key) => {
+            out.push(REMOVE);
+            write_chunk(out, key);
+        }
+        Record::Stats(stats) => {
+            out.push(STATS);
+            for counter in [stats.hits, stats.misses, stats.evictions] {
+                out.extend_from_slice(&counter.to_le_bytes());
+            }
+        }
+    }
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_le_bytes());
+}
+
+/// Décode les enregistrements dans l'ordre d'écriture
+///
+/// Un arrêt pendant un ajout laisse un dernier enregistrement incomplet,
+/// ignoré; un enregistrement complet mais invalide est confié à `recovery`.
+/// Une somme de contrôle fausse n'écarte que son enregistrement; une
+/// étiquette inconnue arrête la lecture, la suite ne pouvant être découpée.
+pub(crate) fn decode(bytes: &[u8], recovery: &mut Recovery) -> Result<Vec<Record>, CacheError> {
+    let mut records = Vec::new();
+    let mut rest = bytes;
+    let mut index = 0;
+    while !rest.is_empty() {
+        let offset = bytes.len() - rest.len();
+        let invalid = || InvalidRecord::new(index, "journal: enregistrement invalide").at(offset);
+
+        let tag = rest[0];
+        if ![PUT, REMOVE, STATS].contains(&tag) {
+            recovery.skip(invalid())?;
+            break;
+        }
+        let Some((record, tail)) = decode_fields(tag, &rest[1..]) else {
+            break;
+        };
+        let len = rest.len() - tail.len();
+        let crc = tail
+            .get(..4)
+            .map(|crc| u32::from_le_bytes(crc.try_into().unwrap()));
+        match crc {
+            None => break,
+            Some(crc) if crc == crc32(&rest[..len]) => records.push(record),
+            Some(_) => recovery.skip(invalid())?,
+        }
+        rest = &tail[4..];
+        index += 1;
+    }
+    Ok(records)
+}
+
+/// `None` si l'enregistrement est tronqué
+fn decode_fields(tag: u8, rest: &[u8]) -> Option<(Record, &[u8])> {
+    match tag {
+        PUT => {
+            let (key, rest) = read_chunk(rest)?;
+            let (value, rest) = read_chunk(rest)?;
+            Some((Record::Put(key.to_vec(), value.to_vec()), rest))
+        }
+        REMOVE => {
+            let (key, rest) = read_chunk(rest)?;
+            Some((Record::Remove(key.to_vec()), rest))
+        }
+        STATS => {
+            let (hits, rest) = read_u64(rest)?;
+            let (misses, rest) = read_u64(rest)?;
+            let (evictions, rest) = read_u64(rest)?;
+            let stats = CacheStats {
+                hits,
+                misses,
+                evictions,
+            };
+            Some((Record::Stats(stats), rest))
+        }
+        _ => None,
+    }
+}
+
+fn write_chunk(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn read_chunk(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let len = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+    let rest = &bytes[4..];
+    (rest.len() >= len).then(|| rest.split_at(len))
+}
+
+fn read_u64(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let value = u64::from_le_bytes(bytes.get(..8)?.try_into().ok()?);
+    Some((value, &bytes[8..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_torn_tail_is_ignored() {
+        let records = vec![
+            Record::Stats(CacheStats::default()),
+            Record::Put(b"a".to_vec(), b"1".to_vec()),
+            Record::Remove(b"a".to_vec()),
+        ];
+        let mut bytes = Vec::new();
+        for record in &records {
+            encode(record, &mut bytes);
+        }
+
+        let strict = || Recovery::strict();
+        assert_eq!(decode(&bytes, &mut strict()).unwrap(), records);
+        assert_eq!(
+            decode(&bytes[..bytes.len() - 1], &mut strict()).unwrap(),
+            records[..2]
+        );
+
+        bytes[34] ^= 1; // clé du deuxième enregistrement
+        assert!(matches!(
+            decode(&bytes, &mut strict()),
+            Err(CacheError::InvalidRecord(_))
+        ));
+
+        let mut lenient = Recovery::new(true);
+        let kept = decode(&bytes, &mut lenient).unwrap();
+        assert_eq!(kept, vec![records[0].clone(), records[2].clone()]);
+        let skipped = &lenient.into_report().skipped[0];
+        assert_eq!((skipped.index, skipped.offset), (1, Some(29)));
+    }
+}
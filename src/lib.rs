@@ -4,10 +4,115 @@
 //!
 //! Le cache évince automatiquement les éléments les moins récemment utilisés.
 
+// Seule l'interface C (feature `ffi`) a besoin de code `unsafe`
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+
+mod async_cache;
+mod async_loader;
+mod async_persistent;
+mod background;
+mod base64;
+mod blob;
+mod builder;
 mod cache;
+mod checksum;
+mod codec;
+mod directory;
+mod error;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod format;
+#[cfg(feature = "http")]
+mod http;
+mod http_cache;
+mod journal;
+mod json;
+mod loader;
+#[cfg(feature = "testing")]
+mod testing;
 mod trait_cache;
+mod transform;
+mod write_behind;
+mod writer;
+mod maintenance;
+#[cfg(feature = "memcached")]
+mod memcached;
+#[cfg(feature = "memcached")]
+mod memcached_server;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod namespace;
+mod negative;
 mod persistent;
+#[cfg(feature = "redis")]
+mod rdb;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "redis")]
+mod redis_server;
+mod remote;
+mod report;
+mod response_cache;
+mod retry;
+mod sampled;
+#[cfg(any(feature = "memcached", feature = "redis", feature = "http"))]
+mod server;
+mod shard_files;
+mod sharded;
+mod snapshot;
+mod stats;
+mod sync;
+mod sync_persistent;
+mod tiered;
 
-pub use cache::LruCache;
+pub use async_cache::{block_on, AsyncLruCache, EntryStream};
+pub use async_loader::{AsyncCacheLoader, AsyncLoadingCache, LoadError};
+pub use async_persistent::AsyncPersistentLruCache;
+pub use background::BackgroundPersistentLruCache;
+pub use blob::{BlobCache, BlobWriter};
+pub use builder::CacheBuilder;
+pub use cache::{CapacityExceeded, EntryInfo, LruCache, VersionConflict};
+pub use codec::Persistable;
+pub use error::CacheError;
+pub use format::Format;
+#[cfg(feature = "http")]
+pub use http::{HttpEndpoint, HttpResponse, HttpServer};
+pub use loader::{CacheLoader, LoadingCache};
 pub use trait_cache::CacheOps;
-pub use persistent::PersistentLruCache;
+#[cfg(feature = "compression")]
+pub use transform::LzCompression;
+pub use transform::Transform;
+pub use write_behind::WriteBehindCache;
+pub use writer::{CacheWriter, WriteThroughCache};
+#[cfg(feature = "memcached")]
+pub use memcached::MemcachedStore;
+#[cfg(feature = "memcached")]
+pub use memcached_server::MemcachedServer;
+pub use maintenance::{Maintain, MaintenanceHandle, Watermarks};
+#[cfg(feature = "redis")]
+pub use redis::RedisStore;
+#[cfg(feature = "redis")]
+pub use redis_server::RedisServer;
+#[cfg(any(feature = "memcached", feature = "redis", feature = "http"))]
+pub use server::ServerHandle;
+pub use remote::{RemoteStore, RemoteTieredCache};
+pub use namespace::{Namespace, NamespacedCache};
+pub use negative::{Lookup, NegativeCache};
+pub use persistent::{Autosave, Durability, LockPolicy, PersistOptions, PersistentLruCache};
+pub use report::{InvalidRecord, LoadReport, WarmReport};
+pub use response_cache::ResponseCache;
+pub use http_cache::{CachedResponse, HttpCache};
+pub use retry::{Retry, RetryPolicy};
+#[cfg(feature = "testing")]
+pub use testing::{Arbitrary, LruModel, Operation, Outcome, Unstructured};
+pub use sampled::{SampledLruCache, DEFAULT_SAMPLE_SIZE};
+pub use sharded::{ShardStats, ShardedLruCache};
+pub use snapshot::CacheSnapshot;
+pub use stats::CacheStats;
+pub use sync::{
+    CasError, FrozenError, FrozenWrites, ReentrantLoadError, SyncLruCache, Transaction,
+};
+pub use sync_persistent::SyncPersistentLruCache;
+pub use tiered::TieredCache;
@@ -0,0 +1,490 @@
+//! Cache en lecture directe: les clés absentes sont chargées à la demande
+
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::retry::{Retry, RetryPolicy};
+use crate::stats::CacheStats;
+use crate::sync::SyncLruCache;
+
+/// Source des valeurs d'un `LoadingCache`
+///
+/// Implémenté pour toute closure `Fn(&K) -> Result<V, E>`.
+pub trait CacheLoader<K, V> {
+    type Error;
+
+    fn load(&self, key: &K) -> Result<V, Self::Error>;
+
+    /// Retente les chargements en échec selon `policy`
+    fn with_retry(self, policy: RetryPolicy) -> Retry<Self>
+    where
+        Self: Sized,
+    {
+        Retry::new(self, policy)
+    }
+}
+
+impl<K, V, E, F> CacheLoader<K, V> for F
+where
+    F: Fn(&K) -> Result<V, E>,
+{
+    type Error = E;
+
+    fn load(&self, key: &K) -> Result<V, E> {
+        self(key)
+    }
+}
+
+/// Cache qui charge lui-même les clés absentes (read-through)
+///
+/// `get` retourne la valeur en cache ou appelle le `CacheLoader` fourni à
+/// la construction, puis garde son résultat. Deux appels concurrents pour
+/// la même clé n'appellent le chargeur qu'une fois (voir
+/// `SyncLruCache::get_or_insert_with`); un échec n'est pas mis en cache.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::LoadingCache;
+///
+/// let users = LoadingCache::new(100, |id: &u32| match id {
+///     1 => Ok("alice".to_string()),
+///     _ => Err(format!("utilisateur {} inconnu", id)),
+/// });
+///
+/// assert_eq!(users.get(&1), Ok("alice".to_string()));
+/// assert!(users.get(&2).is_err());
+/// assert_eq!(users.len(), 1);
+/// ```
+///
+/// Avec `expire_after`, les valeurs chargées expirent; `refresh_ahead`
+/// les recharge en fond avant leur expiration, pour les clés assez lues.
+/// `cache_errors` retient aussi les échecs, pour ménager une source en
+/// panne.
+pub struct LoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    L: CacheLoader<K, V>,
+{
+    cache: SyncLruCache<K, V>,
+    loader: Arc<L>,
+    ttl: Option<Duration>,
+    refresh: Option<RefreshAhead<K>>,
+    errors: Option<ErrorCache<K, L::Error>>,
+}
+
+/// Échecs de chargement récents, rejoués sans rappeler le chargeur
+struct ErrorCache<K, E>
+where
+    K: Hash + Eq + Clone,
+{
+    errors: SyncLruCache<K, E>,
+    ttl: Duration,
+    /// `E::clone`, fixé par `cache_errors`
+    replay: fn(&E) -> E,
+}
+
+impl<K, E> Clone for ErrorCache<K, E>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            errors: self.errors.clone(),
+            ttl: self.ttl,
+            replay: self.replay,
+        }
+    }
+}
+
+/// Rechargement anticipé des entrées lues souvent
+struct RefreshAhead<K> {
+    /// Durée de vie restante sous laquelle l'entrée est rechargée
+    window: Duration,
+    /// Lectures minimales depuis l'insertion pour qu'une clé soit chaude
+    min_hits: u64,
+    /// Lance le rechargement d'une clé sur un thread
+    spawn: Arc<dyn Fn(K) + Send + Sync>,
+}
+
+impl<K> Clone for RefreshAhead<K> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window,
+            min_hits: self.min_hits,
+            spawn: Arc::clone(&self.spawn),
+        }
+    }
+}
+
+impl<K, V, L> Clone for LoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    L: CacheLoader<K, V>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+            loader: Arc::clone(&self.loader),
+            ttl: self.ttl,
+            refresh: self.refresh.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+}
+
+impl<K, V, L> LoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    L: CacheLoader<K, V>,
+{
+    pub fn new(capacity: usize, loader: L) -> Self {
+        Self::with_cache(SyncLruCache::new(capacity), loader)
+    }
+
+    /// Charge les clés absentes de `cache`, partagé avec ses autres clones
+    pub fn with_cache(cache: SyncLruCache<K, V>, loader: L) -> Self {
+        Self {
+            cache,
+            loader: Arc::new(loader),
+            ttl: None,
+            refresh: None,
+            errors: None,
+        }
+    }
+
+    /// Les valeurs chargées expirent après `ttl`
+    pub fn expire_after(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Retient chaque échec de chargement pendant `ttl`
+    ///
+    /// Pendant ce délai, `get` renvoie une copie de l'erreur sans rappeler
+    /// le chargeur. `put` et `invalidate` oublient l'échec de leur clé.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LoadingCache;
+    /// use std::cell::Cell;
+    /// use std::time::Duration;
+    ///
+    /// let calls = Cell::new(0);
+    /// let cache = LoadingCache::new(100, |_: &u32| {
+    ///     calls.set(calls.get() + 1);
+    ///     Err::<String, _>("base indisponible".to_string())
+    /// })
+    /// .cache_errors(Duration::from_secs(1));
+    ///
+    /// assert!(cache.get(&1).is_err());
+    /// assert!(cache.get(&1).is_err());
+    /// assert_eq!(calls.get(), 1);
+    /// ```
+    pub fn cache_errors(mut self, ttl: Duration) -> Self
+    where
+        L::Error: Clone,
+    {
+        self.errors = Some(ErrorCache {
+            errors: SyncLruCache::new(self.cache.capacity()),
+            ttl,
+            replay: L::Error::clone,
+        });
+        self
+    }
+
+    /// Valeur en cache, sinon chargée puis mise en cache
+    pub fn get(&self, key: &K) -> Result<V, L::Error> {
+        if let Some(errors) = &self.errors {
+            if let Some(err) = errors.errors.peek_arc(key) {
+                return Err((errors.replay)(&err));
+            }
+        }
+        let load = || {
+            self.loader.load(key).inspect_err(|err| {
+                if let Some(errors) = &self.errors {
+                    let err = (errors.replay)(err);
+                    errors.errors.put_with_ttl(key.clone(), err, errors.ttl);
+                }
+            })
+        };
+        let value = match self.ttl {
+            Some(ttl) => self
+                .cache
+                .get_or_try_insert_with_ttl(key.clone(), ttl, load)?,
+            None => self.cache.get_or_try_insert_with(key.clone(), load)?,
+        };
+        if let Some(refresh) = &self.refresh {
+            let hot = self.cache.hit_count(key) >= Some(refresh.min_hits);
+            let expiring = self
+                .cache
+                .time_to_live(key)
+                .is_some_and(|ttl| ttl <= refresh.window);
+            if hot && expiring {
+                (refresh.spawn)(key.clone());
+            }
+        }
+        Ok(value)
+    }
+
+    /// Valeur en cache uniquement, sans appeler le chargeur
+    pub fn get_if_present(&self, key: &K) -> Option<V> {
+        self.cache.get(key)
+    }
+
+    pub fn put(&self, key: K, value: V) {
+        self.forget_error(&key);
+        self.cache.put(key, value);
+    }
+
+    /// Oublie une clé: le prochain `get` la rechargera
+    pub fn invalidate(&self, key: &K) {
+        self.forget_error(key);
+        self.cache.remove(key);
+    }
+
+    /// Oublie un lot de clés sous un seul verrou, par exemple à la réception
+    /// d'un message d'invalidation; renvoie le nombre de clés retirées
+    pub fn invalidate_many<I>(&self, keys: I) -> usize
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        for key in &keys {
+            self.forget_error(key);
+        }
+        self.cache.remove_many(keys).len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Charge tout de suite les clés absentes de `keys`
+    ///
+    /// Les clés déjà en cache ne sont pas rechargées; les échecs sont
+    /// ignorés (et retenus avec `cache_errors`). Retourne le nombre de clés
+    /// chargées.
+    pub fn prefetch_now<I>(&self, keys: I) -> usize
+    where
+        I: IntoIterator<Item = K>,
+    {
+        keys.into_iter()
+            .filter(|key| !self.cache.contains_key(key) && self.get(key).is_ok())
+            .count()
+    }
+
+    /// Cache sous-jacent
+    pub fn cache(&self) -> &SyncLruCache<K, V> {
+        &self.cache
+    }
+
+    fn forget_error(&self, key: &K) {
+        if let Some(errors) = &self.errors {
+            errors.errors.remove(key);
+        }
+    }
+}
+
+impl<K, V, L> LoadingCache<K, V, L>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    L: CacheLoader<K, V> + Send + Sync + 'static,
+{
+    /// Comme `prefetch_now`, sur un thread de fond: l'appelant n'attend pas
+    ///
+    /// Le résultat du thread est le nombre de clés chargées.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LoadingCache;
+    ///
+    /// let pages = LoadingCache::new(100, |id: &u32| Ok::<_, ()>(format!("page {}", id)));
+    ///
+    /// // Avant le pic de trafic attendu
+    /// let prefetch = pages.prefetch(1..=10);
+    /// assert_eq!(prefetch.join().unwrap(), 10);
+    /// assert_eq!(pages.get_if_present(&3), Some("page 3".to_string()));
+    /// ```
+    pub fn prefetch<I>(&self, keys: I) -> JoinHandle<usize>
+    where
+        I: IntoIterator<Item = K>,
+        L::Error: Send + Sync,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        let cache = self.clone();
+        thread::spawn(move || cache.prefetch_now(keys))
+    }
+
+    /// Recharge en fond les entrées lues au moins `min_hits` fois dont la
+    /// durée de vie restante passe sous `window`
+    ///
+    /// Le `get` qui le détecte retourne la valeur en cache sans attendre;
+    /// un seul rechargement à la fois par clé. Si le chargeur échoue,
+    /// l'entrée garde sa valeur jusqu'à son expiration. Sans effet sans
+    /// `expire_after`.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::LoadingCache;
+    /// use std::time::Duration;
+    ///
+    /// let rates = LoadingCache::new(100, |currency: &String| {
+    ///     Ok::<_, String>(format!("taux de {}", currency))
+    /// })
+    /// .expire_after(Duration::from_secs(60))
+    /// .refresh_ahead(Duration::from_secs(10), 5);
+    ///
+    /// assert!(rates.get(&"EUR".to_string()).is_ok());
+    /// ```
+    pub fn refresh_ahead(mut self, window: Duration, min_hits: u64) -> Self {
+        let cache = self.cache.clone();
+        let loader = Arc::clone(&self.loader);
+        let ttl = self.ttl;
+        let running = Arc::new(Mutex::new(HashSet::new()));
+
+        let spawn = move |key: K| {
+            let Some(ttl) = ttl else { return };
+            if !running
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(key.clone())
+            {
+                return;
+            }
+            let (cache, loader, running) =
+                (cache.clone(), Arc::clone(&loader), Arc::clone(&running));
+            thread::spawn(move || {
+                if let Ok(value) = loader.load(&key) {
+                    cache.put_with_ttl(key.clone(), value, ttl);
+                }
+                running
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&key);
+            });
+        };
+
+        self.refresh = Some(RefreshAhead {
+            window,
+            min_hits,
+            spawn: Arc::new(spawn),
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_loads_once() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let cache = LoadingCache::new(2, move |key: &i32| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, ()>(key * 10)
+        });
+
+        assert_eq!(cache.get(&1), Ok(10));
+        assert_eq!(cache.get(&1), Ok(10));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        cache.invalidate(&1);
+        assert_eq!(cache.get_if_present(&1), None);
+        assert_eq!(cache.get(&1), Ok(10));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_prefetch_loads_missing_keys_only() {
+        let loaded = Arc::new(Mutex::new(Vec::new()));
+        let log = Arc::clone(&loaded);
+        let cache = LoadingCache::new(10, move |key: &i32| {
+            log.lock().unwrap().push(*key);
+            match key {
+                6 => Err("introuvable"),
+                _ => Ok(key * 10),
+            }
+        });
+        cache.put(2, 20);
+        cache.put(4, 40);
+
+        assert_eq!(cache.prefetch(1..=6).join().unwrap(), 3);
+        assert_eq!(*loaded.lock().unwrap(), vec![1, 3, 5, 6]);
+        assert_eq!(cache.get_if_present(&2), Some(20));
+        assert_eq!(cache.len(), 5);
+
+        // Tout est en cache sauf l'échec, seul rechargé
+        assert_eq!(cache.prefetch_now(1..=6), 0);
+        assert_eq!(*loaded.lock().unwrap(), vec![1, 3, 5, 6, 6]);
+    }
+
+    #[test]
+    fn test_refresh_ahead() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let cache = LoadingCache::new(2, move |_: &i32| {
+            Ok::<_, ()>(counter.fetch_add(1, Ordering::SeqCst))
+        })
+        .expire_after(Duration::from_millis(400))
+        .refresh_ahead(Duration::from_millis(300), 1);
+
+        assert_eq!(cache.get(&1), Ok(0));
+        assert_eq!(cache.get(&1), Ok(0)); // pas encore proche de l'expiration
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(cache.get(&1), Ok(0)); // rechargement lancé en fond
+
+        for _ in 0..100 {
+            if cache.get_if_present(&1) == Some(1) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // La première valeur aurait expiré; la nouvelle est toujours là
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(cache.cache().peek(&1), Some(1));
+    }
+
+    #[test]
+    fn test_cached_errors_expire() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let cache = LoadingCache::new(2, move |_: &i32| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Err::<i32, _>("panne")
+        })
+        .cache_errors(Duration::from_millis(30));
+
+        assert_eq!(cache.get(&1), Err("panne"));
+        assert_eq!(cache.get(&1), Err("panne"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        thread::sleep(Duration::from_millis(40));
+        assert_eq!(cache.get(&1), Err("panne"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        cache.put(1, 10);
+        assert_eq!(cache.get(&1), Ok(10));
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Seuils d'éviction en arrière-plan
+///
+/// Quand le cache dépasse `high` entrées, la maintenance évince les moins
+/// récentes jusqu'à revenir à `low`, pour que les insertions aient rarement
+/// à évincer elles-mêmes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watermarks {
+    pub high: usize,
+    pub low: usize,
+}
+
+/// Cache capable d'exécuter une passe de maintenance
+///
+/// Implémenté par les caches concurrents; une passe retire les entrées
+/// expirées et applique les seuils d'éviction. Pour
+/// `SyncPersistentLruCache`, elle sauvegarde aussi les écritures en
+/// attente.
+pub trait Maintain: Clone + Send + 'static {
+    fn run_maintenance(&self, watermarks: Option<Watermarks>);
+
+    /// Lance une passe de maintenance toutes les `interval` dans un thread
+    ///
+    /// La tâche s'arrête quand le `MaintenanceHandle` retourné est abandonné.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::{Maintain, SyncLruCache};
+    /// use std::time::Duration;
+    ///
+    /// let cache = SyncLruCache::new(100);
+    /// cache.put_with_ttl(1, "a", Duration::ZERO);
+    ///
+    /// let handle = cache.spawn_maintenance(Duration::from_millis(10));
+    /// std::thread::sleep(Duration::from_millis(50));
+    /// drop(handle);
+    ///
+    /// assert!(cache.is_empty());
+    /// ```
+    fn spawn_maintenance(&self, interval: Duration) -> MaintenanceHandle {
+        spawn(self.clone(), interval, None)
+    }
+
+    /// Comme `spawn_maintenance`, en appliquant aussi les seuils d'éviction
+    fn spawn_maintenance_with(
+        &self,
+        interval: Duration,
+        watermarks: Watermarks,
+    ) -> MaintenanceHandle {
+        spawn(self.clone(), interval, Some(watermarks))
+    }
+}
+
+/// Tâche de maintenance en arrière-plan, arrêtée à l'abandon
+pub struct MaintenanceHandle {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MaintenanceHandle {
+    /// Arrête la tâche et attend la fin de la passe en cours
+    pub fn stop(self) {}
+}
+
+impl Drop for MaintenanceHandle {
+    fn drop(&mut self) {
+        // Fermer le canal réveille le thread immédiatement
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn spawn<C: Maintain>(
+    cache: C,
+    interval: Duration,
+    watermarks: Option<Watermarks>,
+) -> MaintenanceHandle {
+    let (stop, stopped) = mpsc::channel::<()>();
+
+    let thread = thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+            cache.run_maintenance(watermarks);
+        }
+    });
+
+    MaintenanceHandle {
+        stop: Some(stop),
+        thread: Some(thread),
+    }
+}
@@ -0,0 +1,236 @@
+//! Client memcached minimal (protocole texte) servant de `RemoteStore`
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::remote::RemoteStore;
+
+/// Au-delà, memcached lit la durée de vie comme une date Unix absolue
+pub(crate) const MAX_RELATIVE_TTL: u64 = 30 * 24 * 3600;
+
+/// Connexion à un serveur memcached, pour `RemoteTieredCache`
+///
+/// Pendant de `RedisStore`: n'utilise que `get`, `set` et `delete`, sur
+/// une seule connexion rouverte après une erreur réseau. Les clés doivent
+/// respecter les règles de memcached (250 octets au plus, sans espace ni
+/// caractère de contrôle); une autre clé est une erreur `InvalidInput`.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{MemcachedStore, RemoteTieredCache};
+///
+/// let memcached = MemcachedStore::connect("127.0.0.1:11211").unwrap();
+/// let cache: RemoteTieredCache<String, String, _> = RemoteTieredCache::new(1000, memcached);
+///
+/// cache.put("a".to_string(), "1".to_string()).unwrap();
+/// assert_eq!(cache.get(&"a".to_string()).unwrap(), Some("1".to_string()));
+/// ```
+pub struct MemcachedStore {
+    addrs: Vec<SocketAddr>,
+    timeout: Option<Duration>,
+    conn: Mutex<Option<Connection>>,
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl MemcachedStore {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::connect_timeout(addr, None)
+    }
+
+    /// Comme `connect`, chaque lecture ou écriture échouant après `timeout`
+    pub fn connect_timeout(
+        addr: impl ToSocketAddrs,
+        timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let store = Self {
+            addrs: addr.to_socket_addrs()?.collect(),
+            timeout,
+            conn: Mutex::new(None),
+        };
+        *store.conn() = Some(store.open()?);
+        Ok(store)
+    }
+
+    fn open(&self) -> io::Result<Connection> {
+        let writer = TcpStream::connect(&self.addrs[..])?;
+        writer.set_nodelay(true)?;
+        writer.set_read_timeout(self.timeout)?;
+        writer.set_write_timeout(self.timeout)?;
+        Ok(Connection {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Option<Connection>> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Envoie une requête et lit sa réponse avec `read`
+    fn call<T>(
+        &self,
+        request: &[u8],
+        read: impl FnOnce(&mut BufReader<TcpStream>) -> io::Result<T>,
+    ) -> io::Result<T> {
+        let mut conn = self.conn();
+        if conn.is_none() {
+            *conn = Some(self.open()?);
+        }
+        let open = conn.as_mut().expect("connexion ouverte");
+
+        let reply = open
+            .writer
+            .write_all(request)
+            .and_then(|()| read(&mut open.reader));
+        if reply
+            .as_ref()
+            .is_err_and(|err| err.kind() != io::ErrorKind::Other)
+        {
+            // Connexion dans un état inconnu: la rouvrir au prochain appel
+            *conn = None;
+        }
+        reply
+    }
+}
+
+impl RemoteStore for MemcachedStore {
+    fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        check_key(key)?;
+        let request = [b"get ", key, b"\r\n"].concat();
+        self.call(&request, read_value)
+    }
+
+    fn set(&self, key: &[u8], value: &[u8], ttl: Option<Duration>) -> io::Result<()> {
+        check_key(key)?;
+        let header = format!(" 0 {} {}\r\n", exptime(ttl), value.len());
+        let request = [b"set ", key, header.as_bytes(), value, b"\r\n"].concat();
+        self.call(&request, |reader| expect_line(reader, &[b"STORED"]))
+    }
+
+    fn delete(&self, key: &[u8]) -> io::Result<()> {
+        check_key(key)?;
+        let request = [b"delete ", key, b"\r\n"].concat();
+        self.call(&request, |reader| {
+            expect_line(reader, &[b"DELETED", b"NOT_FOUND"])
+        })
+    }
+}
+
+pub(crate) fn check_key(key: &[u8]) -> io::Result<()> {
+    let valid = !key.is_empty() && key.len() <= 250 && key.iter().all(|&b| b > b' ' && b != 0x7f);
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "clé invalide pour memcached",
+        ))
+    }
+}
+
+/// 0: pas d'expiration; au moins une seconde sinon
+fn exptime(ttl: Option<Duration>) -> u64 {
+    let Some(ttl) = ttl else { return 0 };
+    let secs = ttl.as_secs() + u64::from(ttl.subsec_nanos() > 0);
+    if secs <= MAX_RELATIVE_TTL {
+        return secs.max(1);
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    now.as_secs() + secs
+}
+
+/// `VALUE <clé> <flags> <octets>\r\n<données>\r\nEND\r\n`, ou `END\r\n`
+fn read_value(reader: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let line = read_line(reader)?;
+    if line == b"END" {
+        return Ok(None);
+    }
+    let header = String::from_utf8_lossy(&line).into_owned();
+    let fields: Vec<&str> = header.split(' ').collect();
+    let len = match fields.as_slice() {
+        ["VALUE", _, _, len, ..] => len.parse::<usize>().ok(),
+        _ => None,
+    };
+    let Some(len) = len else {
+        return Err(error_reply(header));
+    };
+
+    let mut value = vec![0; len + 2];
+    reader.read_exact(&mut value)?;
+    if !value.ends_with(b"\r\n") {
+        return Err(invalid("valeur memcached mal terminée"));
+    }
+    value.truncate(len);
+    expect_line(reader, &[b"END"])?;
+    Ok(Some(value))
+}
+
+fn expect_line(reader: &mut impl BufRead, expected: &[&[u8]]) -> io::Result<()> {
+    let line = read_line(reader)?;
+    if expected.contains(&line.as_slice()) {
+        Ok(())
+    } else {
+        Err(error_reply(String::from_utf8_lossy(&line).into_owned()))
+    }
+}
+
+/// `ERROR`, `CLIENT_ERROR ...` ou `SERVER_ERROR ...`: le serveur a
+/// répondu, la connexion reste utilisable; autre chose est illisible
+fn error_reply(line: String) -> io::Error {
+    if line == "ERROR" || line.starts_with("CLIENT_ERROR ") || line.starts_with("SERVER_ERROR ") {
+        io::Error::other(line)
+    } else {
+        invalid(format!("réponse memcached inattendue: {}", line))
+    }
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Ligne sans son `\r\n` final
+fn read_line(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line)?;
+    if !line.ends_with(b"\r\n") {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connexion memcached interrompue",
+        ));
+    }
+    line.truncate(line.len() - 2);
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_protocol() {
+        let mut replies: &[u8] =
+            b"VALUE k 0 4\r\na\r\nb\r\nEND\r\nEND\r\nSTORED\r\nSERVER_ERROR out of memory\r\n";
+        assert_eq!(read_value(&mut replies).unwrap(), Some(b"a\r\nb".to_vec()));
+        assert_eq!(read_value(&mut replies).unwrap(), None);
+        assert!(expect_line(&mut replies, &[b"STORED"]).is_ok());
+        let err = expect_line(&mut replies, &[b"STORED"]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+
+        assert!(check_key(b"user:1").is_ok());
+        assert!(check_key(b"avec espace").is_err());
+        assert!(check_key(&[b'k'; 251]).is_err());
+
+        assert_eq!(exptime(None), 0);
+        assert_eq!(exptime(Some(Duration::from_millis(1500))), 2);
+        assert!(exptime(Some(Duration::from_secs(MAX_RELATIVE_TTL + 1))) > MAX_RELATIVE_TTL);
+    }
+}
@@ -0,0 +1,281 @@
+//! Serveur memcached minimal (protocole texte) devant un `SyncLruCache`
+
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::memcached::{check_key, MAX_RELATIVE_TTL};
+use crate::server::{self, ServerHandle};
+use crate::sync::SyncLruCache;
+
+/// Taille maximale d'une valeur, comme la limite par défaut de memcached
+const MAX_VALUE_LEN: usize = 1024 * 1024;
+
+/// Longueur maximale d'une ligne de commande
+const MAX_LINE_LEN: usize = 2048;
+
+type Cache = SyncLruCache<Vec<u8>, Vec<u8>>;
+
+/// Expose un `SyncLruCache` aux clients memcached existants
+///
+/// Commandes prises en charge: `get`/`gets` (plusieurs clés), `set`, `add`,
+/// `replace`, `delete`, `stats`, `flush_all`, `version` et `quit`, avec
+/// `noreply`. La durée de vie (`exptime`) devient un TTL de l'entrée.
+/// Les flags ne sont pas conservés: un `set` avec des flags non nuls est
+/// refusé (`CLIENT_ERROR`) plutôt que relu avec des flags différents.
+///
+/// Le cache reste partagé avec le reste du processus: ses clones voient
+/// les écritures des clients et inversement. Chaque connexion est servie
+/// sur son propre thread.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{MemcachedServer, SyncLruCache};
+///
+/// let cache = SyncLruCache::new(10_000);
+/// let server = MemcachedServer::bind("127.0.0.1:11211", cache.clone()).unwrap();
+/// let handle = server.spawn().unwrap();
+///
+/// // telnet 127.0.0.1 11211
+/// // set a 0 0 1
+/// // 1
+/// // STORED
+/// handle.stop();
+/// ```
+pub struct MemcachedServer {
+    listener: TcpListener,
+    cache: Cache,
+}
+
+impl MemcachedServer {
+    /// Écoute sur `addr` (port 0: port libre, voir `local_addr`)
+    pub fn bind(addr: impl ToSocketAddrs, cache: Cache) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            cache,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Sert les connexions sur le thread appelant, sans fin
+    pub fn run(self) {
+        let cache = self.cache;
+        server::serve(&self.listener, &AtomicBool::new(false), move |stream| {
+            connection(&cache, stream)
+        });
+    }
+
+    /// Sert les connexions sur un thread de fond, jusqu'à l'abandon du
+    /// `ServerHandle`
+    pub fn spawn(self) -> io::Result<ServerHandle> {
+        let cache = self.cache;
+        server::spawn(self.listener, move |stream| connection(&cache, stream))
+    }
+}
+
+fn connection(cache: &Cache, stream: TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    handle(cache, reader, BufWriter::new(stream))
+}
+
+/// Traite les commandes jusqu'à `quit` ou la fermeture de la connexion
+fn handle(cache: &Cache, mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        (&mut reader)
+            .take(MAX_LINE_LEN as u64)
+            .read_until(b'\n', &mut line)?;
+        if !line.ends_with(b"\n") {
+            if line.len() >= MAX_LINE_LEN {
+                writer.write_all(b"CLIENT_ERROR ligne trop longue\r\n")?;
+                writer.flush()?;
+            }
+            return Ok(());
+        }
+        let text = String::from_utf8_lossy(&line);
+        let words: Vec<&str> = text.split_ascii_whitespace().collect();
+        let noreply = words.last() == Some(&"noreply");
+
+        let reply = match words.as_slice() {
+            ["get" | "gets", keys @ ..] if !keys.is_empty() => {
+                for key in keys {
+                    if let Some(value) = cache.get_arc(&key.as_bytes().to_vec()) {
+                        write!(writer, "VALUE {} 0 {}\r\n", key, value.len())?;
+                        writer.write_all(&value)?;
+                        writer.write_all(b"\r\n")?;
+                    }
+                }
+                "END".to_string()
+            }
+            [command @ ("set" | "add" | "replace"), key, flags, exptime, len, ..] => {
+                store(cache, &mut reader, command, key, flags, exptime, len)?
+            }
+            ["delete", key, ..] => match cache.remove(&key.as_bytes().to_vec()) {
+                Some(_) => "DELETED".to_string(),
+                None => "NOT_FOUND".to_string(),
+            },
+            ["stats"] => {
+                let stats = cache.stats();
+                let lines = [
+                    ("curr_items", cache.len() as u64),
+                    ("limit_items", cache.capacity() as u64),
+                    ("get_hits", stats.hits),
+                    ("get_misses", stats.misses),
+                    ("evictions", stats.evictions),
+                ];
+                for (name, value) in lines {
+                    write!(writer, "STAT {} {}\r\n", name, value)?;
+                }
+                "END".to_string()
+            }
+            ["flush_all", ..] => {
+                cache.shrink_to(0);
+                "OK".to_string()
+            }
+            ["version"] => format!("VERSION {}", env!("CARGO_PKG_VERSION")),
+            ["quit"] => return Ok(()),
+            _ => "ERROR".to_string(),
+        };
+        if !noreply {
+            writer.write_all(reply.as_bytes())?;
+            writer.write_all(b"\r\n")?;
+        }
+        writer.flush()?;
+    }
+}
+
+/// Lit le bloc de données d'une commande de stockage et l'applique;
+/// retourne la ligne de réponse
+fn store(
+    cache: &Cache,
+    reader: &mut impl BufRead,
+    command: &str,
+    key: &str,
+    flags: &str,
+    exptime: &str,
+    len: &str,
+) -> io::Result<String> {
+    let (Ok(flags), Ok(exptime), Ok(len)) = (
+        flags.parse::<u32>(),
+        exptime.parse::<i64>(),
+        len.parse::<usize>(),
+    ) else {
+        return Ok("CLIENT_ERROR ligne de commande invalide".to_string());
+    };
+    if len > MAX_VALUE_LEN {
+        io::copy(&mut reader.take(len as u64 + 2), &mut io::sink())?;
+        return Ok("SERVER_ERROR valeur trop grande pour le cache".to_string());
+    }
+    let mut data = vec![0; len + 2];
+    reader.read_exact(&mut data)?;
+    if !data.ends_with(b"\r\n") {
+        return Ok("CLIENT_ERROR bloc de données mal terminé".to_string());
+    }
+    data.truncate(len);
+    if check_key(key.as_bytes()).is_err() {
+        return Ok("CLIENT_ERROR clé invalide".to_string());
+    }
+    if flags != 0 {
+        return Ok("CLIENT_ERROR flags non supportés".to_string());
+    }
+
+    // `add` et `replace` testent puis écrivent: une écriture concurrente de
+    // la même clé peut s'intercaler
+    let key = key.as_bytes().to_vec();
+    let stored = match command {
+        "add" => !cache.contains_key(&key),
+        "replace" => cache.contains_key(&key),
+        _ => true,
+    };
+    if stored {
+        match ttl(exptime) {
+            Some(Some(ttl)) => cache.put_with_ttl(key, data, ttl),
+            Some(None) => cache.put(key, data),
+            None => cache.remove(&key),
+        };
+    }
+    Ok(if stored { "STORED" } else { "NOT_STORED" }.to_string())
+}
+
+/// `Some(None)`: pas d'expiration; `None`: déjà expirée
+fn ttl(exptime: i64) -> Option<Option<Duration>> {
+    let secs = match exptime {
+        0 => return Some(None),
+        secs if secs < 0 => return None,
+        secs if secs as u64 <= MAX_RELATIVE_TTL => secs as u64,
+        date => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            (date as u64)
+                .checked_sub(now.as_secs())
+                .filter(|&secs| secs > 0)?
+        }
+    };
+    Some(Some(Duration::from_secs(secs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memcached::MemcachedStore;
+    use crate::remote::RemoteStore;
+
+    fn session(cache: &Cache, requests: &[u8]) -> String {
+        let mut replies = Vec::new();
+        handle(cache, requests, &mut replies).unwrap();
+        String::from_utf8(replies).unwrap()
+    }
+
+    #[test]
+    fn test_text_protocol() {
+        let cache = SyncLruCache::new(2);
+        let replies = session(
+            &cache,
+            b"set a 0 0 4\r\na\r\nb\r\nadd a 0 0 1\r\nx\r\nreplace b 0 0 1\r\nx\r\n\
+              set b 0 0 1 noreply\r\n2\r\nget a b c\r\ndelete c\r\n\
+              set c 5 0 1\r\n3\r\nbogus\r\nquit\r\nget a\r\n",
+        );
+        assert_eq!(
+            replies,
+            "STORED\r\nNOT_STORED\r\nNOT_STORED\r\n\
+             VALUE a 0 4\r\na\r\nb\r\nVALUE b 0 1\r\n2\r\nEND\r\nNOT_FOUND\r\n\
+             CLIENT_ERROR flags non supportés\r\nERROR\r\n"
+        );
+        assert_eq!(cache.get(&b"b".to_vec()), Some(b"2".to_vec()));
+
+        let replies = session(
+            &cache,
+            b"set t 0 -1 1\r\nx\r\nget t\r\nstats\r\nset big 0 0 9999999\r\n",
+        );
+        assert!(replies.starts_with("STORED\r\nEND\r\nSTAT curr_items 2\r\n"));
+        assert!(replies.contains("STAT get_misses 2\r\n"));
+        assert!(replies.ends_with("END\r\nSERVER_ERROR valeur trop grande pour le cache\r\n"));
+    }
+
+    #[test]
+    fn test_serves_memcached_store() {
+        let cache = SyncLruCache::new(10);
+        let server = MemcachedServer::bind("127.0.0.1:0", cache.clone()).unwrap();
+        let handle = server.spawn().unwrap();
+
+        let store = MemcachedStore::connect(handle.local_addr()).unwrap();
+        store
+            .set(b"k", b"v", Some(Duration::from_secs(60)))
+            .unwrap();
+        assert_eq!(store.get(b"k").unwrap(), Some(b"v".to_vec()));
+        assert!(cache.time_to_live(&b"k".to_vec()).is_some());
+        store.delete(b"k").unwrap();
+        assert!(cache.is_empty());
+
+        drop(store);
+        handle.stop();
+    }
+}
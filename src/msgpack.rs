@@ -0,0 +1,224 @@
+//! Sous-ensemble de MessagePack utilisé par le format de persistance
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Nil,
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    /// `str` et `bin` sont tous deux lus comme des octets
+    Bytes(Vec<u8>),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+}
+
+impl Value {
+    pub(crate) fn get(&self, field: &str) -> Option<&Value> {
+        match self {
+            Value::Map(fields) => fields
+                .iter()
+                .find(|(k, _)| *k == Value::Bytes(field.as_bytes().to_vec()))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Int(n) => u64::try_from(*n).ok(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn write_uint(out: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0x7f => out.push(n as u8),
+        0x80..=0xff => out.extend_from_slice(&[0xcc, n as u8]),
+        0x100..=0xffff => {
+            out.push(0xcd);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(0xce);
+            out.extend_from_slice(&(n as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xcf);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+    }
+}
+
+/// `str` si les octets sont de l'UTF-8, `bin` sinon
+pub(crate) fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    let len = bytes.len();
+    if std::str::from_utf8(bytes).is_ok() {
+        match len {
+            0..=31 => out.push(0xa0 | len as u8),
+            32..=0xff => out.extend_from_slice(&[0xd9, len as u8]),
+            0x100..=0xffff => write_tagged_len(out, 0xda, len),
+            _ => write_tagged_len(out, 0xdb, len),
+        }
+    } else {
+        match len {
+            0..=0xff => out.extend_from_slice(&[0xc4, len as u8]),
+            0x100..=0xffff => write_tagged_len(out, 0xc5, len),
+            _ => write_tagged_len(out, 0xc6, len),
+        }
+    }
+    out.extend_from_slice(bytes);
+}
+
+pub(crate) fn write_array_len(out: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=15 => out.push(0x90 | len as u8),
+        16..=0xffff => write_tagged_len(out, 0xdc, len),
+        _ => write_tagged_len(out, 0xdd, len),
+    }
+}
+
+pub(crate) fn write_map_len(out: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=15 => out.push(0x80 | len as u8),
+        16..=0xffff => write_tagged_len(out, 0xde, len),
+        _ => write_tagged_len(out, 0xdf, len),
+    }
+}
+
+fn write_tagged_len(out: &mut Vec<u8>, tag: u8, len: usize) {
+    out.push(tag);
+    if matches!(tag, 0xc5 | 0xda | 0xdc | 0xde) {
+        out.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+pub(crate) fn parse(bytes: &[u8]) -> Result<Value, String> {
+    let mut parser = Parser { bytes, pos: 0 };
+    let value = parser.value()?;
+    if parser.pos != bytes.len() {
+        return Err(parser.error("contenu après la fin du document"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn value(&mut self) -> Result<Value, String> {
+        let tag = self.take(1)?[0];
+        Ok(match tag {
+            0x00..=0x7f => Value::Int(tag as i128),
+            0x80..=0x8f => self.map((tag & 0x0f) as usize)?,
+            0x90..=0x9f => self.array((tag & 0x0f) as usize)?,
+            0xa0..=0xbf => self.bytes((tag & 0x1f) as usize)?,
+            0xc0 => Value::Nil,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4 | 0xd9 => {
+                let len = self.uint(1)? as usize;
+                self.bytes(len)?
+            }
+            0xc5 | 0xda => {
+                let len = self.uint(2)? as usize;
+                self.bytes(len)?
+            }
+            0xc6 | 0xdb => {
+                let len = self.uint(4)? as usize;
+                self.bytes(len)?
+            }
+            0xca => Value::Float(f32::from_bits(self.uint(4)? as u32) as f64),
+            0xcb => Value::Float(f64::from_bits(self.uint(8)?)),
+            0xcc => Value::Int(self.uint(1)? as i128),
+            0xcd => Value::Int(self.uint(2)? as i128),
+            0xce => Value::Int(self.uint(4)? as i128),
+            0xcf => Value::Int(self.uint(8)? as i128),
+            0xd0 => Value::Int(self.uint(1)? as u8 as i8 as i128),
+            0xd1 => Value::Int(self.uint(2)? as u16 as i16 as i128),
+            0xd2 => Value::Int(self.uint(4)? as u32 as i32 as i128),
+            0xd3 => Value::Int(self.uint(8)? as i64 as i128),
+            0xdc => {
+                let len = self.uint(2)? as usize;
+                self.array(len)?
+            }
+            0xdd => {
+                let len = self.uint(4)? as usize;
+                self.array(len)?
+            }
+            0xde => {
+                let len = self.uint(2)? as usize;
+                self.map(len)?
+            }
+            0xdf => {
+                let len = self.uint(4)? as usize;
+                self.map(len)?
+            }
+            0xe0..=0xff => Value::Int(tag as i8 as i128),
+            _ => return Err(self.error("type MessagePack non supporté")),
+        })
+    }
+
+    fn array(&mut self, len: usize) -> Result<Value, String> {
+        let mut items = Vec::with_capacity(len.min(self.remaining()));
+        for _ in 0..len {
+            items.push(self.value()?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn map(&mut self, len: usize) -> Result<Value, String> {
+        let mut fields = Vec::with_capacity(len.min(self.remaining()));
+        for _ in 0..len {
+            let key = self.value()?;
+            fields.push((key, self.value()?));
+        }
+        Ok(Value::Map(fields))
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<Value, String> {
+        Ok(Value::Bytes(self.take(len)?.to_vec()))
+    }
+
+    fn uint(&mut self, width: usize) -> Result<u64, String> {
+        Ok(self
+            .take(width)?
+            .iter()
+            .fold(0, |acc, &b| (acc << 8) | b as u64))
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.remaining() < n {
+            return Err(self.error("document tronqué"));
+        }
+        let slice = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} (octet {})", message, self.pos)
+    }
+}
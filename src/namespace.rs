@@ -0,0 +1,332 @@
+//! Espaces de noms logiques dans un même cache borné
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::cache::LruCache;
+use crate::stats::CacheStats;
+
+/// Cache partagé entre plusieurs sous-systèmes, chacun dans son espace de
+/// noms
+///
+/// Tous les espaces puisent dans la même capacité et le même ordre LRU:
+/// l'entrée évincée est la moins récente du cache entier, quel que soit
+/// son espace. Chaque espace a ses propres statistiques (l'éviction compte
+/// pour l'espace de l'entrée évincée) et peut être vidé seul. Une même clé
+/// peut exister dans plusieurs espaces sans conflit.
+///
+/// Un quota (`set_quota`) borne le nombre d'entrées d'un espace: un espace
+/// bruyant qui l'atteint évince d'abord ses propres entrées, au lieu de
+/// pousser dehors celles des autres.
+///
+/// Comme pour `SyncLruCache`, les méthodes prennent `&self` et les clones
+/// partagent le même contenu.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::NamespacedCache;
+///
+/// let cache = NamespacedCache::new(100);
+/// cache.ns("search").put("rust", vec![1, 2]);
+/// cache.ns("users").put("rust", vec![42]);
+///
+/// assert_eq!(cache.ns("search").get(&"rust"), Some(vec![1, 2]));
+/// cache.ns("search").clear();
+/// assert_eq!(cache.ns("users").get(&"rust"), Some(vec![42]));
+/// assert_eq!(cache.ns("search").stats().hits, 1);
+/// ```
+pub struct NamespacedCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    inner: Arc<Mutex<Shared<K, V>>>,
+}
+
+struct Shared<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: LruCache<(String, K), Arc<V>>,
+    stats: HashMap<String, CacheStats>,
+    quotas: HashMap<String, usize>,
+}
+
+impl<K, V> Shared<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Évince les entrées les moins récentes de l'espace `ns` jusqu'à n'en
+    /// garder que `keep`; retourne leur nombre
+    fn shrink_namespace(&mut self, ns: &str, keep: usize) -> usize {
+        let keys: Vec<(String, K)> = self
+            .cache
+            .iter()
+            .filter(|((name, _), _)| name == ns)
+            .map(|(key, _)| key.clone())
+            .collect();
+        let excess = keys.len().saturating_sub(keep);
+        let evicted = self.cache.remove_many(keys.into_iter().take(excess)).len();
+
+        let mut stats = self.cache.stats();
+        stats.evictions += evicted as u64;
+        self.cache.set_stats(stats);
+        self.stats.entry(ns.to_string()).or_default().evictions += evicted as u64;
+        evicted
+    }
+}
+
+impl<K, V> Clone for NamespacedCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> NamespacedCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// `capacity` entrées au total, tous espaces confondus
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Shared {
+                cache: LruCache::new(capacity),
+                stats: HashMap::new(),
+                quotas: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Accès à l'espace `name`, créé à la première utilisation
+    pub fn ns<'a>(&'a self, name: &'a str) -> Namespace<'a, K, V> {
+        Namespace { cache: self, name }
+    }
+
+    /// Limite l'espace `name` à `max_entries` entrées
+    ///
+    /// Au-delà, une insertion dans l'espace évince sa propre entrée la moins
+    /// récente. Les entrées en trop sont évincées tout de suite; retourne
+    /// leur nombre.
+    ///
+    /// ```
+    /// use lru_cache::NamespacedCache;
+    ///
+    /// let cache = NamespacedCache::new(10);
+    /// cache.set_quota("batch", 2);
+    /// cache.ns("web").put(0, "page");
+    /// for i in 0..5 {
+    ///     cache.ns("batch").put(i, "ligne");
+    /// }
+    ///
+    /// assert_eq!(cache.ns("batch").len(), 2);
+    /// assert!(cache.ns("web").contains_key(&0));
+    /// ```
+    pub fn set_quota(&self, name: &str, max_entries: usize) -> usize {
+        let mut shared = self.lock();
+        shared.quotas.insert(name.to_string(), max_entries);
+        shared.shrink_namespace(name, max_entries)
+    }
+
+    /// Retire le quota de l'espace `name`
+    pub fn remove_quota(&self, name: &str) {
+        self.lock().quotas.remove(name);
+    }
+
+    pub fn quota(&self, name: &str) -> Option<usize> {
+        self.lock().quotas.get(name).copied()
+    }
+
+    /// Espaces contenant au moins une entrée, triés
+    pub fn namespaces(&self) -> Vec<String> {
+        let shared = self.lock();
+        let mut names: Vec<String> = shared.cache.iter().map(|((ns, _), _)| ns.clone()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Nombre d'entrées, tous espaces confondus
+    pub fn len(&self) -> usize {
+        self.lock().cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().cache.capacity()
+    }
+
+    /// Statistiques cumulées de tous les espaces
+    pub fn stats(&self) -> CacheStats {
+        self.lock().cache.stats()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shared<K, V>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Espace de noms d'un `NamespacedCache`, voir `NamespacedCache::ns`
+pub struct Namespace<'a, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: &'a NamespacedCache<K, V>,
+    name: &'a str,
+}
+
+impl<K, V> Namespace<'_, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// Insère une paire clé-valeur, retourne l'ancienne valeur
+    pub fn put(&self, key: K, value: V) -> Option<Arc<V>> {
+        let mut shared = self.cache.lock();
+        let key = self.key(key);
+        if shared.cache.contains_key(&key) {
+            return shared.cache.put(key, Arc::new(value));
+        }
+        if let Some(&quota) = shared.quotas.get(self.name) {
+            if quota == 0 {
+                return None;
+            }
+            shared.shrink_namespace(self.name, quota - 1);
+        }
+        let evicted = shared.cache.put_many([(key, Arc::new(value))]);
+        for ((ns, _), _) in evicted {
+            shared.stats.entry(ns).or_default().evictions += 1;
+        }
+        None
+    }
+
+    /// Récupère une copie de la valeur et la marque comme récemment utilisée
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.get_arc(key).map(|value| V::clone(&value))
+    }
+
+    /// Récupère la valeur partagée et la marque comme récemment utilisée
+    pub fn get_arc(&self, key: &K) -> Option<Arc<V>> {
+        let mut shared = self.cache.lock();
+        let value = shared.cache.get(&self.key(key.clone())).cloned();
+        let stats = shared.stats.entry(self.name.to_string()).or_default();
+        match value {
+            Some(_) => stats.hits += 1,
+            None => stats.misses += 1,
+        }
+        value
+    }
+
+    pub fn remove(&self, key: &K) -> Option<Arc<V>> {
+        self.cache.lock().cache.remove(&self.key(key.clone()))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.cache.lock().cache.contains_key(&self.key(key.clone()))
+    }
+
+    /// Nombre d'entrées de l'espace
+    pub fn len(&self) -> usize {
+        self.cache
+            .lock()
+            .cache
+            .iter()
+            .filter(|((ns, _), _)| ns == self.name)
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Retire toutes les entrées de l'espace, sans toucher aux autres;
+    /// retourne leur nombre
+    pub fn clear(&self) -> usize {
+        let mut shared = self.cache.lock();
+        let keys: Vec<(String, K)> = shared
+            .cache
+            .iter()
+            .filter(|((ns, _), _)| ns == self.name)
+            .map(|(key, _)| key.clone())
+            .collect();
+        shared.cache.remove_many(keys).len()
+    }
+
+    /// Hits, misses et évictions de l'espace
+    pub fn stats(&self) -> CacheStats {
+        let shared = self.cache.lock();
+        shared.stats.get(self.name).copied().unwrap_or_default()
+    }
+
+    pub fn reset_stats(&self) {
+        self.cache.lock().stats.remove(self.name);
+    }
+
+    fn key(&self, key: K) -> (String, K) {
+        (self.name.to_string(), key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_capacity() {
+        let cache = NamespacedCache::new(3);
+        let (search, users) = (cache.ns("search"), cache.ns("users"));
+        search.put(1, "s1");
+        users.put(1, "u1");
+        search.put(2, "s2");
+        assert_eq!(users.get(&1), Some("u1"));
+
+        // "search" 1 est la moins récente du cache entier
+        users.put(2, "u2");
+        assert!(!search.contains_key(&1));
+        assert_eq!(search.stats().evictions, 1);
+        assert_eq!(users.stats().evictions, 0);
+        assert_eq!((search.len(), users.len()), (1, 2));
+
+        assert_eq!(users.clear(), 2);
+        assert_eq!(cache.namespaces(), vec!["search".to_string()]);
+        assert_eq!(users.get(&1), None);
+        assert_eq!(users.stats().misses, 1);
+    }
+
+    #[test]
+    fn test_quota_evicts_within_namespace() {
+        let cache = NamespacedCache::new(4);
+        let (noisy, quiet) = (cache.ns("noisy"), cache.ns("quiet"));
+        quiet.put(0, 0);
+        for i in 0..3 {
+            noisy.put(i, i);
+        }
+        assert_eq!(cache.set_quota("noisy", 2), 1);
+        assert!(!noisy.contains_key(&0));
+
+        noisy.put(3, 3);
+        noisy.put(4, 4);
+        assert!(quiet.contains_key(&0));
+        assert_eq!(noisy.len(), 2);
+        assert_eq!(noisy.stats().evictions, 3);
+        assert_eq!(cache.stats().evictions, 3);
+
+        cache.set_quota("quiet", 0);
+        quiet.put(1, 1);
+        assert!(quiet.is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+//! Cache qui retient aussi l'absence d'une valeur
+
+use std::hash::Hash;
+use std::time::Duration;
+
+use crate::stats::CacheStats;
+use crate::sync::SyncLruCache;
+
+/// Résultat d'une lecture dans un `NegativeCache`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Lookup<V> {
+    /// Valeur en cache
+    Hit(V),
+    /// Absence connue: inutile d'interroger la source
+    Negative,
+    /// Rien en cache: la source doit être interrogée
+    Unknown,
+}
+
+impl<V> Lookup<V> {
+    /// Vrai sauf pour `Unknown`
+    pub fn is_known(&self) -> bool {
+        !matches!(self, Lookup::Unknown)
+    }
+
+    /// La valeur pour `Hit`, `None` sinon
+    pub fn into_option(self) -> Option<V> {
+        match self {
+            Lookup::Hit(value) => Some(value),
+            Lookup::Negative | Lookup::Unknown => None,
+        }
+    }
+}
+
+/// `SyncLruCache` dont les entrées peuvent aussi noter qu'une clé n'existe
+/// pas dans la source
+///
+/// `put_negative` retient l'absence pendant `ttl`; `get` distingue alors
+/// l'absence connue (`Lookup::Negative`) de la clé jamais vue
+/// (`Lookup::Unknown`). Les entrées négatives occupent une place comme les
+/// autres et sont évincées de la même façon.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::{Lookup, NegativeCache};
+/// use std::time::Duration;
+///
+/// let users = NegativeCache::new(100);
+/// users.put(1, "alice".to_string());
+/// users.put_negative(2, Duration::from_secs(30));
+///
+/// assert_eq!(users.get(&1), Lookup::Hit("alice".to_string()));
+/// assert_eq!(users.get(&2), Lookup::Negative); // pas de requête
+/// assert_eq!(users.get(&3), Lookup::Unknown);
+/// ```
+pub struct NegativeCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: SyncLruCache<K, Option<V>>,
+}
+
+impl<K, V> Clone for NegativeCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<K, V> NegativeCache<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            cache: SyncLruCache::new(capacity),
+        }
+    }
+
+    /// Insère une valeur; remplace une éventuelle entrée négative
+    pub fn put(&self, key: K, value: V) {
+        self.cache.put(key, Some(value));
+    }
+
+    pub fn put_with_ttl(&self, key: K, value: V, ttl: Duration) {
+        self.cache.put_with_ttl(key, Some(value), ttl);
+    }
+
+    /// Retient que `key` n'existe pas, pendant `ttl`
+    pub fn put_negative(&self, key: K, ttl: Duration) {
+        self.cache.put_with_ttl(key, None, ttl);
+    }
+
+    pub fn get(&self, key: &K) -> Lookup<V> {
+        match self.cache.get(key) {
+            Some(Some(value)) => Lookup::Hit(value),
+            Some(None) => Lookup::Negative,
+            None => Lookup::Unknown,
+        }
+    }
+
+    /// Oublie la clé, valeur ou absence
+    pub fn remove(&self, key: &K) {
+        self.cache.remove(key);
+    }
+
+    /// Entrées négatives comprises
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Une entrée négative lue compte comme un succès
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Cache sous-jacent, `None` pour les entrées négatives
+    pub fn cache(&self) -> &SyncLruCache<K, Option<V>> {
+        &self.cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_negative_entries_expire() {
+        let cache = NegativeCache::new(2);
+        cache.put_negative("absent", Duration::from_millis(20));
+        assert_eq!(cache.get(&"absent"), Lookup::<i32>::Negative);
+
+        thread::sleep(Duration::from_millis(30));
+        assert_eq!(cache.get(&"absent"), Lookup::Unknown);
+
+        // La valeur trouvée plus tard remplace l'absence
+        cache.put_negative("absent", Duration::from_secs(60));
+        cache.put("absent", 1);
+        assert_eq!(cache.get(&"absent"), Lookup::Hit(1));
+        assert_eq!(cache.len(), 1);
+    }
+}
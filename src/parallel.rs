@@ -0,0 +1,117 @@
+//! Traitement parallèle d'une copie des entrées (feature `parallel`)
+//!
+//! Les entrées sont copiées d'abord (voir `snapshot`), puis réparties entre
+//! autant de threads que de cœurs disponibles: le cache n'est pas verrouillé
+//! pendant le traitement.
+
+use std::hash::Hash;
+use std::sync::Arc;
+use std::thread;
+
+use crate::sharded::ShardedLruCache;
+use crate::sync::SyncLruCache;
+
+impl<K, V> SyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    /// Applique `f` à chaque entrée en parallèle, dans l'ordre LRU
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// cache.put(1, 10);
+    /// cache.put(2, 20);
+    ///
+    /// assert_eq!(cache.par_map(|k, v| k + v), vec![11, 22]);
+    /// ```
+    pub fn par_map<R, F>(&self, f: F) -> Vec<R>
+    where
+        F: Fn(&K, &V) -> R + Sync,
+        R: Send,
+    {
+        par_map_entries(&self.snapshot_arc(), f)
+    }
+}
+
+impl<K, V> ShardedLruCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync,
+    V: Send + Sync,
+{
+    /// Applique `f` à chaque entrée en parallèle, segment par segment
+    pub fn par_map<R, F>(&self, f: F) -> Vec<R>
+    where
+        F: Fn(&K, &V) -> R + Sync,
+        R: Send,
+    {
+        par_map_entries(&self.snapshot_arc(), f)
+    }
+}
+
+fn par_map_entries<K, V, R, F>(entries: &[(K, Arc<V>)], f: F) -> Vec<R>
+where
+    K: Sync,
+    V: Send + Sync,
+    F: Fn(&K, &V) -> R + Sync,
+    R: Send,
+{
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = entries.len().div_ceil(threads).max(1);
+    let f = &f;
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = entries
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|(key, value)| f(key, value))
+                        .collect::<Vec<R>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_map_matches_sequential() {
+        let cache = SyncLruCache::new(1000);
+        for i in 0..1000u64 {
+            cache.put(i, i * i);
+        }
+        cache.get(&0); // 0 passe en dernière position
+
+        let sequential: Vec<u64> = cache
+            .snapshot()
+            .iter()
+            .map(|(key, value)| key + value)
+            .collect();
+        assert_eq!(cache.par_map(|key, value| key + value), sequential);
+
+        // Moins d'entrées que de threads, et aucune
+        assert_eq!(par_map_entries(&[(1, Arc::new(2))], |k, v| k * v), vec![2]);
+        assert!(par_map_entries::<u64, u64, u64, _>(&[], |k, v| k + v).is_empty());
+
+        let sharded = ShardedLruCache::new(1000, 4);
+        for i in 0..500u64 {
+            sharded.put(i, i);
+        }
+        let sequential: Vec<u64> = sharded.snapshot().iter().map(|(k, v)| k + v).collect();
+        assert_eq!(sharded.par_map(|k, v| k + v), sequential);
+    }
+}
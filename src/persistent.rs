@@ -1,10 +1,481 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::hash::Hash;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::background::BackgroundPersistentLruCache;
+use crate::cache::LruCache;
+use crate::checksum;
+use crate::codec::Persistable;
+use crate::directory::EntryDirectory;
+use crate::error::CacheError;
+use crate::format::{EntryMeta, Format, Snapshot};
+use crate::journal::{self, Record};
+use crate::report::{InvalidRecord, LoadReport, Recovery, WarmReport};
+use crate::shard_files::{self, ShardFiles};
+use crate::stats::CacheStats;
+use crate::trait_cache::CacheOps;
+use crate::transform::Transform;
+
+/// Moment où un `PersistentLruCache` réécrit son fichier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Autosave {
+    /// Après chaque écriture (comportement historique)
+    #[default]
+    EveryWrite,
+    /// Toutes les `n` écritures
+    EveryN(usize),
+    /// Lors d'une écriture, si la dernière sauvegarde date d'au moins cette durée
+    Interval(Duration),
+    /// Jamais automatiquement
+    Manual,
+}
+
+/// Verrou consultatif pris sur `<fichier>.lock` à l'ouverture
+///
+/// Empêche deux processus d'utiliser le même fichier de cache; le verrou est
+/// libéré à la destruction du cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockPolicy {
+    /// Aucun verrou (comportement historique)
+    #[default]
+    Disabled,
+    /// `CacheError::Locked` immédiatement si le fichier est déjà verrouillé
+    Fail,
+    /// Attend au plus cette durée avant `CacheError::Locked`; une durée trop
+    /// grande pour l'horloge (`Duration::MAX`) attend comme `Block`
+    Wait(Duration),
+    /// Attend indéfiniment
+    Block,
+}
+
+/// Moment où une sauvegarde est forcée sur le disque (`fsync`)
+///
+/// Le `fsync` porte sur le fichier puis sur son répertoire, pour que le
+/// renommage qui remplace l'ancienne version soit lui aussi durable.
+///
+/// Sans `fsync`, une sauvegarde survit à l'arrêt du processus mais peut être
+/// perdue lors d'une coupure de courant; le fichier reste néanmoins entier
+/// (ancienne ou nouvelle version) grâce au renommage atomique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Durability {
+    /// Jamais: le système écrit sur le disque quand il le décide
+    None,
+    /// À chaque sauvegarde (comportement historique)
+    #[default]
+    EverySave,
+    /// Lors d'une sauvegarde, si le dernier `fsync` date d'au moins cette durée
+    Interval(Duration),
+}
+
+/// Options de persistance d'un `PersistentLruCache`
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{PersistOptions, PersistentLruCache};
+///
+/// let options = PersistOptions::new().persist_stats(true);
+/// let cache: PersistentLruCache =
+///     PersistentLruCache::with_options(3, "cache.txt", options).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PersistOptions {
+    persist_stats: bool,
+    persist_metadata: bool,
+    lazy_load: bool,
+    load_limit: Option<usize>,
+    lenient: bool,
+    read_only: bool,
+    format: Format,
+    keep_backup: bool,
+    autosave: Autosave,
+    save_on_drop: bool,
+    storage: Storage,
+    transforms: Vec<Arc<dyn Transform>>,
+    checksum: bool,
+    lock: LockPolicy,
+    durability: Durability,
+    compact_above: Option<u64>,
+    compact_ratio: Option<f64>,
+    max_file_size: Option<u64>,
+    rotate: bool,
+    seed: Option<PathBuf>,
+    on_error: Option<ErrorHandler>,
+}
+
+#[derive(Clone)]
+struct ErrorHandler(Arc<dyn Fn(&CacheError) + Send + Sync>);
+
+impl std::fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ErrorHandler")
+    }
+}
+
+/// Organisation des fichiers sur disque
+#[derive(Debug, Clone, Copy, Default)]
+enum Storage {
+    /// Le fichier est réécrit en entier à chaque sauvegarde
+    #[default]
+    Snapshot,
+    /// Le fichier est un journal, compacté toutes les `n` écritures
+    Log { compact_after: usize },
+    /// Instantané au format choisi + `<fichier>.journal` des écritures
+    /// suivantes, remis à zéro à chaque instantané
+    Journal { snapshot_every: usize },
+    /// Un dossier: un fichier par valeur et un index des clés
+    Directory,
+    /// Un dossier: un instantané par segment de clés
+    Sharded { shards: usize },
+}
+
+impl PersistOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sauvegarde aussi les compteurs hits/misses/évictions dans l'en-tête
+    pub fn persist_stats(mut self, enabled: bool) -> Self {
+        self.persist_stats = enabled;
+        self
+    }
+
+    /// Sauvegarde aussi l'échéance et les hits de chaque entrée
+    ///
+    /// Au chargement, les TTL reprennent là où ils en étaient (le temps
+    /// d'arrêt compte) et les entrées échues sont ignorées. Seuls les
+    /// instantanés texte, JSON, binaire et MessagePack les contiennent;
+    /// le format RDB ne garde que les échéances.
+    pub fn persist_metadata(mut self, enabled: bool) -> Self {
+        self.persist_metadata = enabled;
+        self
+    }
+
+    /// Format du fichier (texte par défaut)
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Conserve la version précédente du fichier sous `<fichier>.bak`
+    pub fn keep_backup(mut self, enabled: bool) -> Self {
+        self.keep_backup = enabled;
+        self
+    }
+
+    /// Politique de sauvegarde automatique (après chaque écriture par défaut)
+    ///
+    /// ```no_run
+    /// use lru_cache::{Autosave, PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().autosave(Autosave::EveryN(1000));
+    /// let cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(100_000, "cache.txt", options).unwrap();
+    /// ```
+    pub fn autosave(mut self, policy: Autosave) -> Self {
+        self.autosave = policy;
+        self
+    }
+
+    /// Sauvegarde les écritures en attente quand le cache est détruit
+    ///
+    /// Utile avec `Autosave::EveryN`, `Interval` ou `Manual`; les erreurs
+    /// d'écriture sont ignorées à ce moment-là.
+    pub fn save_on_drop(mut self, enabled: bool) -> Self {
+        self.save_on_drop = enabled;
+        self
+    }
+
+    /// Ajoute chaque écriture à la fin du fichier au lieu de le réécrire
+    ///
+    /// Le fichier devient un journal binaire: chaque `put` ou `remove` coûte
+    /// un ajout en O(1), et le journal est compacté (réécrit avec les seules
+    /// entrées présentes) toutes les `compact_after` écritures ou à chaque
+    /// `save()`. La politique `autosave` et le `format` sont alors ignorés.
+    /// Après un arrêt brutal, le journal est rejoué jusqu'au dernier
+    /// enregistrement complet.
+    pub fn write_ahead_log(mut self, compact_after: usize) -> Self {
+        self.storage = Storage::Log {
+            compact_after: compact_after.max(1),
+        };
+        self
+    }
+
+    /// Instantanés périodiques complétés par un journal des écritures
+    ///
+    /// Le fichier reste un instantané dans le `format` choisi; chaque écriture
+    /// est ajoutée à `<fichier>.journal`, rejoué par-dessus l'instantané au
+    /// chargement. Toutes les `snapshot_every` écritures (ou à chaque
+    /// `save()`), un nouvel instantané est écrit et le journal vidé. La
+    /// politique `autosave` est alors ignorée.
+    ///
+    /// Les lectures ne sont pas journalisées, mais chaque éviction l'est: la
+    /// relecture retrouve les entrées présentes à l'arrêt, même si l'ordre
+    /// des lectures depuis le dernier instantané est perdu.
+    pub fn journal(mut self, snapshot_every: usize) -> Self {
+        self.storage = Storage::Journal {
+            snapshot_every: snapshot_every.max(1),
+        };
+        self
+    }
+
+    /// Compacte aussi dès que les ajouts au journal depuis la dernière
+    /// compaction dépassent `max_bytes` octets
+    ///
+    /// S'applique aux modes `write_ahead_log` et `journal`, en plus du
+    /// nombre d'écritures.
+    pub fn compact_above(mut self, max_bytes: u64) -> Self {
+        self.compact_above = Some(max_bytes);
+        self
+    }
+
+    /// Compacte aussi dès que les ajouts au journal dépassent `ratio` fois la
+    /// taille écrite par la dernière compaction (ou le dernier instantané)
+    ///
+    /// ```no_run
+    /// use lru_cache::{PersistOptions, PersistentLruCache};
+    ///
+    /// // Le fichier ne dépasse jamais deux fois la taille des données vivantes
+    /// let options = PersistOptions::new()
+    ///     .write_ahead_log(usize::MAX)
+    ///     .compact_ratio(1.0);
+    /// let cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(1000, "cache.log", options).unwrap();
+    /// ```
+    pub fn compact_ratio(mut self, ratio: f64) -> Self {
+        self.compact_ratio = Some(ratio);
+        self
+    }
+
+    /// Taille maximale sur disque, en octets
+    ///
+    /// Au-delà, le journal est compacté (modes `write_ahead_log` et
+    /// `journal`). Si le fichier dépasse encore la limite, les entrées vivantes
+    /// n'y tiennent pas: `CacheError::FileTooLarge` est signalée à `on_error`.
+    /// Sans effet en modes `directory` et `sharded`.
+    pub fn max_file_size(mut self, max_bytes: u64) -> Self {
+        self.max_file_size = Some(max_bytes);
+        self
+    }
+
+    /// Avant de réécrire un fichier au-delà de `max_file_size`, le renomme en
+    /// `<fichier>.1` (remplaçant la rotation précédente)
+    ///
+    /// ```no_run
+    /// use lru_cache::{PersistOptions, PersistentLruCache};
+    ///
+    /// // Au plus deux fichiers d'environ 10 Mo: cache.log et cache.log.1
+    /// let options = PersistOptions::new()
+    ///     .write_ahead_log(usize::MAX)
+    ///     .max_file_size(10 << 20)
+    ///     .rotate(true)
+    ///     .on_error(|err| eprintln!("cache: {}", err));
+    /// let cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(1000, "cache.log", options).unwrap();
+    /// ```
+    pub fn rotate(mut self, enabled: bool) -> Self {
+        self.rotate = enabled;
+        self
+    }
+
+    /// Enregistre chaque valeur dans son propre fichier
+    ///
+    /// Le chemin désigne alors un dossier contenant `index` (clés et ordre
+    /// LRU) et `values/`. Une écriture ne réécrit que la valeur concernée et
+    /// l'index, adapté aux grosses valeurs (images, artefacts). Chaque
+    /// écriture est sauvegardée immédiatement: `autosave` et `format` sont
+    /// ignorés, les transformations s'appliquent à chaque fichier.
+    ///
+    /// ```no_run
+    /// use lru_cache::{PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().directory();
+    /// let mut blobs: PersistentLruCache<String, String> =
+    ///     PersistentLruCache::with_options(100, "blobs", options).unwrap();
+    /// ```
+    pub fn directory(mut self) -> Self {
+        self.storage = Storage::Directory;
+        self
+    }
+
+    /// Répartit les entrées entre `shards` instantanés d'un dossier, selon
+    /// le hash de leur clé
+    ///
+    /// Une sauvegarde ne réécrit que les segments modifiés depuis la
+    /// précédente, en parallèle: pour un gros cache, une écriture isolée
+    /// coûte environ `1 / shards` d'un instantané complet. Le `format`, les
+    /// transformations et la somme de contrôle s'appliquent à chaque segment.
+    /// Au chargement, l'ordre LRU n'est exact qu'au sein d'un segment.
+    ///
+    /// ```no_run
+    /// use lru_cache::{PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().sharded(16);
+    /// let mut cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(1_000_000, "cache", options).unwrap();
+    /// cache.put("a".into(), "1".into()); // réécrit cache/shard-<n> seulement
+    /// ```
+    pub fn sharded(mut self, shards: usize) -> Self {
+        self.storage = Storage::Sharded {
+            shards: shards.max(1),
+        };
+        self
+    }
+
+    /// Ne décode une valeur qu'au premier accès à sa clé
+    ///
+    /// En mode `directory`, seul l'index est lu au chargement, chaque valeur
+    /// étant lue dans son fichier au premier accès. En mode instantané (par
+    /// défaut), le fichier est lu en entier mais les valeurs restent en
+    /// octets jusqu'au premier `get`, qui garde la valeur décodée: une
+    /// entrée jamais lue ne coûte pas de `Persistable::from_bytes`. Les clés
+    /// pas encore lues sont les moins récentes et sont évincées en premier.
+    /// Sans effet dans les autres modes.
+    pub fn lazy_load(mut self, enabled: bool) -> Self {
+        self.lazy_load = enabled;
+        self
+    }
+
+    /// Ne restaure au chargement que les `max_entries` entrées les plus
+    /// récentes du fichier
+    ///
+    /// Garde l'empreinte mémoire au démarrage prévisible, par exemple après
+    /// une baisse de la capacité. En mode `directory`, les valeurs écartées
+    /// ne sont pas lues.
+    pub fn load_limit(mut self, max_entries: usize) -> Self {
+        self.load_limit = Some(max_entries);
+        self
+    }
+
+    /// Écarte les enregistrements illisibles au chargement au lieu d'échouer
+    ///
+    /// Les enregistrements écartés sont listés par `load_report`. Un fichier
+    /// illisible dans son ensemble (somme de contrôle, JSON mal formé) reste
+    /// une erreur.
+    pub fn lenient(mut self, enabled: bool) -> Self {
+        self.lenient = enabled;
+        self
+    }
+
+    /// Ouvre le fichier sans jamais le modifier, voir
+    /// `PersistentLruCache::open_read_only`
+    pub fn read_only(mut self, enabled: bool) -> Self {
+        self.read_only = enabled;
+        self
+    }
+
+    /// Préfixe l'instantané d'une version et d'une somme de contrôle CRC-32
+    ///
+    /// Au chargement, un fichier altéré donne `CacheError::Corrupted` et une
+    /// version inconnue `CacheError::VersionMismatch`. Les enregistrements du
+    /// journal portent toujours leur propre somme de contrôle.
+    pub fn checksum(mut self, enabled: bool) -> Self {
+        self.checksum = enabled;
+        self
+    }
+
+    /// Verrouille le fichier contre les autres processus
+    ///
+    /// ```no_run
+    /// use lru_cache::{CacheError, LockPolicy, PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().lock(LockPolicy::Fail);
+    /// let cache: Result<PersistentLruCache, _> =
+    ///     PersistentLruCache::with_options(3, "cache.txt", options);
+    /// if let Err(CacheError::Locked(path)) = cache {
+    ///     eprintln!("{} est utilisé par un autre processus", path.display());
+    /// }
+    /// ```
+    pub fn lock(mut self, policy: LockPolicy) -> Self {
+        self.lock = policy;
+        self
+    }
+
+    /// Verrou `Fail` si aucun n'a été choisi
+    pub(crate) fn locked(self) -> Self {
+        match self.lock {
+            LockPolicy::Disabled => self.lock(LockPolicy::Fail),
+            _ => self,
+        }
+    }
+
+    /// Force les sauvegardes sur le disque à chaque fois (par défaut), jamais
+    /// ou périodiquement
+    ///
+    /// ```no_run
+    /// use lru_cache::{Durability, PersistOptions, PersistentLruCache};
+    ///
+    /// // Cache jetable: la vitesse prime
+    /// let options = PersistOptions::new().durability(Durability::None);
+    /// let cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(3, "cache.txt", options).unwrap();
+    /// ```
+    pub fn durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    /// Précharge `path` à l'ouverture si le fichier du cache n'existe pas
+    /// encore, voir `PersistentLruCache::warm_from`
+    ///
+    /// ```no_run
+    /// use lru_cache::{Format, PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().format(Format::Csv).warm_from("seed.csv");
+    /// let cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(1000, "cache.csv", options).unwrap();
+    /// if let Some(report) = cache.warm_report() {
+    ///     println!("{} entrées préchargées", report.loaded);
+    /// }
+    /// ```
+    pub fn warm_from(mut self, path: impl Into<PathBuf>) -> Self {
+        self.seed = Some(path.into());
+        self
+    }
+
+    /// Appelé quand une sauvegarde automatique échoue (`put`, `remove`,
+    /// sauvegarde à la destruction)
+    ///
+    /// ```no_run
+    /// use lru_cache::{PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().on_error(|err| eprintln!("cache: {}", err));
+    /// let mut cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(3, "cache.txt", options).unwrap();
+    /// cache.put("a".into(), "1".into()); // une erreur disque est signalée
+    /// ```
+    pub fn on_error(mut self, handler: impl Fn(&CacheError) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(ErrorHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Ajoute une transformation des octets écrits (compression, chiffrement…)
+    ///
+    /// ```
+    /// # #[cfg(feature = "compression")] {
+    /// use lru_cache::{Format, LzCompression, PersistOptions};
+    ///
+    /// let options = PersistOptions::new()
+    ///     .format(Format::Json { pretty: false })
+    ///     .transform(LzCompression);
+    /// # }
+    /// ```
+    pub fn transform(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.push(Arc::new(transform));
+        self
+    }
+}
 
 /// Cache LRU avec persistance fichier (Itération 4)
 ///
+/// Clés et valeurs sont génériques via le trait `Persistable`; par défaut
+/// `String → String`.
+///
 /// # Exemples
 ///
 /// ```no_run
@@ -15,23 +486,50 @@ use std::path::Path;
 /// cache.put("key".to_string(), "value".to_string());
 ///
 /// // La donnée est automatiquement sauvegardée dans cache.txt
+///
+/// // Types quelconques
+/// let mut scores: PersistentLruCache<u64, i32> =
+///     PersistentLruCache::open(100, "scores.txt").unwrap();
+/// scores.put(42, -7);
 /// ```
-pub struct PersistentLruCache {
-    capacity: usize,
-    items: HashMap<String, String>,
-    usage: Vec<String>,
+pub struct PersistentLruCache<K = String, V = String>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    cache: LruCache<K, V>,
     file_path: Option<String>,
+    options: PersistOptions,
+    /// Écritures depuis la dernière sauvegarde (ou compaction du journal)
+    unsaved: usize,
+    last_save: Instant,
+    /// Dernier `fsync`, pour `Durability::Interval`
+    last_sync: Instant,
+    /// Octets ajoutés au journal depuis la dernière compaction
+    appended: u64,
+    /// Taille écrite par la dernière compaction (ou le dernier instantané)
+    compacted_len: u64,
+    /// Fichier ouvert en ajout, en mode journal
+    log: Option<File>,
+    /// Fichier `.lock` verrouillé tant que le cache existe
+    lock: Option<File>,
+    /// Fichiers des valeurs, en mode dossier
+    directory: Option<EntryDirectory>,
+    /// Segments à réécrire, en mode segmenté
+    shards: Option<ShardFiles>,
+    /// Entrées dont la valeur n'a pas encore été décodée, toutes moins
+    /// récentes que celles de `cache` (`lazy_load`)
+    unloaded: LruCache<K, Raw>,
+    /// Enregistrements écartés au dernier chargement
+    load_report: LoadReport,
+    /// Préchargement fait à l'ouverture (`PersistOptions::warm_from`)
+    warm_report: Option<WarmReport>,
 }
 
-impl PersistentLruCache {
+impl PersistentLruCache<String, String> {
     /// Crée un cache normal sans persistance
     pub fn new(capacity: usize) -> Self {
-        Self {
-            capacity,
-            items: HashMap::new(),
-            usage: Vec::new(),
-            file_path: None,
-        }
+        Self::in_memory(capacity)
     }
 
     /// Crée un cache persistant (auto-charge et auto-sauvegarde)
@@ -44,121 +542,2790 @@ impl PersistentLruCache {
     /// let mut cache = PersistentLruCache::new_persistent(3, "mon_cache.txt").unwrap();
     /// cache.put("user1".to_string(), "Alice".to_string());
     /// ```
-    pub fn new_persistent(capacity: usize, path: &str) -> std::io::Result<Self> {
+    pub fn new_persistent(capacity: usize, path: &str) -> Result<Self, CacheError> {
+        Self::open(capacity, path)
+    }
+
+    /// Comme `new_persistent`, en ne restaurant que les `max_load_entries`
+    /// entrées les plus récentes, voir `PersistOptions::load_limit`
+    ///
+    /// ```no_run
+    /// use lru_cache::PersistentLruCache;
+    ///
+    /// let cache = PersistentLruCache::new_persistent_with_limit(10_000, "cache.txt", 500).unwrap();
+    /// ```
+    pub fn new_persistent_with_limit(
+        capacity: usize,
+        path: &str,
+        max_load_entries: usize,
+    ) -> Result<Self, CacheError> {
+        let options = PersistOptions::new().load_limit(max_load_entries);
+        Self::with_options(capacity, path, options)
+    }
+
+    /// Réécrit un fichier de cache d'un format dans un autre
+    ///
+    /// Capacité, statistiques et métadonnées suivent si les deux formats les
+    /// connaissent; faute de capacité dans la source (CSV), c'est le nombre
+    /// d'entrées. Les fichiers transformés ou munis d'une somme de contrôle
+    /// ne sont pas pris en charge.
+    ///
+    /// ```no_run
+    /// use lru_cache::{Format, PersistentLruCache};
+    ///
+    /// PersistentLruCache::convert(
+    ///     "cache.txt",
+    ///     Format::Text,
+    ///     "cache.json",
+    ///     Format::Json { pretty: true },
+    /// )
+    /// .unwrap();
+    /// ```
+    pub fn convert(
+        path_in: &str,
+        format_in: Format,
+        path_out: &str,
+        format_out: Format,
+    ) -> Result<(), CacheError> {
+        let mut snapshot = format_in.decode(&read(Path::new(path_in))?)?;
+        snapshot.capacity = snapshot.capacity.or(Some(snapshot.entries.len()));
+        let bytes = format_out.encode(&snapshot)?;
+        Ok(write_atomic(Path::new(path_out), &bytes, false, true)?)
+    }
+}
+
+impl<K, V> PersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    /// Crée un cache sans fichier associé
+    pub fn in_memory(capacity: usize) -> Self {
+        Self {
+            cache: LruCache::new(capacity),
+            file_path: None,
+            options: PersistOptions::default(),
+            unsaved: 0,
+            last_save: Instant::now(),
+            last_sync: Instant::now(),
+            appended: 0,
+            compacted_len: 0,
+            log: None,
+            lock: None,
+            directory: None,
+            shards: None,
+            unloaded: LruCache::new(capacity),
+            load_report: LoadReport::default(),
+            warm_report: None,
+        }
+    }
+
+    /// Crée un cache persistant pour n'importe quels types `Persistable`
+    pub fn open(capacity: usize, path: &str) -> Result<Self, CacheError> {
+        Self::with_options(capacity, path, PersistOptions::default())
+    }
+
+    /// Charge un fichier de cache sans jamais le modifier
+    ///
+    /// La capacité est celle enregistrée dans le fichier. `get`, `peek` et
+    /// `iter` fonctionnent normalement; les écritures sont refusées avec
+    /// `CacheError::ReadOnly` et aucun fichier n'est créé ni verrouillé. Un
+    /// fichier absent donne `CacheError::NotFound`. Pour un autre format ou
+    /// un mode de stockage, voir `PersistOptions::read_only`.
+    ///
+    /// ```no_run
+    /// use lru_cache::PersistentLruCache;
+    ///
+    /// let cache: PersistentLruCache =
+    ///     PersistentLruCache::open_read_only("/var/cache/app/cache.txt").unwrap();
+    /// for (key, value) in cache.iter() {
+    ///     println!("{} = {}", key, value);
+    /// }
+    /// ```
+    pub fn open_read_only(path: &str) -> Result<Self, CacheError> {
+        let options = PersistOptions::new().read_only(true);
+        Self::with_options(usize::MAX, path, options)
+    }
+
+    /// Crée un cache persistant avec des options explicites
+    pub fn with_options(
+        capacity: usize,
+        path: &str,
+        options: PersistOptions,
+    ) -> Result<Self, CacheError> {
         let mut cache = Self {
-            capacity,
-            items: HashMap::new(),
-            usage: Vec::new(),
+            cache: LruCache::new(capacity),
             file_path: Some(path.to_string()),
+            options,
+            unsaved: 0,
+            last_save: Instant::now(),
+            last_sync: Instant::now(),
+            appended: 0,
+            compacted_len: 0,
+            log: None,
+            lock: None,
+            directory: None,
+            shards: None,
+            unloaded: LruCache::new(capacity),
+            load_report: LoadReport::default(),
+            warm_report: None,
         };
+        if !cache.options.read_only {
+            cache.lock = acquire_lock(Path::new(path), cache.options.lock)?;
+        }
+
+        if let Storage::Sharded { shards } = cache.options.storage {
+            cache.shards = Some(ShardFiles::new(shards));
+        }
 
         // Charger depuis le fichier s'il existe
-        if Path::new(path).exists() {
+        let exists = if let Storage::Directory = cache.options.storage {
+            cache.directory = Some(EntryDirectory::new(Path::new(path)));
+            EntryDirectory::index_path(Path::new(path)).exists()
+        } else {
+            let journal_exists = cache.log_path().is_some_and(|log| log.exists());
+            Path::new(path).exists() || journal_exists
+        };
+        if exists {
             cache.load()?;
+        } else if cache.options.read_only {
+            // Rien à relire, et rien ne doit être créé
+            return Err(CacheError::NotFound(PathBuf::from(path)));
+        }
+        if !cache.options.read_only {
+            cache.open_log(false)?;
+            if let (false, Some(seed)) = (exists, cache.options.seed.clone()) {
+                cache.warm_report = Some(cache.warm_from(&seed.to_string_lossy())?);
+            }
         }
 
         Ok(cache)
     }
 
-    pub fn put(&mut self, key: String, value: String) -> Option<String> {
-        if self.capacity == 0 {
-            return None;
-        }
+    /// Insère une entrée
+    ///
+    /// Une erreur de sauvegarde automatique est transmise au gestionnaire
+    /// `PersistOptions::on_error`; voir `try_put` pour la récupérer.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let (result, saved) = self.put_inner(key, value, None);
+        self.report(saved);
+        result
+    }
 
-        let result = if let Some(old) = self.items.insert(key.clone(), value) {
-            self.move_to_recent(&key);
-            Some(old)
-        } else {
-            if self.items.len() > self.capacity {
-                if let Some(lru_key) = self.usage.first().cloned() {
-                    self.items.remove(&lru_key);
-                    self.usage.retain(|k| k != &lru_key);
-                }
-            }
-            self.usage.push(key);
-            None
-        };
+    /// Insère une entrée qui expire après `ttl`, voir `LruCache::put_with_ttl`
+    ///
+    /// Le TTL n'est sauvegardé qu'avec `PersistOptions::persist_metadata`.
+    pub fn put_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let (result, saved) = self.put_inner(key, value, Some(ttl));
+        self.report(saved);
+        result
+    }
 
-        // Auto-save
-        if let Some(ref path) = self.file_path {
-            let _ = self.save_to(path);
-        }
+    /// Comme `put`, mais renvoie l'erreur de sauvegarde automatique
+    ///
+    /// L'entrée est insérée en mémoire même en cas d'erreur; elle reste en
+    /// attente de sauvegarde.
+    pub fn try_put(&mut self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        let (result, saved) = self.put_inner(key, value, None);
+        saved.map(|()| result)
+    }
 
+    /// Insère un lot d'entrées et renvoie celles évincées, voir
+    /// `LruCache::put_many`
+    ///
+    /// Le lot compte comme une seule écriture pour la sauvegarde: une seule
+    /// sauvegarde automatique (ou un seul ajout au journal, une seule
+    /// réécriture de l'index en mode dossier) pour tout le lot.
+    pub fn put_many<I>(&mut self, entries: I) -> Vec<(K, V)>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let (evicted, saved) = self.put_many_inner(entries);
+        self.report(saved);
+        evicted
+    }
+
+    /// Comme `put_many`, mais renvoie l'erreur de sauvegarde automatique
+    pub fn try_put_many<I>(&mut self, entries: I) -> Result<Vec<(K, V)>, CacheError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let (evicted, saved) = self.put_many_inner(entries);
+        saved.map(|()| evicted)
+    }
+
+    /// Retire une entrée; compte comme une écriture pour la sauvegarde
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (result, saved) = self.remove_inner(key);
+        self.report(saved);
         result
     }
 
-    pub fn get(&mut self, key: &str) -> Option<&String> {
-        if self.items.contains_key(key) {
-            self.move_to_recent(&key.to_string());
-            self.items.get(key)
-        } else {
-            None
-        }
+    /// Comme `remove`, mais renvoie l'erreur de sauvegarde automatique
+    pub fn try_remove(&mut self, key: &K) -> Result<Option<V>, CacheError> {
+        let (result, saved) = self.remove_inner(key);
+        saved.map(|()| result)
+    }
+
+    /// Retire un lot de clés et renvoie les entrées retirées; le lot compte
+    /// comme une seule écriture pour la sauvegarde
+    ///
+    /// Les clés pas encore lues (`lazy_load`) sont retirées sans être
+    /// décodées: elles ne figurent pas dans le résultat.
+    pub fn remove_many<I>(&mut self, keys: I) -> Vec<(K, V)>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let (removed, saved) = self.remove_many_inner(keys);
+        self.report(saved);
+        removed
     }
 
-    fn move_to_recent(&mut self, key: &String) {
-        self.usage.retain(|k| k != key);
-        self.usage.push(key.clone());
+    /// Comme `remove_many`, mais renvoie l'erreur de sauvegarde automatique
+    pub fn try_remove_many<I>(&mut self, keys: I) -> Result<Vec<(K, V)>, CacheError>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let (removed, saved) = self.remove_many_inner(keys);
+        saved.map(|()| removed)
     }
 
-    fn save_to(&self, path: &str) -> std::io::Result<()> {
-        let mut file = File::create(path)?;
-        writeln!(file, "{}", self.capacity)?;
+    /// Retire toutes les entrées; compte comme une écriture pour la
+    /// sauvegarde
+    ///
+    /// En modes journal, dossier et segmenté, chaque clé retirée est
+    /// enregistrée comme une suppression.
+    pub fn clear(&mut self) {
+        let result = self.clear_inner();
+        self.report(result);
+    }
 
-        for key in &self.usage {
-            if let Some(val) = self.items.get(key) {
-                writeln!(file, "{}:{}", key, val)?;
-            }
-        }
+    /// Comme `clear`, mais renvoie l'erreur de sauvegarde automatique
+    pub fn try_clear(&mut self) -> Result<(), CacheError> {
+        self.clear_inner()
+    }
 
-        Ok(())
+    fn clear_inner(&mut self) -> Result<(), CacheError> {
+        self.check_writable()?;
+        let records = if self.log.is_some() || self.directory.is_some() || self.shards.is_some() {
+            self.unloaded
+                .iter()
+                .map(|(_, raw)| Record::Remove(raw.key.clone()))
+                .chain(
+                    self.cache
+                        .iter()
+                        .map(|(key, _)| Record::Remove(key.to_bytes())),
+                )
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.cache.clear();
+        self.unloaded.clear();
+        self.record_write(records)
     }
 
-    fn load(&mut self) -> std::io::Result<()> {
-        if let Some(ref path) = self.file_path.clone() {
-            let file = File::open(path)?;
-            let reader = BufReader::new(file);
-            let mut lines = reader.lines();
+    /// Nombre d'entrées, clés pas encore lues (`lazy_load`) comprises
+    pub fn len(&self) -> usize {
+        self.cache.len() + self.unloaded.len()
+    }
 
-            if let Some(Ok(cap_line)) = lines.next() {
-                self.capacity = cap_line.parse().unwrap_or(self.capacity);
-            }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-            for line in lines {
-                if let Ok(content) = line {
-                    if let Some(pos) = content.find(':') {
-                        let k = content[..pos].to_string();
-                        let v = content[pos + 1..].to_string();
-                        self.items.insert(k.clone(), v);
-                        self.usage.push(k);
-                    }
+    /// Nombre maximal d'entrées, plafonné par celui enregistré dans le
+    /// fichier au chargement
+    pub fn capacity(&self) -> usize {
+        self.cache.capacity()
+    }
+
+    /// Indique si la clé est présente, sans modifier l'ordre LRU ni lire sa
+    /// valeur sur le disque
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.cache.contains_key(key) || self.unloaded.contains_key(key)
+    }
+
+    fn put_inner(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Option<Duration>,
+    ) -> (Option<V>, Result<(), CacheError>) {
+        if let Err(err) = self.check_writable() {
+            return (None, Err(err));
+        }
+        if self.cache.capacity() == 0 {
+            return (None, Ok(()));
+        }
+        self.load_entry(&key);
+
+        let mut records = Vec::new();
+        let per_entry = self.log.is_some() || self.directory.is_some() || self.shards.is_some();
+        let full = self.cache.len() + self.unloaded.len() >= self.cache.capacity();
+        if full && !self.cache.holds(&key) {
+            let oldest = self.unloaded.iter().next().map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                // Les clés pas encore lues sont les moins récentes
+                let raw = self.unloaded.remove(&oldest).expect("clé non chargée");
+                let mut stats = self.cache.stats();
+                stats.evictions += 1;
+                self.cache.set_stats(stats);
+                if per_entry {
+                    records.push(Record::Remove(raw.key));
+                }
+            } else if per_entry {
+                // L'entrée évincée doit perdre son fichier (ou son segment);
+                // dans un journal, les lectures ne sont pas enregistrées: la
+                // relecture n'évincerait pas forcément la même clé. C'est la
+                // clé que `put` évince qui compte, même expirée
+                if let Some(lru) = self.cache.lru_key() {
+                    records.push(Record::Remove(lru.to_bytes()));
                 }
             }
         }
+        if per_entry {
+            records.push(Record::Put(key.to_bytes(), value.to_bytes()));
+        }
 
-        Ok(())
+        let result = match ttl {
+            Some(ttl) => self.cache.put_with_ttl(key, value, ttl),
+            None => self.cache.put(key, value),
+        };
+        (result, self.record_write(records))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
+    fn put_many_inner<I>(&mut self, entries: I) -> (Vec<(K, V)>, Result<(), CacheError>)
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        if let Err(err) = self.check_writable() {
+            return (Vec::new(), Err(err));
+        }
+        if self.cache.capacity() == 0 {
+            return (Vec::new(), Ok(()));
+        }
+        let entries: Vec<(K, V)> = entries.into_iter().collect();
+        for (key, _) in &entries {
+            self.load_entry(key);
+        }
 
-    #[test]
-    fn test_persistent() {
-        let path = "test_cache_persist.txt";
+        let mut records = Vec::new();
+        let mut evicted = Vec::new();
+        let per_entry = self.log.is_some() || self.directory.is_some() || self.shards.is_some();
 
-        {
-            let mut cache = PersistentLruCache::new_persistent(2, path).unwrap();
-            cache.put("key1".into(), "val1".into());
+        // Les clés pas encore lues sont les moins récentes: elles partent
+        // en premier
+        let mut new_keys = HashSet::new();
+        for (key, _) in &entries {
+            if !self.cache.holds(key) {
+                new_keys.insert(key);
+            }
+        }
+        let total = self.cache.len() + self.unloaded.len() + new_keys.len();
+        let excess = total.saturating_sub(self.cache.capacity());
+        let oldest: Vec<K> = self
+            .unloaded
+            .iter()
+            .take(excess)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in oldest {
+            let raw = self.unloaded.remove(&key).expect("clé non chargée");
+            let mut stats = self.cache.stats();
+            stats.evictions += 1;
+            self.cache.set_stats(stats);
+            if let Some(value) = self.raw_value(&raw).ok().and_then(|v| V::from_bytes(&v)) {
+                evicted.push((key, value));
+            }
+            if per_entry {
+                records.push(Record::Remove(raw.key));
+            }
         }
 
-        {
-            let mut cache2 = PersistentLruCache::new_persistent(2, path).unwrap();
-            assert_eq!(cache2.get("key1"), Some(&"val1".to_string()));
+        let puts: Vec<Record> = entries
+            .iter()
+            .map(|(key, value)| Record::Put(key.to_bytes(), value.to_bytes()))
+            .collect();
+        let batch: HashSet<Vec<u8>> = entries.iter().map(|(key, _)| key.to_bytes()).collect();
+        let in_memory = self.cache.put_many(entries);
+        if per_entry {
+            // Chaque éviction est enregistrée, comme dans `put_inner`. Une
+            // clé antérieure au lot part avant les ajouts; une clé du lot
+            // après le sien, sans quoi son fichier resterait
+            let (from_batch, older): (Vec<Record>, Vec<Record>) = in_memory
+                .iter()
+                .map(|(key, _)| Record::Remove(key.to_bytes()))
+                .partition(|record| matches!(record, Record::Remove(key) if batch.contains(key)));
+            records.extend(older);
+            records.extend(puts);
+            records.extend(from_batch);
+        }
+        evicted.extend(in_memory);
+        (evicted, self.record_write(records))
+    }
+
+    fn remove_inner(&mut self, key: &K) -> (Option<V>, Result<(), CacheError>) {
+        if let Err(err) = self.check_writable() {
+            return (None, Err(err));
+        }
+        self.load_entry(key);
+        let Some(result) = self.cache.remove(key) else {
+            return (None, Ok(()));
+        };
+        let records = if self.log.is_some() || self.directory.is_some() || self.shards.is_some() {
+            vec![Record::Remove(key.to_bytes())]
+        } else {
+            Vec::new()
+        };
+        (Some(result), self.record_write(records))
+    }
+
+    fn remove_many_inner<I>(&mut self, keys: I) -> (Vec<(K, V)>, Result<(), CacheError>)
+    where
+        I: IntoIterator<Item = K>,
+    {
+        if let Err(err) = self.check_writable() {
+            return (Vec::new(), Err(err));
         }
+        let mut records = Vec::new();
+        let mut in_memory = Vec::new();
+        for key in keys {
+            match self.unloaded.remove(&key) {
+                Some(raw) => records.push(Record::Remove(raw.key)),
+                None => in_memory.push(key),
+            }
+        }
+        let removed = self.cache.remove_many(in_memory);
+        if records.is_empty() && removed.is_empty() {
+            return (removed, Ok(()));
+        }
+
+        if self.log.is_some() || self.directory.is_some() || self.shards.is_some() {
+            records.extend(
+                removed
+                    .iter()
+                    .map(|(key, _)| Record::Remove(key.to_bytes())),
+            );
+        } else {
+            records.clear();
+        }
+        (removed, self.record_write(records))
+    }
+
+    fn check_writable(&self) -> Result<(), CacheError> {
+        match self.options.read_only {
+            true => Err(CacheError::ReadOnly),
+            false => Ok(()),
+        }
+    }
+
+    fn record_write(&mut self, records: Vec<Record>) -> Result<(), CacheError> {
+        self.unsaved += 1;
+
+        if self.directory.is_some() {
+            self.write_directory(records)?;
+            self.unsaved = 0;
+            return Ok(());
+        }
+
+        if let Some(ref mut shards) = self.shards {
+            for record in &records {
+                if let Record::Put(key, _) | Record::Remove(key) = record {
+                    shards.mark(key);
+                }
+            }
+        } else if !records.is_empty() {
+            let bytes = self.encode_records(&records);
+            let log = self
+                .log
+                .as_mut()
+                .expect("enregistrement sans journal ouvert");
+            // Un ajout raté laisserait le journal en retard: on compacte
+            if log.write_all(&bytes).is_err() {
+                return self.write_now();
+            }
+            self.appended += bytes.len() as u64;
+        }
+
+        if self.autosave_due() {
+            self.write_now()?;
+        }
+        Ok(())
+    }
+
+    /// Écrit les valeurs modifiées, puis l'index, puis supprime les fichiers
+    /// retirés: l'index ne référence jamais un fichier absent
+    fn write_directory(&mut self, records: Vec<Record>) -> Result<(), CacheError> {
+        let sync = self.sync_due();
+        let mut removed = Vec::new();
+        for record in records {
+            match record {
+                Record::Put(key, value) => {
+                    let bytes = self.apply_transforms(value);
+                    let directory = self.directory.as_mut().expect("mode dossier");
+                    directory.write_value(&key, &bytes, sync)?;
+                }
+                Record::Remove(key) => removed.push(key),
+                Record::Stats(_) => {}
+            }
+        }
+
+        self.write_index(sync)?;
+        let directory = self.directory.as_mut().expect("mode dossier");
+        for key in removed {
+            directory.remove_value(&key)?;
+        }
+        Ok(())
+    }
+
+    fn write_index(&self, sync: bool) -> Result<(), CacheError> {
+        let directory = self.directory.as_ref().expect("mode dossier");
+        let stats = self
+            .options
+            .persist_stats
+            .then(|| Record::Stats(self.cache.stats()));
+        let keys = self
+            .unloaded
+            .iter()
+            .map(|(_, raw)| raw.key.clone())
+            .chain(self.cache.iter().map(|(key, _)| key.to_bytes()));
+        let records: Vec<Record> = stats
+            .into_iter()
+            .chain(directory.index_records(keys))
+            .collect();
+
+        let mut bytes = Vec::new();
+        for record in &records {
+            journal::encode(record, &mut bytes);
+        }
+        let bytes = self.apply_transforms(bytes);
+        let root = Path::new(self.file_path.as_ref().expect("mode dossier"));
+        Ok(write_atomic(
+            &EntryDirectory::index_path(root),
+            &bytes,
+            false,
+            sync,
+        )?)
+    }
+
+    /// Sauvegarde les écritures en attente, les erreurs allant à `on_error`
+    pub(crate) fn flush_pending(&mut self) {
+        let result = self.flush();
+        self.report(result);
+    }
+
+    /// Sauvegarde les écritures en attente, s'il y en a
+    ///
+    /// Avec `Autosave::EveryN`, `Interval` ou `Manual`, une rafale de `put`
+    /// ne donne lieu qu'à une sauvegarde; `flush` la déclenche sans attendre,
+    /// pour l'appelant qui a besoin que ses écritures soient sur disque.
+    ///
+    /// ```no_run
+    /// use lru_cache::{Autosave, PersistOptions, PersistentLruCache};
+    ///
+    /// let options = PersistOptions::new().autosave(Autosave::Manual);
+    /// let mut cache: PersistentLruCache =
+    ///     PersistentLruCache::with_options(100, "cache.txt", options).unwrap();
+    /// for i in 0..50 {
+    ///     cache.put(i.to_string(), "x".into());
+    /// }
+    /// cache.flush().unwrap(); // une seule réécriture du fichier
+    /// ```
+    pub fn flush(&mut self) -> Result<(), CacheError> {
+        if self.unsaved == 0 {
+            return Ok(());
+        }
+        self.write_now()
+    }
+
+    /// Indique si des écritures attendent d'être sauvegardées
+    pub fn is_dirty(&self) -> bool {
+        self.unsaved > 0
+    }
+
+    /// Retire les entrées expirées, en une seule écriture; retourne leur
+    /// nombre
+    pub fn purge_expired(&mut self) -> usize {
+        let expired = self.cache.expired_keys();
+        if expired.is_empty() || self.check_writable().is_err() {
+            return 0;
+        }
+        self.remove_many(expired).len()
+    }
+
+    /// Évince les entrées les moins récentes jusqu'à n'en garder que `len`,
+    /// en une seule écriture; retourne le nombre d'entrées évincées
+    ///
+    /// Les clés pas encore lues (`lazy_load`) partent en premier.
+    pub fn shrink_to(&mut self, len: usize) -> usize {
+        if self.check_writable().is_err() {
+            return 0;
+        }
+        let oldest: Vec<K> = self
+            .unloaded
+            .iter()
+            .map(|(key, _)| key)
+            .chain(self.cache.iter().map(|(key, _)| key))
+            .take(self.len().saturating_sub(len))
+            .cloned()
+            .collect();
+        if oldest.is_empty() {
+            return 0;
+        }
+
+        // Compté avant l'écriture, que la sauvegarde automatique emporte
+        let mut stats = self.cache.stats();
+        stats.evictions += oldest.len() as u64;
+        self.cache.set_stats(stats);
+        let evicted = oldest.len();
+        let (_, saved) = self.remove_many_inner(oldest);
+        self.report(saved);
+        evicted
+    }
+
+    pub(crate) fn report(&self, result: Result<(), CacheError>) {
+        if let (Err(err), Some(handler)) = (result, &self.options.on_error) {
+            (handler.0)(&err);
+        }
+    }
+
+    /// Sauvegarde immédiatement dans le fichier associé
+    ///
+    /// Sans effet pour un cache sans fichier. En cas d'échec, les écritures
+    /// restent en attente et l'appel peut être retenté.
+    pub fn save(&mut self) -> Result<(), CacheError> {
+        self.write_now()
+    }
+
+    /// Sauvegarde dans `path`, qui devient le fichier associé au cache
+    ///
+    /// Permet de migrer un cache vers un autre fichier (ou un autre format
+    /// via les options d'origine).
+    pub fn save_as(&mut self, path: &str) -> Result<(), CacheError> {
+        self.check_writable()?;
+        let lock = acquire_lock(Path::new(path), self.options.lock)?;
+        if let Some(ref mut shards) = self.shards {
+            shards.mark_all();
+        }
+        if let Some(ref directory) = self.directory {
+            let mut moved = directory.moved_to(Path::new(path));
+            // Les valeurs pas encore lues sont copiées telles quelles
+            for (_, raw) in self.unloaded.iter() {
+                moved.write_value(&raw.key, &directory.read_value(&raw.key)?, true)?;
+            }
+            self.directory = Some(moved);
+            self.file_path = Some(path.to_string());
+        }
+        self.save_to(path)?;
+        self.lock = lock;
+        self.file_path = Some(path.to_string());
+        self.unsaved = 0;
+        self.last_save = Instant::now();
+        Ok(self.open_log(true)?)
+    }
+
+    /// Remplace le contenu en mémoire par celui du fichier associé
+    ///
+    /// Les écritures non sauvegardées sont perdues. En cas d'erreur, le
+    /// contenu en mémoire est conservé tel quel.
+    ///
+    /// ```no_run
+    /// use lru_cache::{CacheError, PersistentLruCache};
+    ///
+    /// let mut cache = PersistentLruCache::new_persistent(3, "cache.txt").unwrap();
+    /// match cache.reload() {
+    ///     Ok(()) => {}
+    ///     Err(CacheError::NotFound(_)) => println!("rien à relire"),
+    ///     Err(CacheError::Corrupted(raison)) => eprintln!("fichier ignoré: {}", raison),
+    ///     Err(CacheError::InvalidRecord(record)) => eprintln!("à corriger: {}", record),
+    ///     Err(err) => eprintln!("{}", err),
+    /// }
+    /// ```
+    pub fn reload(&mut self) -> Result<(), CacheError> {
+        self.load()
+    }
+
+    /// Enregistrements écartés au dernier chargement, voir
+    /// `PersistOptions::lenient`
+    pub fn load_report(&self) -> &LoadReport {
+        &self.load_report
+    }
+
+    /// Écrit un instantané dans `writer` plutôt que dans le fichier associé
+    ///
+    /// L'instantané suit le `format`, les transformations et la somme de
+    /// contrôle configurés, quel que soit le mode de stockage.
+    ///
+    /// ```
+    /// use lru_cache::PersistentLruCache;
+    ///
+    /// let mut cache = PersistentLruCache::new(3);
+    /// cache.put("a".to_string(), "1".to_string());
+    ///
+    /// let mut buffer = Vec::new();
+    /// cache.save_to_writer(&mut buffer).unwrap();
+    ///
+    /// let mut copy = PersistentLruCache::new(3);
+    /// copy.load_from_reader(buffer.as_slice()).unwrap();
+    /// assert_eq!(copy.get("a"), Some(&"1".to_string()));
+    /// ```
+    pub fn save_to_writer<W: Write>(&self, mut writer: W) -> Result<(), CacheError> {
+        writer.write_all(&self.snapshot_bytes()?)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Remplace le contenu en mémoire par un instantané lu depuis `reader`
+    ///
+    /// Le fichier associé n'est pas modifié: le nouveau contenu compte comme
+    /// une écriture en attente de sauvegarde. En cas d'erreur, le contenu en
+    /// mémoire est conservé tel quel.
+    pub fn load_from_reader<R: Read>(&mut self, mut reader: R) -> Result<(), CacheError> {
+        self.check_writable()?;
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut cache = LruCache::new(self.cache.capacity());
+        let mut recovery = Recovery::new(self.options.lenient);
+        self.restore_snapshot(&mut cache, &bytes, &mut recovery)?;
+        self.cache = cache;
+        self.unloaded = LruCache::new(self.cache.capacity());
+        self.load_report = recovery.into_report();
+        if let Some(ref mut shards) = self.shards {
+            shards.mark_all();
+        }
+        self.unsaved += 1;
+        Ok(())
+    }
+
+    /// Ajoute au cache les entrées d'un fichier de départ
+    ///
+    /// Le fichier est lu avec le format et les options du cache: un
+    /// instantané écrit par `save` ou `backup`, ou un fichier clé/valeur
+    /// écrit à la main (par exemple en `Format::Csv`). Les enregistrements
+    /// illisibles ou expirés sont comptés dans `WarmReport::skipped` plutôt
+    /// que de faire échouer le chargement. Les clés déjà présentes gardent
+    /// leur valeur; le fichier associé est écrit une fois, à la fin.
+    pub fn warm_from(&mut self, path: &str) -> Result<WarmReport, CacheError> {
+        self.check_writable()?;
+        let bytes = read(Path::new(path))?;
+        let mut seed = LruCache::new(self.cache.capacity());
+        let mut recovery = Recovery::new(true);
+        let expired = self.restore_snapshot(&mut seed, &bytes, &mut recovery)?;
+
+        let mut report = WarmReport {
+            loaded: 0,
+            skipped: expired + recovery.into_report().skipped.len(),
+        };
+        let autosave = std::mem::replace(&mut self.options.autosave, Autosave::Manual);
+        let mut saved = Ok(());
+        let keys: Vec<K> = seed.iter().map(|(key, _)| key.clone()).collect();
+        for key in keys {
+            let ttl = seed.time_to_live(&key);
+            let Some(value) = seed.remove(&key) else {
+                continue;
+            };
+            if self.contains_key(&key) {
+                continue;
+            }
+            let (_, result) = self.put_inner(key, value, ttl);
+            saved = saved.and(result);
+            report.loaded += 1;
+        }
+        self.options.autosave = autosave;
+
+        saved?;
+        if self.unsaved > 0 && self.autosave_due() {
+            self.write_now()?;
+        }
+        Ok(report)
+    }
+
+    /// Préchargement fait à l'ouverture, voir `PersistOptions::warm_from`
+    pub fn warm_report(&self) -> Option<&WarmReport> {
+        self.warm_report.as_ref()
+    }
+
+    /// Copie cohérente du cache dans `path`, sans arrêter le processus
+    ///
+    /// Écrit un instantané des entrées en mémoire (et de celles pas encore
+    /// lues en mode dossier) plutôt que de copier le fichier associé, qui peut
+    /// être en cours d'écriture ou réparti entre instantané et journal.
+    ///
+    /// ```no_run
+    /// use lru_cache::PersistentLruCache;
+    ///
+    /// let mut cache = PersistentLruCache::new_persistent(100, "cache.txt").unwrap();
+    /// cache.backup("cache-2026-10-15.txt").unwrap();
+    /// // ...
+    /// cache.restore("cache-2026-10-15.txt").unwrap();
+    /// ```
+    pub fn backup(&self, path: &str) -> Result<(), CacheError> {
+        let bytes = self.snapshot_bytes()?;
+        Ok(write_atomic(Path::new(path), &bytes, false, true)?)
+    }
+
+    /// Remplace le contenu du cache par une copie écrite avec `backup`
+    ///
+    /// Le fichier associé est réécrit aussitôt. En cas d'erreur de lecture,
+    /// le contenu en mémoire est conservé tel quel.
+    pub fn restore(&mut self, path: &str) -> Result<(), CacheError> {
+        let bytes = read(Path::new(path))?;
+        self.load_from_reader(bytes.as_slice())?;
+        self.write_now()
+    }
+
+    /// Réécrit le journal avec les seules entrées présentes
+    ///
+    /// En mode `journal`, écrit un nouvel instantané et vide le journal; dans
+    /// les autres modes, équivaut à `save()`.
+    pub fn compact(&mut self) -> Result<(), CacheError> {
+        self.write_now()
+    }
+
+    fn write_now(&mut self) -> Result<(), CacheError> {
+        self.check_writable()?;
+        let Some(path) = self.file_path.clone() else {
+            // Sans fichier, rien n'attend d'être écrit: le cache est à jour
+            self.unsaved = 0;
+            self.last_save = Instant::now();
+            return Ok(self.open_log(true)?);
+        };
+        if self.options.rotate && self.over_size_limit() {
+            rotate_file(Path::new(&path))?;
+        }
+        self.save_to(&path)?;
+        self.unsaved = 0;
+        self.last_save = Instant::now();
+        self.open_log(true)?;
+
+        if self.over_size_limit() {
+            let (size, limit) = (self.disk_size(), self.options.max_file_size.unwrap_or(0));
+            let path = PathBuf::from(path);
+            self.report(Err(CacheError::FileTooLarge { path, size, limit }));
+        }
+        Ok(())
+    }
+
+    /// Octets occupés sur disque par l'instantané et le journal
+    fn disk_size(&self) -> u64 {
+        match self.options.storage {
+            Storage::Log { .. } | Storage::Journal { .. } => self.compacted_len + self.appended,
+            Storage::Snapshot => self
+                .file_path
+                .as_ref()
+                .and_then(|path| fs::metadata(path).ok())
+                .map_or(0, |m| m.len()),
+            Storage::Directory | Storage::Sharded { .. } => 0,
+        }
+    }
+
+    fn over_size_limit(&self) -> bool {
+        self.options
+            .max_file_size
+            .is_some_and(|max| self.disk_size() > max)
+    }
+
+    /// Fichier recevant les ajouts, selon le mode de stockage
+    fn log_path(&self) -> Option<PathBuf> {
+        let path = Path::new(self.file_path.as_ref()?);
+        match self.options.storage {
+            Storage::Snapshot | Storage::Directory | Storage::Sharded { .. } => None,
+            Storage::Log { .. } => Some(path.to_path_buf()),
+            Storage::Journal { .. } => Some(sibling(path, "journal")),
+        }
+    }
+
+    /// (Ré)ouvre le journal en ajout après une sauvegarde: la compaction
+    /// remplace le fichier, un instantané rend le journal obsolète
+    fn open_log(&mut self, saved: bool) -> std::io::Result<()> {
+        if let Some(path) = self.log_path() {
+            let truncate = saved && matches!(self.options.storage, Storage::Journal { .. });
+            let mut options = OpenOptions::new();
+            if truncate {
+                options.write(true).truncate(true);
+            } else {
+                options.append(true);
+            }
+            let log = options.create(true).open(path)?;
+
+            let log_len = log.metadata()?.len();
+            if let Storage::Journal { .. } = self.options.storage {
+                let snapshot = self.file_path.as_ref().and_then(|p| fs::metadata(p).ok());
+                self.compacted_len = snapshot.map_or(0, |m| m.len());
+                self.appended = log_len;
+            } else {
+                self.compacted_len = log_len;
+                self.appended = 0;
+            }
+            self.log = Some(log);
+        }
+        Ok(())
+    }
+
+    fn compaction_due(&self) -> bool {
+        let above = self
+            .options
+            .compact_above
+            .is_some_and(|max| self.appended > max);
+        let ratio = self
+            .options
+            .compact_ratio
+            .is_some_and(|ratio| self.appended as f64 > ratio * self.compacted_len as f64);
+        above || ratio || self.over_size_limit()
+    }
+
+    /// Indique si l'écriture en cours doit être suivie d'un `fsync`
+    fn sync_due(&mut self) -> bool {
+        let due = match self.options.durability {
+            Durability::None => false,
+            Durability::EverySave => true,
+            Durability::Interval(period) => self.last_sync.elapsed() >= period,
+        };
+        if due {
+            self.last_sync = Instant::now();
+        }
+        due
+    }
+
+    fn autosave_due(&self) -> bool {
+        match self.options.storage {
+            Storage::Log { compact_after: n } | Storage::Journal { snapshot_every: n } => {
+                return self.unsaved >= n || self.compaction_due();
+            }
+            Storage::Snapshot | Storage::Sharded { .. } => {}
+            Storage::Directory => return false,
+        }
+        match self.options.autosave {
+            Autosave::EveryWrite => true,
+            Autosave::EveryN(n) => self.unsaved >= n.max(1),
+            Autosave::Interval(period) => self.last_save.elapsed() >= period,
+            Autosave::Manual => false,
+        }
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.load_entry(key);
+        self.cache.get(key)
+    }
+
+    /// Récupère plusieurs valeurs (voir `LruCache::get_many`); les clés pas
+    /// encore lues (`lazy_load`) le sont d'abord
+    pub fn get_many<I>(&mut self, keys: I) -> Vec<Option<&V>>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        let keys: Vec<K> = keys.into_iter().collect();
+        for key in &keys {
+            self.load_entry(key);
+        }
+        self.cache.get_many(keys)
+    }
+
+    /// Valeur d'une clé sans la marquer comme récente
+    ///
+    /// Les clés pas encore lues (`lazy_load`) sont ignorées.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.cache.peek(key)
+    }
+
+    /// Entrées en mémoire, de la moins récente à la plus récente
+    ///
+    /// Les clés pas encore lues (`lazy_load`) sont ignorées.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.cache.iter()
+    }
+
+    /// Décode la valeur d'une clé pas encore chargée (`lazy_load`), qui
+    /// devient la plus récente; une erreur de lecture va à `on_error` et
+    /// l'entrée est abandonnée
+    fn load_entry<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let ttl = self.unloaded.time_to_live(key);
+        let hits = self.unloaded.hit_count(key).unwrap_or(0);
+        let Some(raw) = self.unloaded.remove(key) else {
+            return;
+        };
+        match self.raw_value(&raw) {
+            Ok(val) => {
+                if let (Some(k), Some(v)) = (K::from_bytes(&raw.key), V::from_bytes(&val)) {
+                    self.cache.restore_entry(k.clone(), v);
+                    let expires_at = ttl.and_then(|ttl| SystemTime::now().checked_add(ttl));
+                    self.cache.restore_metadata(&k, expires_at, hits);
+                }
+            }
+            Err(err) => self.report(Err(err)),
+        }
+    }
+
+    /// Octets de la valeur d'une entrée pas encore décodée
+    fn raw_value(&self, raw: &Raw) -> Result<Vec<u8>, CacheError> {
+        match (&raw.value, &self.directory) {
+            (Some(value), _) => Ok(value.clone()),
+            (None, Some(directory)) => self.revert_transforms(directory.read_value(&raw.key)?),
+            (None, None) => unreachable!("valeur hors instantané sans dossier"),
+        }
+    }
+
+    /// Confie les sauvegardes à un thread qui écrit au plus une fois par
+    /// `interval`, puis à la fermeture
+    ///
+    /// Les écritures ne font que marquer le cache comme modifié: leur
+    /// latence ne dépend plus de celle du disque. Une sauvegarde en cours
+    /// retient néanmoins les accès jusqu'à sa fin.
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use lru_cache::PersistentLruCache;
+    ///
+    /// let cache: PersistentLruCache<String, i32> =
+    ///     PersistentLruCache::open(100, "compteurs.cache").unwrap();
+    /// let cache = cache.save_in_background(Duration::from_secs(1));
+    ///
+    /// cache.put("visites".to_string(), 1);
+    /// // sauvegardé au plus tard une seconde après, ou à la fermeture
+    /// ```
+    pub fn save_in_background(mut self, interval: Duration) -> BackgroundPersistentLruCache<K, V>
+    where
+        K: Send + 'static,
+        V: Send + 'static,
+    {
+        self.options.autosave = Autosave::Manual;
+        BackgroundPersistentLruCache::new(self, interval)
+    }
+
+    /// Compteurs de hits, misses et évictions
+    ///
+    /// Restaurés depuis le fichier s'ils y ont été sauvegardés.
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    fn save_to(&mut self, path: &str) -> Result<(), CacheError> {
+        if self.directory.is_some() {
+            // Sauvegarde complète: toutes les valeurs, l'index, puis ménage
+            let records = self
+                .cache
+                .iter()
+                .map(|(key, val)| Record::Put(key.to_bytes(), val.to_bytes()))
+                .collect();
+            self.write_directory(records)?;
+            let directory = self.directory.as_ref().expect("mode dossier");
+            return Ok(directory.remove_orphans()?);
+        }
+
+        if self.shards.is_some() {
+            let dirty = self.shards.as_ref().expect("mode segmenté").dirty();
+            let files = dirty
+                .into_iter()
+                .map(|shard| Ok((shard, self.shard_bytes(shard)?)))
+                .collect::<Result<Vec<_>, CacheError>>()?;
+            let sync = self.sync_due();
+            let shards = self.shards.as_mut().expect("mode segmenté");
+            return Ok(shards.write(Path::new(path), files, self.options.keep_backup, sync)?);
+        }
+
+        if let Storage::Log { .. } = self.options.storage {
+            let stats = self
+                .options
+                .persist_stats
+                .then(|| Record::Stats(self.cache.stats()));
+            let records: Vec<Record> = stats
+                .into_iter()
+                .chain(
+                    self.cache
+                        .iter()
+                        .map(|(key, val)| Record::Put(key.to_bytes(), val.to_bytes())),
+                )
+                .collect();
+            let bytes = self.encode_records(&records);
+            return Ok(write_atomic(
+                Path::new(path),
+                &bytes,
+                self.options.keep_backup,
+                self.sync_due(),
+            )?);
+        }
+
+        let bytes = self.snapshot_bytes()?;
+        Ok(write_atomic(
+            Path::new(path),
+            &bytes,
+            self.options.keep_backup,
+            self.sync_due(),
+        )?)
+    }
+
+    /// Instantané encodé dans le format configuré, transformé et scellé
+    fn snapshot_bytes(&self) -> Result<Vec<u8>, CacheError> {
+        let mut entries = Vec::new();
+        for (_, raw) in self.unloaded.iter() {
+            entries.push((raw.key.clone(), self.raw_value(raw)?));
+        }
+        entries.extend(
+            self.cache
+                .iter()
+                .map(|(key, val)| (key.to_bytes(), val.to_bytes())),
+        );
+
+        let snapshot = Snapshot {
+            capacity: Some(self.cache.capacity()),
+            stats: self.options.persist_stats.then(|| self.cache.stats()),
+            entries,
+            metadata: if self.options.persist_metadata {
+                self.unloaded
+                    .iter()
+                    .map(|(key, _)| entry_meta(&self.unloaded, key))
+                    .chain(
+                        self.cache
+                            .iter()
+                            .map(|(key, _)| entry_meta(&self.cache, key)),
+                    )
+                    .collect()
+            } else {
+                Vec::new()
+            },
+        };
+        self.encode_snapshot(&snapshot)
+    }
+
+    /// Instantané des seules entrées d'un segment
+    fn shard_bytes(&self, shard: usize) -> Result<Vec<u8>, CacheError> {
+        let shards = self.shards.as_ref().expect("mode segmenté");
+        let mut entries = Vec::new();
+        let mut metadata = Vec::new();
+        for (key, val) in self.cache.iter() {
+            let bytes = key.to_bytes();
+            if shards.shard_of(&bytes) != shard {
+                continue;
+            }
+            if self.options.persist_metadata {
+                metadata.push(entry_meta(&self.cache, key));
+            }
+            entries.push((bytes, val.to_bytes()));
+        }
+
+        let snapshot = Snapshot {
+            capacity: Some(self.cache.capacity()),
+            stats: self.options.persist_stats.then(|| self.cache.stats()),
+            entries,
+            metadata,
+        };
+        self.encode_snapshot(&snapshot)
+    }
+
+    fn encode_snapshot(&self, snapshot: &Snapshot) -> Result<Vec<u8>, CacheError> {
+        let mut bytes = self.apply_transforms(self.options.format.encode(snapshot)?);
+        if self.options.checksum {
+            bytes = checksum::seal(&bytes);
+        }
+        Ok(bytes)
+    }
+
+    fn apply_transforms(&self, bytes: Vec<u8>) -> Vec<u8> {
+        self.options
+            .transforms
+            .iter()
+            .fold(bytes, |bytes, transform| transform.apply(&bytes))
+    }
+
+    fn revert_transforms(&self, bytes: Vec<u8>) -> Result<Vec<u8>, CacheError> {
+        self.options
+            .transforms
+            .iter()
+            .rev()
+            .try_fold(bytes, |bytes, transform| transform.revert(&bytes))
+            .map_err(|err| CacheError::corrupted(err.to_string()))
+    }
+
+    /// Sans transformation, les enregistrements sont mis bout à bout; sinon
+    /// chacun est transformé et préfixé par sa longueur
+    fn encode_records(&self, records: &[Record]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for record in records {
+            if self.options.transforms.is_empty() {
+                journal::encode(record, &mut out);
+            } else {
+                let mut bytes = Vec::new();
+                journal::encode(record, &mut bytes);
+                let bytes = self.apply_transforms(bytes);
+                out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                out.extend_from_slice(&bytes);
+            }
+        }
+        out
+    }
+
+    /// S'arrête au premier enregistrement incomplet (écriture interrompue)
+    fn decode_records(
+        &self,
+        mut bytes: &[u8],
+        recovery: &mut Recovery,
+    ) -> Result<Vec<Record>, CacheError> {
+        if self.options.transforms.is_empty() {
+            return journal::decode(bytes, recovery);
+        }
+
+        let mut records = Vec::new();
+        let mut index = 0;
+        let mut offset = 0;
+        while let Some(len) = bytes.get(..4) {
+            let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+            let Some(frame) = bytes.get(4..4 + len) else {
+                break;
+            };
+            match self.revert_transforms(frame.to_vec()) {
+                Ok(plain) => records.extend(journal::decode(&plain, recovery)?),
+                Err(CacheError::Corrupted(reason)) => {
+                    recovery.skip(InvalidRecord::new(index, reason).at(offset))?
+                }
+                Err(err) => return Err(err),
+            }
+            bytes = &bytes[4 + len..];
+            index += 1;
+            offset += 4 + len;
+        }
+        Ok(records)
+    }
+
+    /// Vérifie le fichier associé sans toucher au contenu en mémoire
+    ///
+    /// Enveloppe, version, sommes de contrôle et chaque enregistrement sont
+    /// contrôlés, ainsi que le nombre d'entrées au regard de la capacité de
+    /// l'en-tête. Une erreur signale un fichier inutilisable; sinon le
+    /// rapport liste tous les enregistrements fautifs.
+    pub fn verify(&self) -> Result<LoadReport, CacheError> {
+        let mut recovery = Recovery::auditing();
+        self.read_files(&mut recovery)?;
+        Ok(recovery.into_report())
+    }
+
+    /// Comme `verify`, le premier enregistrement fautif étant une erreur
+    ///
+    /// ```no_run
+    /// use lru_cache::PersistentLruCache;
+    ///
+    /// let cache = PersistentLruCache::new_persistent(100, "cache.txt").unwrap();
+    /// let ready = cache.health_check().is_ok();
+    /// ```
+    pub fn health_check(&self) -> Result<(), CacheError> {
+        match self.verify()?.skipped.into_iter().next() {
+            Some(record) => Err(CacheError::InvalidRecord(record)),
+            None => Ok(()),
+        }
+    }
+
+    fn load(&mut self) -> Result<(), CacheError> {
+        let mut recovery = Recovery::new(self.options.lenient);
+        let Some(loaded) = self.read_files(&mut recovery)? else {
+            return Ok(());
+        };
+
+        if loaded.directory.is_some() {
+            self.directory = loaded.directory;
+        }
+        self.cache = loaded.cache;
+        self.unloaded = loaded.unloaded;
+        self.load_report = recovery.into_report();
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    /// Lit les fichiers associés, `None` pour un cache sans fichier
+    fn read_files(&self, recovery: &mut Recovery) -> Result<Option<Loaded<K, V>>, CacheError> {
+        let Some(ref path) = self.file_path else {
+            return Ok(None);
+        };
+        let path = Path::new(path);
+
+        let mut cache = LruCache::new(self.cache.capacity());
+        let mut unloaded = LruCache::new(self.cache.capacity());
+        let mut loaded_directory = None;
+        match self.options.storage {
+            Storage::Directory => {
+                let index = read(&EntryDirectory::index_path(path))?;
+                let records = journal::decode(&self.revert_transforms(index)?, recovery)?;
+                let mut directory = EntryDirectory::new(path);
+                let (keys, others) = directory.read_index(records)?;
+
+                for record in others {
+                    if let Record::Stats(stats) = record {
+                        cache.set_stats(stats);
+                    }
+                }
+                let skipped = match self.options.load_limit {
+                    Some(limit) => keys.len().saturating_sub(limit),
+                    None => 0,
+                };
+                for (index, key) in keys.into_iter().enumerate().skip(skipped) {
+                    let Some(k) = K::from_bytes(&key) else {
+                        recovery.skip(InvalidRecord::new(index, "clé illisible"))?;
+                        continue;
+                    };
+                    if self.options.lazy_load && !recovery.is_auditing() {
+                        unloaded.restore_entry(k, Raw { key, value: None });
+                        continue;
+                    }
+                    let val = directory
+                        .read_value(&key)
+                        .and_then(|val| self.revert_transforms(val));
+                    match val.map(|val| V::from_bytes(&val)) {
+                        Ok(Some(v)) => cache.restore_entry(k, v),
+                        Ok(None) => recovery.skip(InvalidRecord::new(index, "valeur illisible"))?,
+                        Err(CacheError::Corrupted(reason)) => {
+                            recovery.skip(InvalidRecord::new(index, reason))?
+                        }
+                        Err(err) => return Err(err),
+                    }
+                }
+                loaded_directory = Some(directory);
+            }
+            Storage::Sharded { shards } => {
+                let mut loaded = Vec::new();
+                for shard in 0..shards {
+                    let mut shard_cache = LruCache::new(self.cache.capacity());
+                    let file = ShardFiles::path(path, shard);
+                    // Un segment jamais écrit est vide
+                    if file.exists() {
+                        self.restore_snapshot(&mut shard_cache, &read(&file)?, recovery)?;
+                    }
+                    loaded.push(shard_cache);
+                }
+                let capacity = loaded.iter().map(LruCache::capacity).min();
+                cache = shard_files::merge(loaded, capacity.unwrap_or(cache.capacity()));
+            }
+            Storage::Log { .. } => self.replay(&mut cache, &read(path)?, recovery)?,
+            Storage::Snapshot if self.options.lazy_load && !recovery.is_auditing() => {
+                let bytes = read(path)?;
+                self.restore_raw(&mut cache, &mut unloaded, &bytes, recovery)?;
+            }
+            Storage::Snapshot => {
+                self.restore_snapshot(&mut cache, &read(path)?, recovery)?;
+            }
+            Storage::Journal { .. } => {
+                let log = self.log_path().filter(|log| log.exists());
+                // L'instantané peut manquer si rien n'a encore été compacté
+                if path.exists() || log.is_none() {
+                    self.restore_snapshot(&mut cache, &read(path)?, recovery)?;
+                }
+                if let Some(log) = log {
+                    self.replay(&mut cache, &read(&log)?, recovery)?;
+                }
+            }
+        }
+
+        if let Some(limit) = self.options.load_limit {
+            cache.discard_lru(limit);
+            unloaded.discard_lru(limit);
+        }
+        Ok(Some(Loaded {
+            cache,
+            unloaded,
+            directory: loaded_directory,
+        }))
+    }
+
+    /// Charge un instantané dans le format configuré
+    /// Retourne le nombre d'entrées écartées car déjà expirées
+    fn restore_snapshot<T: Persistable>(
+        &self,
+        cache: &mut LruCache<K, T>,
+        bytes: &[u8],
+        recovery: &mut Recovery,
+    ) -> Result<usize, CacheError> {
+        let bytes = if self.options.checksum {
+            checksum::open(bytes)?
+        } else {
+            bytes
+        };
+        let bytes = self.revert_transforms(bytes.to_vec())?;
+        let snapshot = self.options.format.decode_with(&bytes, recovery)?;
+        // La capacité du fichier fait foi, sauf si celle demandée est plus petite
+        if let Some(capacity) = snapshot.capacity {
+            *cache = LruCache::new(capacity.min(cache.capacity()));
+            if snapshot.entries.len() > capacity {
+                let reason = format!(
+                    "{} entrées pour une capacité de {}",
+                    snapshot.entries.len(),
+                    capacity
+                );
+                recovery.audit(InvalidRecord::new(capacity, reason));
+            }
+        }
+
+        if let Some(stats) = snapshot.stats {
+            cache.set_stats(stats);
+        }
+
+        let mut metadata = snapshot.metadata.into_iter();
+        let now = SystemTime::now();
+        let mut expired = 0;
+        for (index, (key, val)) in snapshot.entries.into_iter().enumerate() {
+            let meta = metadata.next();
+            let expires_at = meta
+                .and_then(|meta| meta.expires_at)
+                .and_then(|millis| UNIX_EPOCH.checked_add(Duration::from_millis(millis)));
+            if expires_at.is_some_and(|expires_at| expires_at <= now) {
+                expired += 1;
+                continue;
+            }
+            let (Some(k), Some(v)) = (K::from_bytes(&key), T::from_bytes(&val)) else {
+                recovery.skip(InvalidRecord::new(index, "clé ou valeur illisible"))?;
+                continue;
+            };
+            cache.restore_entry(k.clone(), v);
+            if let Some(meta) = meta {
+                cache.restore_metadata(&k, expires_at, meta.hits);
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Comme `restore_snapshot`, les valeurs restant en octets dans
+    /// `unloaded` (`lazy_load`); `cache` ne reçoit que la capacité et les
+    /// statistiques
+    fn restore_raw(
+        &self,
+        cache: &mut LruCache<K, V>,
+        unloaded: &mut LruCache<K, Raw>,
+        bytes: &[u8],
+        recovery: &mut Recovery,
+    ) -> Result<(), CacheError> {
+        let mut raw: LruCache<K, Vec<u8>> = LruCache::new(cache.capacity());
+        self.restore_snapshot(&mut raw, bytes, recovery)?;
+        *cache = LruCache::new(raw.capacity());
+        cache.set_stats(raw.stats());
+        *unloaded = LruCache::new(raw.capacity());
+
+        let keys: Vec<K> = raw.iter().map(|(key, _)| key.clone()).collect();
+        for key in keys {
+            let (expires_at, hits) = raw.entry_metadata(&key);
+            if let Some(value) = raw.remove(&key) {
+                let bytes = key.to_bytes();
+                unloaded.restore_entry(
+                    key.clone(),
+                    Raw {
+                        key: bytes,
+                        value: Some(value),
+                    },
+                );
+                unloaded.restore_metadata(&key, expires_at, hits);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rejoue des enregistrements de journal sans compter leurs évictions
+    fn replay(
+        &self,
+        cache: &mut LruCache<K, V>,
+        bytes: &[u8],
+        recovery: &mut Recovery,
+    ) -> Result<(), CacheError> {
+        let mut stats = cache.stats();
+
+        let records = self.decode_records(bytes, recovery)?;
+        for (index, record) in records.into_iter().enumerate() {
+            match record {
+                Record::Put(key, val) => match (K::from_bytes(&key), V::from_bytes(&val)) {
+                    (Some(k), Some(v)) => {
+                        cache.put(k, v);
+                    }
+                    _ => recovery.skip(InvalidRecord::new(index, "clé ou valeur illisible"))?,
+                },
+                Record::Remove(key) => match K::from_bytes(&key) {
+                    Some(k) => {
+                        cache.remove(&k);
+                    }
+                    None => recovery.skip(InvalidRecord::new(index, "clé illisible"))?,
+                },
+                Record::Stats(saved) => stats = saved,
+            }
+        }
+
+        cache.set_stats(stats);
+        Ok(())
+    }
+}
+
+impl<K, V> PersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + AsRef<str>,
+    V: Persistable,
+{
+    /// Entrées en mémoire dont la clé commence par `prefix`, voir
+    /// `LruCache::iter_prefix`
+    ///
+    /// Les clés pas encore lues (`lazy_load`) sont ignorées.
+    pub fn iter_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a K, &'a V)> {
+        self.cache.iter_prefix(prefix)
+    }
+
+    /// Retire les entrées dont la clé commence par `prefix`, comme
+    /// `remove_many`: une seule écriture pour la sauvegarde
+    pub fn remove_prefix(&mut self, prefix: &str) -> Vec<(K, V)> {
+        let keys = self.keys_with_prefix(prefix);
+        self.remove_many(keys)
+    }
+
+    /// Comme `remove_prefix`, mais renvoie l'erreur de sauvegarde automatique
+    pub fn try_remove_prefix(&mut self, prefix: &str) -> Result<Vec<(K, V)>, CacheError> {
+        let keys = self.keys_with_prefix(prefix);
+        self.try_remove_many(keys)
+    }
+
+    fn keys_with_prefix(&self, prefix: &str) -> Vec<K> {
+        self.unloaded
+            .iter()
+            .map(|(key, _)| key)
+            .chain(self.cache.iter().map(|(key, _)| key))
+            .filter(|key| key.as_ref().starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Contenu lu depuis les fichiers, avant de remplacer celui en mémoire
+struct Loaded<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: LruCache<K, V>,
+    unloaded: LruCache<K, Raw>,
+    directory: Option<EntryDirectory>,
+}
+
+/// Entrée pas encore décodée (`lazy_load`)
+struct Raw {
+    /// Octets de la clé persistée
+    key: Vec<u8>,
+    /// Octets de la valeur en mode instantané; `None` en mode dossier, où
+    /// la valeur reste dans son fichier
+    value: Option<Vec<u8>>,
+}
+
+/// Les écritures suivent la politique `autosave`; une erreur de sauvegarde
+/// va à `PersistOptions::on_error`, comme pour `put`
+impl<K, V> CacheOps<K, V> for PersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.put(key, value)
+    }
+
+    fn retrieve(&mut self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Drop for PersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    fn drop(&mut self) {
+        if self.options.save_on_drop {
+            self.flush_pending();
+        }
+    }
+}
+
+fn entry_meta<K: Hash + Eq + Clone, V>(cache: &LruCache<K, V>, key: &K) -> EntryMeta {
+    let (expires_at, hits) = cache.entry_metadata(key);
+    EntryMeta {
+        // Au-delà de u64 millisecondes: pas d'échéance, plutôt qu'une
+        // échéance tronquée qui ferait disparaître l'entrée
+        expires_at: expires_at.and_then(|deadline| {
+            let since_epoch = deadline.duration_since(UNIX_EPOCH).unwrap_or_default();
+            u64::try_from(since_epoch.as_millis()).ok()
+        }),
+        hits,
+    }
+}
+
+fn acquire_lock(path: &Path, policy: LockPolicy) -> Result<Option<File>, CacheError> {
+    if policy == LockPolicy::Disabled {
+        return Ok(None);
+    }
+
+    let lock_path = sibling(path, "lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    // Une attente trop longue pour l'horloge revient à attendre sans limite
+    let (policy, deadline) = match policy {
+        LockPolicy::Wait(timeout) => match Instant::now().checked_add(timeout) {
+            Some(deadline) => (policy, Some(deadline)),
+            None => (LockPolicy::Block, None),
+        },
+        _ => (policy, None),
+    };
+
+    loop {
+        match file.try_lock() {
+            Ok(()) => return Ok(Some(file)),
+            Err(TryLockError::WouldBlock) => {}
+            Err(TryLockError::Error(err)) => return Err(err.into()),
+        }
+        match policy {
+            LockPolicy::Block => {
+                file.lock()?;
+                return Ok(Some(file));
+            }
+            LockPolicy::Wait(_) if deadline.is_some_and(|d| Instant::now() < d) => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            _ => return Err(CacheError::Locked(path.to_path_buf())),
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, CacheError> {
+    fs::read(path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::NotFound => CacheError::NotFound(path.to_path_buf()),
+        _ => CacheError::Io(err),
+    })
+}
+
+/// Écrit dans un fichier temporaire du même dossier puis le renomme sur la
+/// cible: un arrêt en cours d'écriture laisse l'ancien fichier intact
+pub(crate) fn write_atomic(
+    path: &Path,
+    bytes: &[u8],
+    keep_backup: bool,
+    sync: bool,
+) -> std::io::Result<()> {
+    let tmp = sibling(path, "tmp");
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(bytes)?;
+        if sync {
+            file.sync_all()?;
+        }
+    }
+
+    if keep_backup && path.exists() {
+        let backup = sibling(path, "bak");
+        let _ = fs::remove_file(&backup);
+        // Un lien dur évite de copier, la cible existe toujours pendant l'opération
+        if fs::hard_link(path, &backup).is_err() {
+            fs::copy(path, &backup)?;
+        }
+    }
+
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })?;
+    // Le renommage n'est lui-même durable qu'une fois le répertoire écrit
+    if sync {
+        sync_parent(path)?;
+    }
+    Ok(())
+}
+
+/// `fsync` du répertoire de `path`, pour qu'une création ou un renommage
+/// survive à une coupure de courant
+///
+/// Sans effet hors Unix, où un répertoire ne s'ouvre pas comme un fichier.
+pub(crate) fn sync_parent(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Copie `cache.txt` en `cache.txt.1`, que `write_atomic` remplacera ensuite:
+/// le fichier courant reste en place jusqu'à la nouvelle version
+fn rotate_file(path: &Path) -> std::io::Result<()> {
+    let rotated = sibling(path, "1");
+    let _ = fs::remove_file(&rotated);
+    if fs::hard_link(path, &rotated).is_err() {
+        fs::copy(path, &rotated)?;
+    }
+    Ok(())
+}
+
+/// `cache.txt` → `cache.txt.<extension>`
+fn sibling(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_persistent() {
+        let path = "test_cache_persist.txt";
+
+        {
+            let mut cache = PersistentLruCache::new_persistent(2, path).unwrap();
+            cache.put("key1".into(), "val1".into());
+        }
+
+        {
+            let mut cache2 = PersistentLruCache::new_persistent(2, path).unwrap();
+            assert_eq!(cache2.get("key1"), Some(&"val1".to_string()));
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_persisted_stats() {
+        let path = "test_cache_stats.txt";
+        let options = PersistOptions::new().persist_stats(true);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(1, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.get("a");
+            cache.get("zz");
+            cache.put("b".into(), "2".into()); // évince a, sauvegarde
+        }
+
+        {
+            let cache: PersistentLruCache =
+                PersistentLruCache::with_options(1, path, options).unwrap();
+            let stats = cache.stats();
+            assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 1, 1));
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_generic_types() {
+        let path = "test_cache_generic.txt";
+
+        {
+            let mut cache: PersistentLruCache<u32, i64> =
+                PersistentLruCache::open(3, path).unwrap();
+            cache.put(7, -42);
+        }
+
+        {
+            let mut cache: PersistentLruCache<u32, i64> =
+                PersistentLruCache::open(3, path).unwrap();
+            assert_eq!(cache.get(&7), Some(&-42));
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_atomic_save_keeps_backup() {
+        let path = "test_cache_backup.txt";
+        let options = PersistOptions::new().keep_backup(true);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+        }
+
+        assert_eq!(fs::read_to_string(path).unwrap(), "2\na:1\nb:2\n");
+        assert_eq!(
+            fs::read_to_string("test_cache_backup.txt.bak").unwrap(),
+            "2\na:1\n"
+        );
+        assert!(!Path::new("test_cache_backup.txt.tmp").exists());
+
+        fs::remove_file(path).ok();
+        fs::remove_file("test_cache_backup.txt.bak").ok();
+    }
+
+    #[test]
+    fn test_sync_parent() {
+        // Chemin sans répertoire: c'est le répertoire courant qui est écrit
+        sync_parent(Path::new("test_cache_sync.txt")).unwrap();
+
+        let dir = Path::new("test_cache_sync_dir");
+        fs::create_dir_all(dir).unwrap();
+        write_atomic(&dir.join("cache.txt"), b"0\n", false, true).unwrap();
+        assert_eq!(fs::read(dir.join("cache.txt")).unwrap(), b"0\n");
+        fs::remove_dir_all(dir).ok();
+
+        #[cfg(unix)]
+        assert!(sync_parent(Path::new("test_cache_absent/cache.txt")).is_err());
+    }
+
+    #[test]
+    fn test_autosave_every_n() {
+        let path = "test_cache_every_n.txt";
+        let options = PersistOptions::new().autosave(Autosave::EveryN(2));
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        cache.put("a".into(), "1".into());
+        assert!(!Path::new(path).exists());
+        cache.put("b".into(), "2".into());
+        assert_eq!(fs::read_to_string(path).unwrap(), "3\na:1\nb:2\n");
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_save_on_drop() {
+        let path = "test_cache_drop.txt";
+        let options = PersistOptions::new()
+            .autosave(Autosave::Manual)
+            .save_on_drop(true);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options).unwrap();
+            cache.put("a".into(), "1".into());
+            assert!(!Path::new(path).exists());
+        }
+
+        assert_eq!(fs::read_to_string(path).unwrap(), "3\na:1\n");
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_flush_coalesces_writes() {
+        let path = "test_cache_flush.txt";
+        let options = PersistOptions::new().autosave(Autosave::Manual);
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        cache.flush().unwrap();
+        assert!(!Path::new(path).exists());
+
+        cache.put("a".into(), "1".into());
+        cache.put("b".into(), "2".into());
+        assert!(cache.is_dirty());
+        cache.flush().unwrap();
+        assert!(!cache.is_dirty());
+        assert_eq!(fs::read_to_string(path).unwrap(), "3\na:1\nb:2\n");
+
+        fs::remove_file(path).ok();
+
+        let mut memory: PersistentLruCache = PersistentLruCache::in_memory(3);
+        memory.put("a".into(), "1".into());
+        memory.flush().unwrap();
+        assert!(!memory.is_dirty());
+    }
+
+    #[test]
+    fn test_save_as_and_reload() {
+        let first = "test_cache_save_as_1.txt";
+        let second = "test_cache_save_as_2.txt";
+        let options = PersistOptions::new().autosave(Autosave::Manual);
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(3, first, options).unwrap();
+        cache.put("a".into(), "1".into());
+        cache.save().unwrap();
+        cache.save_as(second).unwrap();
+
+        cache.put("b".into(), "2".into());
+        cache.reload().unwrap();
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(&"1".to_string()));
+
+        fs::remove_file(second).unwrap();
+        assert!(matches!(cache.reload(), Err(CacheError::NotFound(_))));
+        assert_eq!(cache.get("a"), Some(&"1".to_string()));
+
+        fs::remove_file(first).ok();
+    }
+
+    #[test]
+    fn test_write_ahead_log() {
+        let path = "test_cache_wal.log";
+        let options = PersistOptions::new().write_ahead_log(4);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+            cache.remove(&"a".to_string());
+            let len = fs::metadata(path).unwrap().len();
+            cache.put("c".into(), "3".into()); // 4e écriture: compaction
+            assert!(fs::metadata(path).unwrap().len() < len);
+            cache.put("d".into(), "4".into());
+        }
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options).unwrap();
+            assert_eq!(cache.get("a"), None);
+            assert_eq!(cache.get("b"), Some(&"2".to_string()));
+            assert_eq!(cache.get("d"), Some(&"4".to_string()));
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_log_replays_evictions() {
+        let path = "test_cache_wal_evictions.log";
+        let options = PersistOptions::new().write_ahead_log(100);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+            cache.get("a"); // lecture non journalisée: b devient le moins récent
+            cache.put("c".into(), "3".into());
+            cache.get("a");
+            cache.put_many([("d".to_string(), "4".to_string())]);
+        }
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            assert_eq!(cache.get("a"), Some(&"1".to_string()));
+            assert_eq!(cache.get("d"), Some(&"4".to_string()));
+            assert_eq!(cache.len(), 2);
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_expired_lru_eviction_recorded() {
+        let log = "test_cache_expired_lru.log";
+        let dir = "test_cache_expired_lru";
+        for (path, options) in [
+            (log, PersistOptions::new().write_ahead_log(100)),
+            (dir, PersistOptions::new().directory()),
+        ] {
+            {
+                let mut cache: PersistentLruCache =
+                    PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+                cache.put_with_ttl("a".into(), "1".into(), Duration::ZERO);
+                cache.put("b".into(), "2".into());
+                cache.put("c".into(), "3".into()); // évince a, déjà expirée
+                assert_eq!(cache.get("b"), Some(&"2".to_string()));
+            }
+
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            assert_eq!(cache.get("a"), None, "{}", path);
+            assert_eq!(cache.get("b"), Some(&"2".to_string()), "{}", path);
+            assert_eq!(cache.get("c"), Some(&"3".to_string()), "{}", path);
+        }
+
+        fs::remove_file(log).ok();
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_compaction_triggers() {
+        let path = "test_cache_compact.log";
+        let options = PersistOptions::new()
+            .write_ahead_log(usize::MAX)
+            .compact_ratio(1.0);
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(1, path, options).unwrap();
+        cache.put("a".into(), "0".into()); // journal vide: compaction
+        let compacted = fs::metadata(path).unwrap().len();
+        for i in 1..20 {
+            cache.put("a".into(), i.to_string());
+            assert!(fs::metadata(path).unwrap().len() <= 2 * compacted + 20);
+        }
+
+        cache.options.compact_ratio = None;
+        cache.compact().unwrap();
+        let live = fs::metadata(path).unwrap().len();
+        for i in 0..20 {
+            cache.put("a".into(), i.to_string());
+        }
+        assert!(fs::metadata(path).unwrap().len() > 2 * live + 20);
+        cache.compact().unwrap();
+        assert_eq!(fs::metadata(path).unwrap().len(), live);
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_snapshot_plus_journal() {
+        let path = "test_cache_journal.json";
+        let journal = "test_cache_journal.json.journal";
+        let options = PersistOptions::new()
+            .format(Format::Json { pretty: false })
+            .journal(3);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+            assert!(!Path::new(path).exists());
+            cache.put("c".into(), "3".into()); // instantané, journal vidé
+            assert!(fs::read_to_string(path).unwrap().contains("\"c\""));
+            assert_eq!(fs::metadata(journal).unwrap().len(), 0);
+            cache.remove(&"a".to_string());
+        }
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options).unwrap();
+            assert_eq!(cache.get("a"), None);
+            assert_eq!(cache.get("c"), Some(&"3".to_string()));
+        }
+
+        fs::remove_file(path).ok();
+        fs::remove_file(journal).ok();
+    }
+
+    #[test]
+    fn test_journal_replays_evictions() {
+        let path = "test_cache_journal_evictions.json";
+        let journal = "test_cache_journal_evictions.json.journal";
+        let options = PersistOptions::new()
+            .format(Format::Json { pretty: false })
+            .journal(100);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+            cache.save().unwrap(); // instantané [a, b]
+            cache.get("a");
+            cache.put("c".into(), "3".into()); // évince b
+        }
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            assert_eq!(cache.get("b"), None);
+            assert_eq!(cache.get("a"), Some(&"1".to_string()));
+            assert_eq!(cache.get("c"), Some(&"3".to_string()));
+        }
+
+        fs::remove_file(path).ok();
+        fs::remove_file(journal).ok();
+    }
+
+    #[derive(Debug)]
+    struct Xor(u8);
+
+    impl Transform for Xor {
+        fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+            bytes.iter().map(|b| b ^ self.0).collect()
+        }
+
+        fn revert(&self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+            Ok(self.apply(bytes))
+        }
+    }
+
+    #[test]
+    fn test_transforms() {
+        for (path, options) in [
+            ("test_cache_xor.txt", PersistOptions::new()),
+            (
+                "test_cache_xor.log",
+                PersistOptions::new().write_ahead_log(10),
+            ),
+        ] {
+            let options = options.transform(Xor(0x5a)).transform(Xor(0x01));
+            {
+                let mut cache: PersistentLruCache =
+                    PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+                cache.put("clé".into(), "valeur".into());
+            }
+
+            let raw = fs::read(path).unwrap();
+            assert!(!raw.windows(6).any(|w| w == b"valeur"));
+
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            assert_eq!(cache.get("clé"), Some(&"valeur".to_string()));
+            fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_checksum_detects_corruption() {
+        let path = "test_cache_checksum.txt";
+        let options = PersistOptions::new().checksum(true);
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".into());
+        }
+
+        let mut bytes = fs::read(path).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        fs::write(path, &bytes).unwrap();
+
+        let result: Result<PersistentLruCache, _> =
+            PersistentLruCache::with_options(2, path, options);
+        assert!(matches!(result, Err(CacheError::Corrupted(_))));
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_lenient_load() {
+        let path = "test_cache_lenient.txt";
+        fs::write(path, "3\na:1\nb:deux\nc:3\n").unwrap();
+
+        let strict: Result<PersistentLruCache<String, i32>, _> = PersistentLruCache::open(3, path);
+        match strict {
+            Err(CacheError::InvalidRecord(record)) => assert_eq!(record.index, 1),
+            _ => panic!("chargement strict accepté"),
+        }
+
+        let options = PersistOptions::new().lenient(true);
+        let mut cache: PersistentLruCache<String, i32> =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        assert_eq!(cache.get("c"), Some(&3));
+        let skipped = &cache.load_report().skipped;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].index, 1);
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_verify() {
+        let path = "test_cache_verify.txt";
+        fs::write(path, "2\na:1\nb:2\n").unwrap();
+
+        let mut cache: PersistentLruCache<String, i32> = PersistentLruCache::open(2, path).unwrap();
+        assert!(cache.health_check().is_ok());
+
+        fs::write(path, "2\na:1\nb:x\nc:3\n").unwrap();
+        let report = cache.verify().unwrap();
+        let indexes: Vec<usize> = report.skipped.iter().map(|r| r.index).collect();
+        assert_eq!(indexes, vec![2, 1]); // capacité dépassée, puis b illisible
+        assert!(matches!(
+            cache.health_check(),
+            Err(CacheError::InvalidRecord(_))
+        ));
+        assert_eq!(cache.get("a"), Some(&1));
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_lock_excludes_second_opener() {
+        let path = "test_cache_locked.txt";
+        let options = PersistOptions::new().lock(LockPolicy::Fail);
+
+        let first: PersistentLruCache =
+            PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+        let second: Result<PersistentLruCache, _> =
+            PersistentLruCache::with_options(2, path, options.clone());
+        assert!(matches!(second, Err(CacheError::Locked(_))));
+
+        drop(first);
+        let waited: Result<PersistentLruCache, _> = PersistentLruCache::with_options(
+            2,
+            path,
+            options
+                .clone()
+                .lock(LockPolicy::Wait(Duration::from_millis(50))),
+        );
+        assert!(waited.is_ok());
+
+        // Attente sans limite représentable: bloque jusqu'à la libération
+        let unbounded = options.lock(LockPolicy::Wait(Duration::MAX));
+        let opener = std::thread::spawn(move || {
+            PersistentLruCache::<String, String>::with_options(2, path, unbounded).is_ok()
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        drop(waited);
+        assert!(opener.join().unwrap());
+
+        fs::remove_file("test_cache_locked.txt.lock").ok();
+    }
+
+    #[test]
+    fn test_autosave_errors_are_reported() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Le dossier parent n'existe pas: toute sauvegarde échoue
+        let path = "test_cache_absent_dir/cache.txt";
+        let failures = Arc::new(AtomicUsize::new(0));
+        let counter = failures.clone();
+        let options = PersistOptions::new().on_error(move |err| {
+            assert!(matches!(err, CacheError::Io(_)));
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(2, path, options).unwrap();
+        cache.put("a".into(), "1".into());
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+
+        assert!(cache.try_put("b".into(), "2".into()).is_err());
+        assert_eq!(cache.get("b"), Some(&"2".to_string()));
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_max_file_size() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let path = "test_cache_max_size.log";
+        let too_large = Arc::new(AtomicUsize::new(0));
+        let counter = too_large.clone();
+        let options = PersistOptions::new()
+            .write_ahead_log(usize::MAX)
+            .max_file_size(200)
+            .rotate(true)
+            .on_error(move |err| {
+                assert!(matches!(err, CacheError::FileTooLarge { limit: 200, .. }));
+                counter.fetch_add(1, Ordering::SeqCst);
+            });
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(2, path, options).unwrap();
+        for i in 0..50 {
+            cache.put((i % 2).to_string(), i.to_string());
+            assert!(fs::metadata(path).unwrap().len() <= 200 + 20);
+        }
+        assert!(Path::new("test_cache_max_size.log.1").exists());
+        assert_eq!(too_large.load(Ordering::SeqCst), 0);
+
+        // Les entrées vivantes ne tiennent plus dans la limite
+        cache.put("0".into(), "x".repeat(300));
+        assert_eq!(too_large.load(Ordering::SeqCst), 1);
+
+        drop(cache);
+        fs::remove_file(path).ok();
+        fs::remove_file("test_cache_max_size.log.1").ok();
+    }
+
+    #[test]
+    fn test_sharded_storage() {
+        let path = "test_cache_sharded";
+        let options = PersistOptions::new().sharded(4);
+        let shard = |key: &str| ShardFiles::new(4).shard_of(key.as_bytes());
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(10, path, options.clone()).unwrap();
+        for i in 0..8 {
+            cache.put(i.to_string(), i.to_string());
+        }
+        let other = (0..4).find(|&s| s != shard("0")).unwrap();
+        fs::remove_file(ShardFiles::path(Path::new(path), other)).unwrap();
+
+        // Seul le segment de la clé modifiée est réécrit
+        cache.put("0".into(), "zéro".into());
+        assert!(!ShardFiles::path(Path::new(path), other).exists());
+
+        drop(cache);
+        let mut reopened: PersistentLruCache =
+            PersistentLruCache::with_options(10, path, options).unwrap();
+        assert_eq!(reopened.get("0"), Some(&"zéro".to_string()));
+        for i in 1..8 {
+            let key = i.to_string();
+            let expected = (shard(&key) != other).then(|| key.clone());
+            assert_eq!(reopened.get(&key), expected.as_ref());
+        }
+
+        drop(reopened);
+        fs::remove_dir_all(path).ok();
+        fs::remove_file("test_cache_sharded.lock").ok();
+    }
+
+    #[test]
+    fn test_directory_storage() {
+        let path = "test_cache_directory";
+        let options = PersistOptions::new().directory();
+
+        {
+            let mut cache: PersistentLruCache<String, String> =
+                PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+            cache.put("a".into(), "1".repeat(1000));
+            cache.put("b".into(), "2".repeat(1000));
+            cache.put("c".into(), "3".repeat(1000)); // évince a et son fichier
+            let files = fs::read_dir(format!("{}/values", path)).unwrap().count();
+            assert_eq!(files, 2);
+        }
+
+        {
+            let mut cache: PersistentLruCache<String, String> =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            assert_eq!(cache.get("a"), None);
+            assert_eq!(cache.get("c"), Some(&"3".repeat(1000)));
+        }
+
+        fs::remove_dir_all(path).ok();
+    }
+
+    #[test]
+    fn test_lazy_directory_load() {
+        let path = "test_cache_lazy";
+        let options = PersistOptions::new().directory();
+
+        {
+            let mut cache: PersistentLruCache<String, i32> =
+                PersistentLruCache::with_options(3, path, options.clone()).unwrap();
+            cache.put("a".into(), 1);
+            cache.put("b".into(), 2);
+            cache.put("c".into(), 3);
+        }
+
+        let options = options.lazy_load(true);
+        let mut cache: PersistentLruCache<String, i32> =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        assert_eq!(cache.cache.len(), 0);
+        assert_eq!(cache.get("b"), Some(&2));
+        assert_eq!(cache.cache.len(), 1);
+
+        cache.put("d".into(), 4); // évince a, jamais lue
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("c"), Some(&3));
+        assert_eq!(cache.stats().evictions, 1);
+
+        let mut buffer = Vec::new();
+        cache.save_to_writer(&mut buffer).unwrap();
+        assert_eq!(buffer, b"3\nb:2\nd:4\nc:3\n");
+
+        drop(cache);
+        fs::remove_dir_all(path).ok();
+    }
+
+    #[test]
+    fn test_lazy_snapshot_decode() {
+        let path = "test_cache_lazy_snapshot.txt";
+        let options = PersistOptions::new().persist_metadata(true);
+        {
+            let mut cache: PersistentLruCache<String, i32> =
+                PersistentLruCache::with_options(3, path, options.clone()).unwrap();
+            cache.put_with_ttl("a".into(), 1, Duration::from_secs(60));
+            cache.put("b".into(), 2);
+            cache.get("a");
+            cache.save().unwrap();
+        }
+
+        let options = options.lazy_load(true);
+        let mut cache: PersistentLruCache<String, i32> =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        assert_eq!((cache.cache.len(), cache.len()), (0, 2));
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.cache.len(), 1);
+        assert_eq!(cache.cache.hit_count("a"), Some(2));
+        assert!(cache.cache.time_to_live("a").unwrap() > Duration::from_secs(50));
+
+        // "b", jamais décodée, est réécrite telle quelle
+        let mut buffer = Vec::new();
+        cache.save_to_writer(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(text.starts_with("3\nb:2:-:0\na:1:"));
+
+        // Plein: "b" est évincée sans avoir été décodée
+        cache.put("c".into(), 3);
+        cache.put("d".into(), 4);
+        assert!(!cache.contains_key("b"));
+        assert_eq!(cache.len(), 3);
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_put_many_saves_once() {
+        let path = "test_cache_put_many.txt";
+        {
+            let mut cache: PersistentLruCache<String, i32> =
+                PersistentLruCache::open(3, path).unwrap();
+            cache.put("a".into(), 1);
+            cache.put("b".into(), 2);
+        }
+
+        let options = PersistOptions::new()
+            .lazy_load(true)
+            .autosave(Autosave::EveryN(2));
+        let mut cache: PersistentLruCache<String, i32> =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        let batch = [("c", 3), ("a", 10), ("d", 4)].map(|(k, v)| (k.to_string(), v));
+
+        // "b", jamais lue, est la moins récente
+        assert_eq!(cache.put_many(batch), vec![("b".to_string(), 2)]);
+        assert_eq!(cache.len(), 3);
+        assert_eq!(cache.stats().evictions, 1);
+        assert!(!fs::read_to_string(path).unwrap().contains('c'));
+
+        cache.put("a".into(), 11);
+        assert!(fs::read_to_string(path).unwrap().contains('c'));
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_remove_many_in_log() {
+        let path = "test_cache_remove_many.log";
+        let options = PersistOptions::new().write_ahead_log(100);
+        {
+            let mut cache: PersistentLruCache<String, i32> =
+                PersistentLruCache::with_options(5, path, options.clone()).unwrap();
+            for (i, key) in ["a", "b", "c"].into_iter().enumerate() {
+                cache.put(key.into(), i as i32);
+            }
+            let keys = ["a", "c", "x"].map(String::from);
+            assert_eq!(
+                cache.remove_many(keys),
+                vec![("a".to_string(), 0), ("c".to_string(), 2)]
+            );
+            assert_eq!(cache.unsaved, 4);
+        }
+
+        let mut cache: PersistentLruCache<String, i32> =
+            PersistentLruCache::with_options(5, path, options).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("b"), Some(&1));
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_metadata_survives_restart() {
+        let path = "test_cache_metadata.txt";
+        let options = PersistOptions::new().persist_metadata(true);
+
+        {
+            let mut cache: PersistentLruCache<String, i32> =
+                PersistentLruCache::with_options(5, path, options.clone()).unwrap();
+            cache.put_with_ttl("a".into(), 1, Duration::from_secs(60));
+            cache.put("b".into(), 2);
+            cache.get("a");
+            cache.get("a");
+            // Échéances hors de l'horloge système, puis hors de u64 ms
+            let huge = Duration::from_secs(i64::MAX as u64 - 5_000_000);
+            cache.put_with_ttl("c".into(), 3, huge);
+            cache.put_with_ttl("d".into(), 4, Duration::from_secs(u64::MAX / 4));
+            cache.save().unwrap();
+        }
+
+        let mut cache: PersistentLruCache<String, i32> =
+            PersistentLruCache::with_options(5, path, options).unwrap();
+        assert_eq!(cache.cache.hit_count("a"), Some(2));
+        assert!(cache.cache.time_to_live("a").unwrap() > Duration::from_secs(50));
+        assert_eq!(cache.cache.time_to_live("b"), None);
+        assert_eq!(cache.get("c"), Some(&3));
+        assert_eq!(cache.get("d"), Some(&4));
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_load_truncates_to_capacity() {
+        let path = "test_cache_truncate.txt";
+        fs::write(path, "5\na:1\nb:2\nc:3\nd:4\n").unwrap();
+
+        let mut cache: PersistentLruCache<String, i32> = PersistentLruCache::open(2, path).unwrap();
+        assert_eq!(cache.cache.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(&3));
+        assert_eq!(cache.stats().evictions, 0);
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_writer_and_reader() {
+        let options = PersistOptions::new().format(Format::Binary).checksum(true);
+        let mut cache: PersistentLruCache<String, i32> = PersistentLruCache::in_memory(3);
+        cache.options = options.clone();
+        cache.put("a".into(), 1);
+        cache.put("b".into(), 2);
+
+        let mut buffer = Vec::new();
+        cache.save_to_writer(&mut buffer).unwrap();
+
+        let mut copy: PersistentLruCache<String, i32> = PersistentLruCache::in_memory(3);
+        copy.options = options;
+        copy.load_from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(copy.get("b"), Some(&2));
+
+        *buffer.last_mut().unwrap() ^= 1;
+        assert!(copy.load_from_reader(buffer.as_slice()).is_err());
+        assert_eq!(copy.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn test_read_only() {
+        let path = "test_cache_read_only.log";
+        let options = PersistOptions::new().write_ahead_log(100);
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options).unwrap();
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+            cache.save().unwrap();
+        }
+        let before = fs::read(path).unwrap();
+
+        let options = PersistOptions::new().write_ahead_log(100).read_only(true);
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(usize::MAX, path, options).unwrap();
+        assert_eq!(cache.get("a"), Some(&"1".to_string()));
+        assert_eq!(cache.peek("b"), Some(&"2".to_string()));
+        assert_eq!(cache.iter().count(), 2);
+        assert!(matches!(
+            cache.try_put("c".into(), "3".into()),
+            Err(CacheError::ReadOnly)
+        ));
+        assert!(matches!(
+            cache.try_remove(&"a".to_string()),
+            Err(CacheError::ReadOnly)
+        ));
+        assert!(matches!(cache.save(), Err(CacheError::ReadOnly)));
+        assert_eq!(cache.peek("c"), None);
+
+        drop(cache);
+        assert_eq!(fs::read(path).unwrap(), before);
+        assert!(!Path::new("test_cache_read_only.log.lock").exists());
+        fs::remove_file(path).ok();
+
+        let missing = PersistentLruCache::<String, String>::open_read_only(path);
+        assert!(matches!(missing, Err(CacheError::NotFound(p)) if p == Path::new(path)));
+        assert!(!Path::new(path).exists());
+        assert!(!Path::new("test_cache_read_only.log.lock").exists());
+    }
+
+    #[test]
+    fn test_cache_ops() {
+        fn fill<C: CacheOps<String, String>>(cache: &mut C) {
+            cache.insert("a".into(), "1".into());
+            cache.insert("b".into(), "2".into());
+        }
+
+        let path = "test_cache_ops.txt";
+        let mut cache: PersistentLruCache = PersistentLruCache::new_persistent(3, path).unwrap();
+        fill(&mut cache);
+        assert_eq!(cache.size(), 2);
+        assert_eq!(cache.retrieve(&"a".to_string()), Some(&"1".to_string()));
+
+        drop(cache);
+        let mut reopened = PersistentLruCache::new_persistent(3, path).unwrap();
+        assert_eq!(reopened.get("b"), Some(&"2".to_string()));
+
+        drop(reopened);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_len_contains_and_clear() {
+        for options in [
+            PersistOptions::new(),
+            PersistOptions::new().write_ahead_log(100),
+            PersistOptions::new().directory(),
+        ] {
+            let path = "test_cache_clear";
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options.clone()).unwrap();
+            assert!(cache.is_empty());
+            cache.put("a".into(), "1".into());
+            cache.put("b".into(), "2".into());
+            assert_eq!(cache.len(), 2);
+            assert!(cache.contains_key("a"));
+
+            cache.clear();
+            assert!(cache.is_empty());
+            assert!(!cache.contains_key("a"));
+            cache.put("c".into(), "3".into());
+
+            drop(cache);
+            let reopened: PersistentLruCache =
+                PersistentLruCache::with_options(3, path, options).unwrap();
+            assert_eq!(reopened.len(), 1);
+            assert!(reopened.contains_key("c"));
+
+            drop(reopened);
+            fs::remove_file(path).ok();
+            fs::remove_dir_all(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let path = "test_cache_restore.log";
+        let backup = "test_cache_restore.bak";
+        let options = PersistOptions::new().write_ahead_log(100);
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(3, path, options.clone()).unwrap();
+        cache.put("a".into(), "1".into());
+        cache.put("b".into(), "2".into());
+        cache.backup(backup).unwrap();
+
+        cache.put("c".into(), "3".into());
+        cache.remove(&"a".to_string());
+        cache.restore(backup).unwrap();
+        assert_eq!(cache.get("a"), Some(&"1".to_string()));
+        assert_eq!(cache.get("c"), None);
+
+        drop(cache);
+        let mut reopened: PersistentLruCache =
+            PersistentLruCache::with_options(3, path, options).unwrap();
+        assert_eq!(reopened.get("b"), Some(&"2".to_string()));
+        assert_eq!(reopened.get("c"), None);
+
+        drop(reopened);
+        fs::remove_file(path).ok();
+        fs::remove_file(backup).ok();
+    }
+
+    #[test]
+    fn test_durability_interval() {
+        let path = "test_cache_durability.txt";
+        let options =
+            PersistOptions::new().durability(Durability::Interval(Duration::from_secs(60)));
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(2, path, options).unwrap();
+        assert!(!cache.sync_due()); // dernier fsync à l'ouverture
+        cache.last_sync -= Duration::from_secs(61);
+        assert!(cache.sync_due());
+        assert!(!cache.sync_due());
+
+        cache.put("a".into(), "1".into());
+        assert_eq!(fs::read_to_string(path).unwrap(), "2\na:1\n");
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_load_limit() {
+        let path = "test_cache_load_limit.txt";
+        fs::write(path, "4\na:1\nb:2\nc:3\nd:4\n").unwrap();
+
+        let mut cache = PersistentLruCache::new_persistent_with_limit(4, path, 2).unwrap();
+        assert_eq!(cache.cache.len(), 2);
+        assert_eq!(cache.cache.capacity(), 4);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("d"), Some(&"4".to_string()));
+
+        drop(cache);
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_convert() {
+        let text = "test_cache_convert.txt";
+        let json = "test_cache_convert.json";
+        fs::write(text, "5 1 2 3\na:1\nb:x\\:y\n").unwrap();
+
+        PersistentLruCache::convert(text, Format::Text, json, Format::Json { pretty: false })
+            .unwrap();
+        let options = PersistOptions::new()
+            .format(Format::Json { pretty: false })
+            .persist_stats(true);
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(10, json, options).unwrap();
+        assert_eq!(cache.cache.capacity(), 5);
+        assert_eq!(cache.stats().misses, 2);
+        assert_eq!(cache.get("b"), Some(&"x:y".to_string()));
+
+        drop(cache);
+        fs::remove_file(text).ok();
+        fs::remove_file(json).ok();
+    }
+
+    #[test]
+    fn test_json_format() {
+        let path = "test_cache_json.json";
+        let options = PersistOptions::new()
+            .persist_stats(true)
+            .format(Format::Json { pretty: true });
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options.clone()).unwrap();
+            cache.get("absente");
+            cache.put("clé".into(), "a:b\n\"c\"".into());
+        }
+
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.contains("\"key\": \"clé\""));
+
+        {
+            let mut cache: PersistentLruCache =
+                PersistentLruCache::with_options(2, path, options).unwrap();
+            assert_eq!(cache.stats().misses, 1);
+            assert_eq!(cache.get("clé"), Some(&"a:b\n\"c\"".to_string()));
+        }
+
+        fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_warm_from() {
+        let (seed, path) = ("test_warm_seed.csv", "test_warm.csv");
+        fs::write(seed, "key,value\na,1\nb,2,trop\nc,3\n").unwrap();
+        let options = PersistOptions::new().format(Format::Csv).warm_from(seed);
+
+        let mut cache: PersistentLruCache =
+            PersistentLruCache::with_options(10, path, options.clone()).unwrap();
+        let report = WarmReport {
+            loaded: 2,
+            skipped: 1,
+        };
+        assert_eq!(cache.warm_report(), Some(&report));
+        assert_eq!(cache.get("c"), Some(&"3".to_string()));
+
+        // Les clés présentes gardent leur valeur
+        cache.put("a".to_string(), "10".to_string());
+        assert_eq!(cache.warm_from(seed).unwrap().loaded, 0);
+        assert_eq!(cache.get("a"), Some(&"10".to_string()));
+        drop(cache);
+
+        // Le fichier du cache existe: pas de nouveau préchargement
+        let cache: PersistentLruCache =
+            PersistentLruCache::with_options(10, path, options).unwrap();
+        assert_eq!(cache.warm_report(), None);
+        assert_eq!(cache.peek("a"), Some(&"10".to_string()));
 
+        drop(cache);
+        fs::remove_file(seed).ok();
         fs::remove_file(path).ok();
     }
 }
@@ -0,0 +1,437 @@
+//! Sous-ensemble du format RDB de Redis: chaînes et échéances
+//!
+//! L'export écrit un fichier version 9 (Redis 5 et suivants) ne contenant
+//! que des chaînes, dans la base 0. L'import lit les versions 1 à 12 et
+//! ignore les autres types de valeur, qu'il sait sauter.
+
+use crate::error::CacheError;
+use crate::format::{EntryMeta, Snapshot};
+use crate::report::{InvalidRecord, Recovery};
+
+const MAGIC: &[u8; 5] = b"REDIS";
+const VERSION: u32 = 9;
+const MAX_VERSION: u32 = 12;
+
+/// Champ auxiliaire portant la capacité, ignoré par Redis
+const CAPACITY_AUX: &[u8] = b"lru-capacity";
+
+const OPCODE_SLOT_INFO: u8 = 0xF4;
+const OPCODE_FUNCTION2: u8 = 0xF5;
+const OPCODE_IDLE: u8 = 0xF8;
+const OPCODE_FREQ: u8 = 0xF9;
+const OPCODE_AUX: u8 = 0xFA;
+const OPCODE_RESIZEDB: u8 = 0xFB;
+const OPCODE_EXPIRETIME_MS: u8 = 0xFC;
+const OPCODE_EXPIRETIME: u8 = 0xFD;
+const OPCODE_SELECTDB: u8 = 0xFE;
+const OPCODE_EOF: u8 = 0xFF;
+
+const TYPE_STRING: u8 = 0;
+
+/// `REDIS0009`, capacité, base 0, entrées de la moins à la plus récemment
+/// utilisée, puis le CRC-64 du tout
+pub(crate) fn encode(snapshot: &Snapshot) -> Vec<u8> {
+    let mut out = format!("REDIS{:04}", VERSION).into_bytes();
+    if let Some(capacity) = snapshot.capacity {
+        out.push(OPCODE_AUX);
+        write_string(&mut out, CAPACITY_AUX);
+        write_string(&mut out, capacity.to_string().as_bytes());
+    }
+
+    out.push(OPCODE_SELECTDB);
+    write_length(&mut out, 0);
+    let expiring = snapshot
+        .metadata
+        .iter()
+        .filter(|meta| meta.expires_at.is_some())
+        .count();
+    out.push(OPCODE_RESIZEDB);
+    write_length(&mut out, snapshot.entries.len() as u64);
+    write_length(&mut out, expiring as u64);
+
+    for (i, (key, value)) in snapshot.entries.iter().enumerate() {
+        if let Some(expires_at) = snapshot.metadata.get(i).and_then(|meta| meta.expires_at) {
+            out.push(OPCODE_EXPIRETIME_MS);
+            out.extend_from_slice(&expires_at.to_le_bytes());
+        }
+        out.push(TYPE_STRING);
+        write_string(&mut out, key);
+        write_string(&mut out, value);
+    }
+
+    out.push(OPCODE_EOF);
+    let checksum = crc64(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// Chaînes de la base 0 avec leurs échéances
+///
+/// Les valeurs d'un autre type et les clés des autres bases sont confiées
+/// à `recovery`: le chargement strict les refuse.
+pub(crate) fn decode(bytes: &[u8], recovery: &mut Recovery) -> Result<Snapshot, CacheError> {
+    let mut reader = Reader { bytes, pos: 0 };
+    if reader.take(5)? != MAGIC {
+        return Err(CacheError::corrupted("RDB: signature absente"));
+    }
+    let version = std::str::from_utf8(reader.take(4)?)
+        .ok()
+        .and_then(|digits| digits.parse::<u32>().ok())
+        .ok_or_else(|| CacheError::corrupted("RDB: version illisible"))?;
+    if !(1..=MAX_VERSION).contains(&version) {
+        return Err(CacheError::corrupted(format!(
+            "RDB: version {} non prise en charge",
+            version
+        )));
+    }
+
+    let mut snapshot = Snapshot::default();
+    let mut database = 0;
+    let mut expires_at = None;
+    let mut index = 0;
+    loop {
+        let start = reader.pos;
+        match reader.byte()? {
+            OPCODE_EOF => break,
+            OPCODE_AUX => {
+                let (name, value) = (reader.string()?, reader.string()?);
+                if name == CAPACITY_AUX {
+                    snapshot.capacity = std::str::from_utf8(&value)
+                        .ok()
+                        .and_then(|value| value.parse().ok());
+                }
+            }
+            OPCODE_SELECTDB => database = reader.length()?,
+            OPCODE_RESIZEDB => {
+                reader.length()?;
+                reader.length()?;
+            }
+            OPCODE_EXPIRETIME_MS => expires_at = Some(reader.u64_le()?),
+            OPCODE_EXPIRETIME => expires_at = Some(reader.u32_le()? as u64 * 1000),
+            OPCODE_IDLE => {
+                reader.length()?;
+            }
+            OPCODE_FREQ => {
+                reader.byte()?;
+            }
+            OPCODE_FUNCTION2 => {
+                reader.string()?;
+            }
+            OPCODE_SLOT_INFO => {
+                for _ in 0..3 {
+                    reader.length()?;
+                }
+            }
+            value_type => {
+                let key = reader.string()?;
+                let skipped = match value_type {
+                    TYPE_STRING => {
+                        let value = reader.string()?;
+                        if database == 0 {
+                            snapshot.entries.push((key, value));
+                            snapshot.metadata.push(EntryMeta {
+                                expires_at: expires_at.take(),
+                                hits: 0,
+                            });
+                            None
+                        } else {
+                            Some(format!("RDB: clé de la base {} ignorée", database))
+                        }
+                    }
+                    other => {
+                        reader.skip_value(other)?;
+                        Some(format!("RDB: valeur de type {} ignorée", other))
+                    }
+                };
+                expires_at = None;
+                if let Some(reason) = skipped {
+                    recovery.skip(InvalidRecord::new(index, reason).at(start))?;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    // Les versions antérieures à 5 n'ont pas de somme de contrôle; 0 veut
+    // dire qu'elle a été désactivée
+    if version >= 5 {
+        let end = reader.pos;
+        let checksum = reader.u64_le()?;
+        if checksum != 0 && checksum != crc64(&bytes[..end]) {
+            return Err(CacheError::corrupted("RDB: somme de contrôle invalide"));
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Longueur sur 6, 14, 32 ou 64 bits selon sa valeur
+fn write_length(out: &mut Vec<u8>, len: u64) {
+    match len {
+        0..=0x3F => out.push(len as u8),
+        0x40..=0x3FFF => out.extend_from_slice(&(0x4000 | len as u16).to_be_bytes()),
+        0x4000..=0xFFFF_FFFF => {
+            out.push(0x80);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0x81);
+            out.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_length(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+/// Longueur ou encodage spécial (les deux bits de poids fort à 1)
+enum Length {
+    Plain(usize),
+    Special(u8),
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CacheError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| CacheError::corrupted("RDB: fichier tronqué"))?;
+        let chunk = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(chunk)
+    }
+
+    fn byte(&mut self) -> Result<u8, CacheError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32_le(&mut self) -> Result<u32, CacheError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64_le(&mut self) -> Result<u64, CacheError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn length_or_special(&mut self) -> Result<Length, CacheError> {
+        let first = self.byte()?;
+        let len = match first >> 6 {
+            0 => (first & 0x3F) as u64,
+            1 => u16::from_be_bytes([first & 0x3F, self.byte()?]) as u64,
+            3 => return Ok(Length::Special(first & 0x3F)),
+            _ => match first {
+                0x80 => u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+                0x81 => u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+                _ => return Err(CacheError::corrupted("RDB: longueur illisible")),
+            },
+        };
+        usize::try_from(len)
+            .map(Length::Plain)
+            .map_err(|_| CacheError::corrupted("RDB: longueur trop grande"))
+    }
+
+    fn length(&mut self) -> Result<usize, CacheError> {
+        match self.length_or_special()? {
+            Length::Plain(len) => Ok(len),
+            Length::Special(_) => Err(CacheError::corrupted("RDB: longueur attendue")),
+        }
+    }
+
+    /// Chaîne brute, entière (écrite en décimal) ou compressée LZF
+    fn string(&mut self) -> Result<Vec<u8>, CacheError> {
+        match self.length_or_special()? {
+            Length::Plain(len) => Ok(self.take(len)?.to_vec()),
+            Length::Special(0) => Ok((self.byte()? as i8).to_string().into_bytes()),
+            Length::Special(1) => {
+                let n = i16::from_le_bytes(self.take(2)?.try_into().unwrap());
+                Ok(n.to_string().into_bytes())
+            }
+            Length::Special(2) => Ok((self.u32_le()? as i32).to_string().into_bytes()),
+            Length::Special(3) => {
+                let compressed_len = self.length()?;
+                let len = self.length()?;
+                lzf_decompress(self.take(compressed_len)?, len)
+            }
+            Length::Special(_) => Err(CacheError::corrupted("RDB: encodage de chaîne inconnu")),
+        }
+    }
+
+    /// Saute une valeur d'un type autre que chaîne
+    fn skip_value(&mut self, value_type: u8) -> Result<(), CacheError> {
+        match value_type {
+            // Liste, ensemble, quicklist: suite de chaînes
+            1 | 2 | 14 => self.skip_strings(1),
+            // Table de hachage: paires de chaînes
+            4 => self.skip_strings(2),
+            // Ensemble trié, score en texte (253 à 255: NaN et infinis)
+            3 => {
+                for _ in 0..self.length()? {
+                    self.string()?;
+                    let len = self.byte()?;
+                    if len < 253 {
+                        self.take(len as usize)?;
+                    }
+                }
+                Ok(())
+            }
+            // Ensemble trié, score binaire
+            5 => {
+                for _ in 0..self.length()? {
+                    self.string()?;
+                    self.take(8)?;
+                }
+                Ok(())
+            }
+            // Encodages compacts (zipmap, ziplist, intset, listpack): un bloc
+            9..=13 | 16 | 17 | 20 => self.string().map(drop),
+            // Quicklist 2: conteneur puis bloc
+            18 => {
+                for _ in 0..self.length()? {
+                    self.length()?;
+                    self.string()?;
+                }
+                Ok(())
+            }
+            other => Err(CacheError::corrupted(format!(
+                "RDB: type de valeur {} illisible",
+                other
+            ))),
+        }
+    }
+
+    fn skip_strings(&mut self, per_item: usize) -> Result<(), CacheError> {
+        for _ in 0..self.length()?.saturating_mul(per_item) {
+            self.string()?;
+        }
+        Ok(())
+    }
+}
+
+/// Décompression LZF, celle des chaînes compressées par Redis
+fn lzf_decompress(input: &[u8], len: usize) -> Result<Vec<u8>, CacheError> {
+    let corrupted = || CacheError::corrupted("RDB: chaîne LZF invalide");
+    let mut out = Vec::with_capacity(len);
+    let mut i = 0;
+    while i < input.len() {
+        let control = input[i] as usize;
+        i += 1;
+        if control < 32 {
+            let literal = input.get(i..i + control + 1).ok_or_else(corrupted)?;
+            out.extend_from_slice(literal);
+            i += control + 1;
+            continue;
+        }
+
+        let mut run = control >> 5;
+        if run == 7 {
+            run += *input.get(i).ok_or_else(corrupted)? as usize;
+            i += 1;
+        }
+        let low = *input.get(i).ok_or_else(corrupted)? as usize;
+        i += 1;
+        let back = ((control & 0x1F) << 8) + low + 1;
+        let from = out.len().checked_sub(back).ok_or_else(corrupted)?;
+        // La copie peut chevaucher sa source: octet par octet
+        for at in from..from + run + 2 {
+            out.push(out[at]);
+        }
+    }
+    if out.len() != len {
+        return Err(corrupted());
+    }
+    Ok(out)
+}
+
+/// CRC-64 Jones (réfléchi), celui des fichiers RDB
+fn crc64(bytes: &[u8]) -> u64 {
+    const TABLE: [u64; 256] = {
+        let mut table = [0u64; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u64;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ 0x95AC_9329_AC4B_C9B5
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    bytes.iter().fold(0u64, |crc, &b| {
+        TABLE[((crc ^ b as u64) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let snapshot = Snapshot {
+            capacity: Some(100),
+            stats: None,
+            entries: vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (vec![b'k'; 100], vec![0xFF; 20_000]),
+            ],
+            metadata: vec![
+                EntryMeta {
+                    expires_at: Some(1_700_000_000_000),
+                    hits: 0,
+                },
+                EntryMeta::default(),
+            ],
+        };
+        let bytes = encode(&snapshot);
+        assert!(bytes.starts_with(b"REDIS0009"));
+        assert_eq!(decode(&bytes, &mut Recovery::strict()).unwrap(), snapshot);
+
+        let mut corrupted = bytes.clone();
+        corrupted[20] ^= 1;
+        assert!(decode(&corrupted, &mut Recovery::strict()).is_err());
+    }
+
+    #[test]
+    fn test_redis_encodings() {
+        assert_eq!(crc64(b"123456789"), 0xE9C6_D914_C4B8_D9CA);
+
+        // Dump comme Redis l'écrirait: entier, chaîne LZF, liste ignorée,
+        // échéance en secondes, somme de contrôle désactivée
+        let mut bytes = b"REDIS0011\xFE\x00".to_vec();
+        bytes.extend_from_slice(b"\x00\x01n\xC1\x39\x30");
+        bytes.extend_from_slice(b"\x00\x01z\xC3\x05\x08\x01aa\x80\x00");
+        bytes.extend_from_slice(b"\x01\x01l\x02\x01x\x01y");
+        bytes.extend_from_slice(b"\xFD\x10\x00\x00\x00\x00\x01e\x01v");
+        bytes.extend_from_slice(b"\xFF\x00\x00\x00\x00\x00\x00\x00\x00");
+
+        assert!(decode(&bytes, &mut Recovery::strict()).is_err());
+        let mut recovery = Recovery::new(true);
+        let snapshot = decode(&bytes, &mut recovery).unwrap();
+        assert_eq!(
+            snapshot.entries,
+            [
+                (b"n".to_vec(), b"12345".to_vec()),
+                (b"z".to_vec(), b"aaaaaaaa".to_vec()),
+                (b"e".to_vec(), b"v".to_vec()),
+            ]
+        );
+        assert_eq!(snapshot.metadata[2].expires_at, Some(16_000));
+        assert_eq!(snapshot.capacity, None);
+        assert_eq!(recovery.into_report().skipped.len(), 1);
+    }
+}
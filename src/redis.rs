@@ -0,0 +1,235 @@
+//! Client Redis minimal (protocole RESP) servant de `RemoteStore`
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::remote::RemoteStore;
+
+/// Connexion à un serveur Redis, pour `RemoteTieredCache`
+///
+/// N'utilise que `GET`, `SET` (avec `PX` pour la durée de vie) et `DEL`.
+/// Les commandes passent par une seule connexion, partagée derrière un
+/// verrou; après une erreur réseau, la connexion est rouverte au
+/// prochain appel.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{RedisStore, RemoteTieredCache};
+///
+/// let redis = RedisStore::connect("127.0.0.1:6379").unwrap();
+/// let cache: RemoteTieredCache<String, String, _> = RemoteTieredCache::new(1000, redis);
+///
+/// cache.put("a".to_string(), "1".to_string()).unwrap();
+/// assert_eq!(cache.get(&"a".to_string()).unwrap(), Some("1".to_string()));
+/// ```
+pub struct RedisStore {
+    addrs: Vec<SocketAddr>,
+    timeout: Option<Duration>,
+    conn: Mutex<Option<Connection>>,
+}
+
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+/// Réponse du serveur
+#[derive(Debug, PartialEq)]
+pub(crate) enum Reply {
+    Status(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<Reply>),
+}
+
+impl RedisStore {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::connect_timeout(addr, None)
+    }
+
+    /// Comme `connect`, chaque lecture ou écriture échouant après `timeout`
+    pub fn connect_timeout(
+        addr: impl ToSocketAddrs,
+        timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        let store = Self {
+            addrs: addr.to_socket_addrs()?.collect(),
+            timeout,
+            conn: Mutex::new(None),
+        };
+        *store.conn() = Some(store.open()?);
+        Ok(store)
+    }
+
+    fn open(&self) -> io::Result<Connection> {
+        let writer = TcpStream::connect(&self.addrs[..])?;
+        writer.set_nodelay(true)?;
+        writer.set_read_timeout(self.timeout)?;
+        writer.set_write_timeout(self.timeout)?;
+        Ok(Connection {
+            reader: BufReader::new(writer.try_clone()?),
+            writer,
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Option<Connection>> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Envoie une commande et lit sa réponse; `-ERR` devient une erreur
+    fn call(&self, args: &[&[u8]]) -> io::Result<Reply> {
+        let mut conn = self.conn();
+        if conn.is_none() {
+            *conn = Some(self.open()?);
+        }
+        let open = conn.as_mut().expect("connexion ouverte");
+
+        let mut request = Vec::new();
+        encode_command(args, &mut request);
+        let reply = open
+            .writer
+            .write_all(&request)
+            .and_then(|()| read_reply(&mut open.reader));
+        if reply
+            .as_ref()
+            .is_err_and(|err| err.kind() != io::ErrorKind::Other)
+        {
+            // Connexion dans un état inconnu: la rouvrir au prochain appel
+            *conn = None;
+        }
+        reply
+    }
+}
+
+impl RemoteStore for RedisStore {
+    fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+        match self.call(&[b"GET", key])? {
+            Reply::Bulk(value) => Ok(value),
+            reply => Err(unexpected(reply)),
+        }
+    }
+
+    fn set(&self, key: &[u8], value: &[u8], ttl: Option<Duration>) -> io::Result<()> {
+        let reply = match ttl {
+            Some(ttl) => {
+                let millis = ttl.as_millis().max(1).to_string();
+                self.call(&[b"SET", key, value, b"PX", millis.as_bytes()])?
+            }
+            None => self.call(&[b"SET", key, value])?,
+        };
+        match reply {
+            Reply::Status(_) => Ok(()),
+            reply => Err(unexpected(reply)),
+        }
+    }
+
+    fn delete(&self, key: &[u8]) -> io::Result<()> {
+        match self.call(&[b"DEL", key])? {
+            Reply::Integer(_) => Ok(()),
+            reply => Err(unexpected(reply)),
+        }
+    }
+}
+
+fn unexpected(reply: Reply) -> io::Error {
+    invalid(format!("réponse Redis inattendue: {:?}", reply))
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// Tableau de chaînes binaires: `*<n>\r\n` puis `$<len>\r\n<octets>\r\n`
+fn encode_command(args: &[&[u8]], out: &mut Vec<u8>) {
+    out.extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+    for arg in args {
+        out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        out.extend_from_slice(arg);
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+fn read_reply(reader: &mut impl BufRead) -> io::Result<Reply> {
+    let line = read_line(reader)?;
+    let (tag, rest) = line
+        .split_first()
+        .ok_or_else(|| invalid("réponse Redis vide"))?;
+    let text = String::from_utf8_lossy(rest).into_owned();
+    let number = || {
+        text.parse::<i64>()
+            .map_err(|_| invalid(format!("longueur Redis invalide: {}", text)))
+    };
+    match tag {
+        b'+' => Ok(Reply::Status(text)),
+        b'-' => Err(io::Error::other(text)),
+        b':' => Ok(Reply::Integer(number()?)),
+        b'$' => {
+            let Ok(len) = usize::try_from(number()?) else {
+                return Ok(Reply::Bulk(None));
+            };
+            let mut bytes = vec![0; len + 2];
+            reader.read_exact(&mut bytes)?;
+            if !bytes.ends_with(b"\r\n") {
+                return Err(invalid("chaîne Redis mal terminée"));
+            }
+            bytes.truncate(len);
+            Ok(Reply::Bulk(Some(bytes)))
+        }
+        b'*' => {
+            let count = number()?;
+            (0..count.max(0))
+                .map(|_| read_reply(reader))
+                .collect::<io::Result<_>>()
+                .map(Reply::Array)
+        }
+        _ => Err(invalid(format!("réponse Redis inconnue: {}", *tag as char))),
+    }
+}
+
+/// Ligne sans son `\r\n` final
+fn read_line(reader: &mut impl BufRead) -> io::Result<Vec<u8>> {
+    let mut line = Vec::new();
+    reader.read_until(b'\n', &mut line)?;
+    if !line.ends_with(b"\r\n") {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connexion Redis interrompue",
+        ));
+    }
+    line.truncate(line.len() - 2);
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resp_encoding() {
+        let mut request = Vec::new();
+        encode_command(&[b"SET", b"k", b"a\r\nb"], &mut request);
+        assert_eq!(request, b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$4\r\na\r\nb\r\n");
+
+        let mut replies: &[u8] =
+            b"+OK\r\n:1\r\n$-1\r\n$4\r\na\r\nb\r\n*2\r\n:2\r\n$0\r\n\r\n-ERR x\r\n";
+        assert_eq!(
+            read_reply(&mut replies).unwrap(),
+            Reply::Status("OK".into())
+        );
+        assert_eq!(read_reply(&mut replies).unwrap(), Reply::Integer(1));
+        assert_eq!(read_reply(&mut replies).unwrap(), Reply::Bulk(None));
+        assert_eq!(
+            read_reply(&mut replies).unwrap(),
+            Reply::Bulk(Some(b"a\r\nb".to_vec()))
+        );
+        assert_eq!(
+            read_reply(&mut replies).unwrap(),
+            Reply::Array(vec![Reply::Integer(2), Reply::Bulk(Some(Vec::new()))])
+        );
+        assert_eq!(read_reply(&mut replies).unwrap_err().to_string(), "ERR x");
+        assert!(read_reply(&mut replies).is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! Serveur Redis minimal (protocole RESP) devant un `SyncLruCache`
+
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use crate::redis::Reply;
+use crate::server::{self, ServerHandle};
+use crate::sync::SyncLruCache;
+
+/// Taille maximale d'un argument, comme `proto-max-bulk-len` de Redis
+const MAX_BULK_LEN: usize = 512 * 1024 * 1024;
+
+/// Nombre maximal d'arguments d'une commande
+const MAX_ARGS: usize = 1024 * 1024;
+
+/// Longueur maximale d'une commande en ligne (`telnet`)
+const MAX_INLINE_LEN: usize = 64 * 1024;
+
+type Cache = SyncLruCache<Vec<u8>, Vec<u8>>;
+
+/// Expose un `SyncLruCache` aux clients Redis (`redis-cli`, bibliothèques)
+///
+/// Sous-ensemble pris en charge: `GET`, `SET` (options `EX`, `PX`, `NX`,
+/// `XX`), `DEL`, `EXISTS`, `EXPIRE`, `PEXPIRE`, `PERSIST`, `TTL`, `PTTL`,
+/// `KEYS` (motifs `*`, `?`, `[...]`), `DBSIZE`, `FLUSHDB`/`FLUSHALL`,
+/// `PING`, `ECHO`, `SELECT 0` et `QUIT`. Les commandes en ligne (`telnet`)
+/// sont acceptées. Une seule base, pas de persistance ni de réplication:
+/// l'éviction suit l'ordre LRU du cache, quelle que soit la durée de vie.
+///
+/// Comme pour `MemcachedServer`, le cache reste partagé avec le reste du
+/// processus et chaque connexion est servie sur son propre thread.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{RedisServer, SyncLruCache};
+///
+/// let cache = SyncLruCache::new(10_000);
+/// let handle = RedisServer::bind("127.0.0.1:6379", cache).unwrap().spawn().unwrap();
+///
+/// // redis-cli SET a 1 EX 60
+/// handle.stop();
+/// ```
+pub struct RedisServer {
+    listener: TcpListener,
+    cache: Cache,
+}
+
+impl RedisServer {
+    /// Écoute sur `addr` (port 0: port libre, voir `local_addr`)
+    pub fn bind(addr: impl ToSocketAddrs, cache: Cache) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            cache,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Sert les connexions sur le thread appelant, sans fin
+    pub fn run(self) {
+        let cache = self.cache;
+        server::serve(&self.listener, &AtomicBool::new(false), move |stream| {
+            connection(&cache, stream)
+        });
+    }
+
+    /// Sert les connexions sur un thread de fond, jusqu'à l'abandon du
+    /// `ServerHandle`
+    pub fn spawn(self) -> io::Result<ServerHandle> {
+        let cache = self.cache;
+        server::spawn(self.listener, move |stream| connection(&cache, stream))
+    }
+}
+
+fn connection(cache: &Cache, stream: TcpStream) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    handle(cache, reader, BufWriter::new(stream))
+}
+
+/// Traite les commandes jusqu'à `QUIT` ou la fermeture de la connexion
+fn handle(cache: &Cache, mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    loop {
+        let args = match read_command(&mut reader) {
+            Ok(Some(args)) => args,
+            Ok(None) => return Ok(()),
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                // Redis ferme aussi la connexion après une erreur de protocole
+                write!(writer, "-ERR Protocol error: {}\r\n", err)?;
+                return writer.flush();
+            }
+            Err(err) => return Err(err),
+        };
+        if args.is_empty() {
+            continue;
+        }
+        let quit = args[0].eq_ignore_ascii_case(b"QUIT");
+        match execute(cache, &args) {
+            Ok(reply) => write_reply(&mut writer, &reply)?,
+            Err(message) => write!(writer, "-{}\r\n", message)?,
+        }
+        writer.flush()?;
+        if quit {
+            return Ok(());
+        }
+    }
+}
+
+/// Exécute une commande; `Err` porte le message d'erreur RESP
+fn execute(cache: &Cache, args: &[Vec<u8>]) -> Result<Reply, String> {
+    let name = String::from_utf8_lossy(&args[0]).to_ascii_uppercase();
+    let ok = || Reply::Status("OK".to_string());
+    match (name.as_str(), &args[1..]) {
+        ("PING", []) => Ok(Reply::Status("PONG".to_string())),
+        ("PING" | "ECHO", [message]) => Ok(Reply::Bulk(Some(message.clone()))),
+        ("QUIT", _) => Ok(ok()),
+        ("SELECT", [db]) if db == b"0" => Ok(ok()),
+        ("SELECT", [_]) => Err("ERR DB index is out of range".to_string()),
+        // Interrogé par redis-cli à la connexion
+        ("COMMAND", _) => Ok(Reply::Array(Vec::new())),
+        ("GET", [key]) => Ok(Reply::Bulk(cache.get(key))),
+        ("SET", [key, value, options @ ..]) => set(cache, key, value, options),
+        ("DEL", keys) if !keys.is_empty() => {
+            let removed = keys.iter().filter(|key| cache.remove(key).is_some());
+            Ok(Reply::Integer(removed.count() as i64))
+        }
+        ("EXISTS", keys) if !keys.is_empty() => {
+            let found = keys.iter().filter(|key| cache.contains_key(key));
+            Ok(Reply::Integer(found.count() as i64))
+        }
+        ("EXPIRE" | "PEXPIRE", [key, ttl]) => {
+            let unit = if name == "EXPIRE" { 1000 } else { 1 };
+            let millis = integer(ttl)?.saturating_mul(unit);
+            let updated = if millis <= 0 {
+                cache.remove(key).is_some()
+            } else {
+                cache.set_ttl(key, Some(Duration::from_millis(millis as u64)))
+            };
+            Ok(Reply::Integer(updated as i64))
+        }
+        ("PERSIST", [key]) => {
+            let had_ttl = cache.time_to_live(key).is_some();
+            Ok(Reply::Integer((had_ttl && cache.set_ttl(key, None)) as i64))
+        }
+        ("TTL" | "PTTL", [key]) => {
+            let remaining = match cache.time_to_live(key) {
+                Some(ttl) if name == "TTL" => ((ttl.as_millis() + 500) / 1000) as i64,
+                Some(ttl) => ttl.as_millis() as i64,
+                None if cache.contains_key(key) => -1,
+                None => -2,
+            };
+            Ok(Reply::Integer(remaining))
+        }
+        ("KEYS", [pattern]) => {
+            let keys = cache
+                .keys()
+                .into_iter()
+                .filter(|key| glob_match(pattern, key));
+            Ok(Reply::Array(
+                keys.map(|key| Reply::Bulk(Some(key))).collect(),
+            ))
+        }
+        ("DBSIZE", []) => {
+            cache.purge_expired();
+            Ok(Reply::Integer(cache.len() as i64))
+        }
+        ("FLUSHDB" | "FLUSHALL", _) => {
+            cache.shrink_to(0);
+            Ok(ok())
+        }
+        (
+            "PING" | "ECHO" | "SELECT" | "GET" | "SET" | "DEL" | "EXISTS" | "EXPIRE" | "PEXPIRE"
+            | "PERSIST" | "TTL" | "PTTL" | "KEYS" | "DBSIZE",
+            _,
+        ) => Err(format!(
+            "ERR wrong number of arguments for '{}' command",
+            name.to_ascii_lowercase()
+        )),
+        _ => Err(format!(
+            "ERR unknown command '{}'",
+            name.to_ascii_lowercase()
+        )),
+    }
+}
+
+/// `SET key value [EX s | PX ms] [NX | XX]`
+fn set(cache: &Cache, key: &[u8], value: &[u8], options: &[Vec<u8>]) -> Result<Reply, String> {
+    let mut ttl = None;
+    let mut condition = None;
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let option = String::from_utf8_lossy(option).to_ascii_uppercase();
+        match option.as_str() {
+            "EX" | "PX" if ttl.is_none() => {
+                let amount = integer(options.next().ok_or("ERR syntax error")?)?;
+                if amount <= 0 {
+                    return Err("ERR invalid expire time in 'set' command".to_string());
+                }
+                let unit = if option == "EX" { 1000 } else { 1 };
+                ttl = Some(Duration::from_millis(amount.saturating_mul(unit) as u64));
+            }
+            "NX" | "XX" if condition.is_none() => condition = Some(option),
+            _ => return Err("ERR syntax error".to_string()),
+        }
+    }
+
+    // NX et XX testent puis écrivent: une écriture concurrente de la même
+    // clé peut s'intercaler
+    let key = key.to_vec();
+    let allowed = match condition.as_deref() {
+        Some("NX") => !cache.contains_key(&key),
+        Some(_) => cache.contains_key(&key),
+        None => true,
+    };
+    if !allowed {
+        return Ok(Reply::Bulk(None));
+    }
+    match ttl {
+        Some(ttl) => cache.put_with_ttl(key, value.to_vec(), ttl),
+        None => cache.put(key, value.to_vec()),
+    };
+    Ok(Reply::Status("OK".to_string()))
+}
+
+fn integer(arg: &[u8]) -> Result<i64, String> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|text| text.parse().ok())
+        .ok_or_else(|| "ERR value is not an integer or out of range".to_string())
+}
+
+/// Commande suivante: tableau RESP de chaînes binaires, ou ligne de mots
+/// séparés par des espaces; `None` à la fermeture de la connexion
+fn read_command(reader: &mut impl BufRead) -> io::Result<Option<Vec<Vec<u8>>>> {
+    let mut line = Vec::new();
+    reader
+        .by_ref()
+        .take(MAX_INLINE_LEN as u64)
+        .read_until(b'\n', &mut line)?;
+    if line.is_empty() {
+        return Ok(None);
+    }
+    if !line.ends_with(b"\n") {
+        return Err(invalid("commande trop longue ou incomplète"));
+    }
+    let line = line
+        .strip_suffix(b"\r\n")
+        .unwrap_or(&line[..line.len() - 1]);
+    let Some(count) = line.strip_prefix(b"*") else {
+        let words = line.split(|b| b.is_ascii_whitespace());
+        return Ok(Some(
+            words
+                .filter(|w| !w.is_empty())
+                .map(<[u8]>::to_vec)
+                .collect(),
+        ));
+    };
+
+    let count = length(count, MAX_ARGS)?;
+    let mut args = Vec::with_capacity(count.min(64));
+    for _ in 0..count {
+        let mut header = Vec::new();
+        reader.by_ref().take(64).read_until(b'\n', &mut header)?;
+        let len = header
+            .strip_prefix(b"$")
+            .and_then(|rest| rest.strip_suffix(b"\r\n"))
+            .ok_or_else(|| invalid("chaîne RESP attendue"))?;
+        let len = length(len, MAX_BULK_LEN)?;
+        // Lu au fil de l'eau: une longueur annoncée n'est pas allouée d'avance
+        let mut arg = Vec::new();
+        reader.by_ref().take(len as u64 + 2).read_to_end(&mut arg)?;
+        if arg.len() < len + 2 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "connexion Redis interrompue",
+            ));
+        }
+        if !arg.ends_with(b"\r\n") {
+            return Err(invalid("chaîne RESP mal terminée"));
+        }
+        arg.truncate(len);
+        args.push(arg);
+    }
+    Ok(Some(args))
+}
+
+fn length(digits: &[u8], max: usize) -> io::Result<usize> {
+    std::str::from_utf8(digits)
+        .ok()
+        .and_then(|text| text.parse::<usize>().ok())
+        .filter(|&len| len <= max)
+        .ok_or_else(|| invalid("longueur RESP invalide"))
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_reply(writer: &mut impl Write, reply: &Reply) -> io::Result<()> {
+    match reply {
+        Reply::Status(status) => write!(writer, "+{}\r\n", status),
+        Reply::Integer(n) => write!(writer, ":{}\r\n", n),
+        Reply::Bulk(None) => writer.write_all(b"$-1\r\n"),
+        Reply::Bulk(Some(bytes)) => {
+            write!(writer, "${}\r\n", bytes.len())?;
+            writer.write_all(bytes)?;
+            writer.write_all(b"\r\n")
+        }
+        Reply::Array(items) => {
+            write!(writer, "*{}\r\n", items.len())?;
+            items.iter().try_for_each(|item| write_reply(writer, item))
+        }
+    }
+}
+
+/// Motif de `KEYS`: `*`, `?`, `[abc]`, `[^a]`, `[a-z]` et `\` d'échappement
+fn glob_match(pattern: &[u8], key: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => key.is_empty(),
+        Some((b'*', rest)) => (0..=key.len()).any(|skip| glob_match(rest, &key[skip..])),
+        Some((b'?', rest)) => !key.is_empty() && glob_match(rest, &key[1..]),
+        Some((b'[', rest)) => {
+            let Some(end) = rest.iter().position(|&b| b == b']') else {
+                return key.first() == Some(&b'[') && glob_match(rest, &key[1..]);
+            };
+            let Some((&byte, key_rest)) = key.split_first() else {
+                return false;
+            };
+            let (negate, class) = match rest[..end].split_first() {
+                Some((b'^', class)) => (true, class),
+                _ => (false, &rest[..end]),
+            };
+            let mut matched = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == b'-' {
+                    let (low, high) = (class[i].min(class[i + 2]), class[i].max(class[i + 2]));
+                    matched |= (low..=high).contains(&byte);
+                    i += 3;
+                } else {
+                    matched |= class[i] == byte;
+                    i += 1;
+                }
+            }
+            matched != negate && glob_match(&rest[end + 1..], key_rest)
+        }
+        Some((b'\\', [escaped, rest @ ..])) => {
+            key.first() == Some(escaped) && glob_match(rest, &key[1..])
+        }
+        Some((&literal, rest)) => key.first() == Some(&literal) && glob_match(rest, &key[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redis::RedisStore;
+    use crate::remote::RemoteStore;
+
+    fn session(cache: &Cache, requests: &[u8]) -> String {
+        let mut replies = Vec::new();
+        handle(cache, requests, &mut replies).unwrap();
+        String::from_utf8(replies).unwrap()
+    }
+
+    #[test]
+    fn test_resp_commands() {
+        let cache = SyncLruCache::new(10);
+        let replies = session(
+            &cache,
+            b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$4\r\n1\r\n2\r\n\
+              SET b 2 NX\r\nset b 3 nx\r\nSET c 3 XX\r\nGET a\r\nGET c\r\n\
+              EXPIRE a 100\r\nTTL a\r\nTTL b\r\nTTL c\r\nPERSIST a\r\nPTTL a\r\n\
+              EXISTS a b c\r\nDEL a c\r\nKEYS *\r\nDBSIZE\r\n\
+              GET\r\nFOO\r\nSET d 1 EX 0\r\nQUIT\r\nPING\r\n",
+        );
+        assert_eq!(
+            replies,
+            "+OK\r\n+OK\r\n$-1\r\n$-1\r\n$4\r\n1\r\n2\r\n$-1\r\n\
+             :1\r\n:100\r\n:-1\r\n:-2\r\n:1\r\n:-1\r\n\
+             :2\r\n:1\r\n*1\r\n$1\r\nb\r\n:1\r\n\
+             -ERR wrong number of arguments for 'get' command\r\n\
+             -ERR unknown command 'foo'\r\n-ERR invalid expire time in 'set' command\r\n+OK\r\n"
+        );
+
+        let replies = session(&cache, b"*1\r\n$x\r\n");
+        assert!(replies.starts_with("-ERR Protocol error"));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"user:*", b"user:42"));
+        assert!(glob_match(b"h?llo", b"hallo"));
+        assert!(glob_match(b"h[ae]llo", b"hello"));
+        assert!(!glob_match(b"h[^e]llo", b"hello"));
+        assert!(glob_match(b"h[a-c]llo", b"hbllo"));
+        assert!(glob_match(b"a\\*", b"a*"));
+        assert!(!glob_match(b"a\\*", b"ab"));
+        assert!(!glob_match(b"*:1", b"user:12"));
+    }
+
+    #[test]
+    fn test_serves_redis_store() {
+        let cache = SyncLruCache::new(10);
+        let handle = RedisServer::bind("127.0.0.1:0", cache.clone())
+            .unwrap()
+            .spawn()
+            .unwrap();
+
+        let store = RedisStore::connect(handle.local_addr()).unwrap();
+        store
+            .set(b"k", b"v", Some(Duration::from_secs(60)))
+            .unwrap();
+        assert_eq!(store.get(b"k").unwrap(), Some(b"v".to_vec()));
+        assert!(cache.time_to_live(&b"k".to_vec()).is_some());
+        store.delete(b"k").unwrap();
+        assert!(cache.is_empty());
+
+        drop(store);
+        handle.stop();
+    }
+}
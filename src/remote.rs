@@ -0,0 +1,178 @@
+//! Cache local devant un stockage distant partagé entre instances
+
+use std::hash::Hash;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::codec::Persistable;
+use crate::sync::SyncLruCache;
+
+/// Stockage clé-valeur distant (Redis, memcached...), en octets
+pub trait RemoteStore {
+    /// `None` si la clé est absente ou expirée
+    fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>>;
+
+    /// Écrit une valeur, qui expire après `ttl` si donné
+    fn set(&self, key: &[u8], value: &[u8], ttl: Option<Duration>) -> io::Result<()>;
+
+    fn delete(&self, key: &[u8]) -> io::Result<()>;
+}
+
+/// `SyncLruCache` local devant un `RemoteStore` partagé
+///
+/// Les écritures vont d'abord au stockage distant, puis au cache local: une
+/// entrée évincée localement reste disponible pour toutes les instances.
+/// Un défaut local interroge le stockage distant et garde la valeur
+/// trouvée. Le cache local n'est pas prévenu des écritures des autres
+/// instances: `expire_after` borne la durée pendant laquelle il peut
+/// servir une valeur périmée, `invalidate_local` l'oublie tout de suite.
+///
+/// Voir `RedisStore` (feature `redis`) et `MemcachedStore` (feature
+/// `memcached`).
+pub struct RemoteTieredCache<K, V, S>
+where
+    K: Hash + Eq + Clone,
+{
+    local: SyncLruCache<K, V>,
+    remote: Arc<S>,
+    ttl: Option<Duration>,
+}
+
+impl<K, V, S> Clone for RemoteTieredCache<K, V, S>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            local: self.local.clone(),
+            remote: Arc::clone(&self.remote),
+            ttl: self.ttl,
+        }
+    }
+}
+
+impl<K, V, S> RemoteTieredCache<K, V, S>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable + Clone,
+    S: RemoteStore,
+{
+    pub fn new(local_capacity: usize, remote: S) -> Self {
+        Self::with_cache(SyncLruCache::new(local_capacity), remote)
+    }
+
+    pub fn with_cache(local: SyncLruCache<K, V>, remote: S) -> Self {
+        Self {
+            local,
+            remote: Arc::new(remote),
+            ttl: None,
+        }
+    }
+
+    /// Les entrées expirent après `ttl`, dans les deux niveaux
+    pub fn expire_after(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Valeur locale, sinon lue dans le stockage distant et gardée
+    ///
+    /// Une valeur distante illisible est une erreur `InvalidData`.
+    pub fn get(&self, key: &K) -> io::Result<Option<V>> {
+        if let Some(value) = self.local.get(key) {
+            return Ok(Some(value));
+        }
+        let Some(bytes) = self.remote.get(&key.to_bytes())? else {
+            return Ok(None);
+        };
+        let value = V::from_bytes(&bytes).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "valeur distante illisible")
+        })?;
+        self.put_local(key.clone(), value.clone());
+        Ok(Some(value))
+    }
+
+    /// Écrit dans le stockage distant, puis localement s'il l'a acceptée
+    pub fn put(&self, key: K, value: V) -> io::Result<()> {
+        self.remote
+            .set(&key.to_bytes(), &value.to_bytes(), self.ttl)?;
+        self.put_local(key, value);
+        Ok(())
+    }
+
+    pub fn remove(&self, key: &K) -> io::Result<()> {
+        self.local.remove(key);
+        self.remote.delete(&key.to_bytes())
+    }
+
+    /// Oublie la copie locale: le prochain `get` relira le stockage distant
+    pub fn invalidate_local(&self, key: &K) {
+        self.local.remove(key);
+    }
+
+    /// Cache local
+    pub fn local(&self) -> &SyncLruCache<K, V> {
+        &self.local
+    }
+
+    /// Stockage distant
+    pub fn remote(&self) -> &S {
+        &self.remote
+    }
+
+    fn put_local(&self, key: K, value: V) {
+        match self.ttl {
+            Some(ttl) => self.local.put_with_ttl(key, value, ttl),
+            None => self.local.put(key, value),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+    impl RemoteStore for MemoryStore {
+        fn get(&self, key: &[u8]) -> io::Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        fn set(&self, key: &[u8], value: &[u8], _: Option<Duration>) -> io::Result<()> {
+            self.0.lock().unwrap().insert(key.to_vec(), value.to_vec());
+            Ok(())
+        }
+
+        fn delete(&self, key: &[u8]) -> io::Result<()> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_local_overflow_stays_remote() {
+        let cache: RemoteTieredCache<String, String, _> =
+            RemoteTieredCache::new(1, MemoryStore::default());
+        let key = |k: &str| k.to_string();
+
+        cache.put(key("a"), key("1")).unwrap();
+        cache.put(key("b"), key("2")).unwrap(); // "a" évincé localement
+        assert!(!cache.local().contains_key(&key("a")));
+        assert_eq!(cache.get(&key("a")).unwrap(), Some(key("1")));
+        assert!(cache.local().contains_key(&key("a")));
+
+        // Écriture d'une autre instance
+        cache.remote().set(b"a", b"3", None).unwrap();
+        assert_eq!(cache.get(&key("a")).unwrap(), Some(key("1")));
+        cache.invalidate_local(&key("a"));
+        assert_eq!(cache.get(&key("a")).unwrap(), Some(key("3")));
+
+        cache.remove(&key("a")).unwrap();
+        assert_eq!(cache.get(&key("a")).unwrap(), None);
+    }
+}
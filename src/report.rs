@@ -0,0 +1,164 @@
+//! Enregistrements illisibles rencontrés au chargement d'un fichier de cache
+
+use std::fmt;
+
+use crate::error::CacheError;
+
+/// Enregistrements illisibles écartés lors du dernier chargement
+///
+/// Toujours vide sans `PersistOptions::lenient`: le premier enregistrement
+/// illisible fait alors échouer le chargement avec
+/// `CacheError::InvalidRecord`.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{PersistOptions, PersistentLruCache};
+///
+/// let options = PersistOptions::new().lenient(true);
+/// let cache: PersistentLruCache =
+///     PersistentLruCache::with_options(100, "cache.txt", options).unwrap();
+/// for skipped in &cache.load_report().skipped {
+///     eprintln!("ignoré: {}", skipped);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    pub skipped: Vec<InvalidRecord>,
+}
+
+impl LoadReport {
+    /// Indique si tous les enregistrements ont été lus
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty()
+    }
+}
+
+/// Résultat d'un préchargement, voir `PersistentLruCache::warm_from`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WarmReport {
+    /// Entrées ajoutées au cache
+    pub loaded: usize,
+    /// Enregistrements illisibles ou déjà expirés
+    pub skipped: usize,
+}
+
+/// Enregistrement illisible et sa position
+///
+/// Les positions portent sur le contenu décodé, après retrait de la somme
+/// de contrôle et des transformations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRecord {
+    /// Rang de l'enregistrement dans le fichier, à partir de 0
+    pub index: usize,
+    /// Ligne, à partir de 1, pour le format texte
+    pub line: Option<usize>,
+    /// Position du début de l'enregistrement en octets, si le format la donne
+    pub offset: Option<usize>,
+    pub reason: String,
+}
+
+impl InvalidRecord {
+    pub(crate) fn new(index: usize, reason: impl Into<String>) -> Self {
+        Self {
+            index,
+            line: None,
+            offset: None,
+            reason: reason.into(),
+        }
+    }
+
+    pub(crate) fn at(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub(crate) fn on_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl fmt::Display for InvalidRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "enregistrement {}", self.index)?;
+        if let Some(line) = self.line {
+            write!(f, ", ligne {}", line)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, ", octet {}", offset)?;
+        }
+        write!(f, ": {}", self.reason)
+    }
+}
+
+/// Sort des enregistrements illisibles selon le mode de chargement
+pub(crate) struct Recovery {
+    lenient: bool,
+    /// Relève aussi les incohérences tolérées au chargement (`verify`)
+    auditing: bool,
+    report: LoadReport,
+}
+
+impl Recovery {
+    pub(crate) fn new(lenient: bool) -> Self {
+        Self {
+            lenient,
+            auditing: false,
+            report: LoadReport::default(),
+        }
+    }
+
+    pub(crate) fn strict() -> Self {
+        Self::new(false)
+    }
+
+    pub(crate) fn auditing() -> Self {
+        Self {
+            auditing: true,
+            ..Self::new(true)
+        }
+    }
+
+    pub(crate) fn is_auditing(&self) -> bool {
+        self.auditing
+    }
+
+    /// Note une incohérence que le chargement tolère, lors d'une vérification
+    pub(crate) fn audit(&mut self, record: InvalidRecord) {
+        if self.auditing {
+            self.report.skipped.push(record);
+        }
+    }
+
+    /// Note l'enregistrement écarté, ou renvoie l'erreur en mode strict
+    pub(crate) fn skip(&mut self, record: InvalidRecord) -> Result<(), CacheError> {
+        if !self.lenient {
+            return Err(CacheError::InvalidRecord(record));
+        }
+        self.report.skipped.push(record);
+        Ok(())
+    }
+
+    pub(crate) fn into_report(self) -> LoadReport {
+        self.report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_position() {
+        let record = InvalidRecord::new(2, "valeur illisible").on_line(4).at(17);
+        assert_eq!(
+            record.to_string(),
+            "enregistrement 2, ligne 4, octet 17: valeur illisible"
+        );
+        assert_eq!(
+            InvalidRecord::new(0, "clé illisible").to_string(),
+            "enregistrement 0: clé illisible"
+        );
+    }
+}
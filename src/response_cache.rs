@@ -0,0 +1,181 @@
+//! Cache de réponses indexé par méthode et URI, pour un middleware HTTP
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::cache::LruCache;
+use crate::stats::CacheStats;
+
+type Predicate<R> = Arc<dyn Fn(&R) -> bool + Send + Sync>;
+
+/// Réponses mises en cache par méthode et URI, avec une durée de vie
+///
+/// Indépendant de tout framework: un middleware (axum, hyper, ou un
+/// serveur maison) appelle `lookup` avant le gestionnaire et `store`
+/// après, ou `call` d'un seul coup en code synchrone. Seules les méthodes
+/// `GET` et `HEAD` sont mises en cache; une requête `POST`, `PUT`, `PATCH`
+/// ou `DELETE` passée à `call` invalide les réponses de son URI.
+///
+/// Les clones partagent le même contenu.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::ResponseCache;
+/// use std::time::Duration;
+///
+/// let cache = ResponseCache::new(1000, Duration::from_secs(30))
+///     .cache_if(|response: &(u16, String)| response.0 == 200);
+///
+/// let first = cache.call("GET", "/users/42", || (200, "Ada".to_string()));
+/// let second = cache.call("GET", "/users/42", || unreachable!());
+/// assert_eq!(first, second);
+///
+/// cache.call("PUT", "/users/42", || (204, String::new()));
+/// assert!(cache.lookup("GET", "/users/42").is_none());
+/// ```
+pub struct ResponseCache<R> {
+    inner: Arc<Mutex<LruCache<String, Arc<R>>>>,
+    ttl: Duration,
+    cache_if: Option<Predicate<R>>,
+}
+
+impl<R> Clone for ResponseCache<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            ttl: self.ttl,
+            cache_if: self.cache_if.clone(),
+        }
+    }
+}
+
+impl<R> ResponseCache<R> {
+    /// `capacity` réponses au plus, chacune gardée `ttl`
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LruCache::new(capacity))),
+            ttl,
+            cache_if: None,
+        }
+    }
+
+    /// Ne met en cache que les réponses acceptées par `predicate` (par
+    /// exemple les statuts 200)
+    pub fn cache_if(mut self, predicate: impl Fn(&R) -> bool + Send + Sync + 'static) -> Self {
+        self.cache_if = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Réponse en cache pour cette requête, marquée comme récente
+    pub fn lookup(&self, method: &str, uri: &str) -> Option<Arc<R>> {
+        if !cacheable(method) {
+            return None;
+        }
+        self.lock().get(&key(method, uri)).cloned()
+    }
+
+    /// Met `response` en cache si la méthode et le prédicat le permettent,
+    /// et la rend partagée
+    pub fn store(&self, method: &str, uri: &str, response: R) -> Arc<R> {
+        let response = Arc::new(response);
+        let accepted = self
+            .cache_if
+            .as_ref()
+            .is_none_or(|predicate| predicate(&response));
+        if cacheable(method) && accepted {
+            self.lock()
+                .put_with_ttl(key(method, uri), Arc::clone(&response), self.ttl);
+        }
+        response
+    }
+
+    /// Réponse en cache, ou celle de `handler` mise en cache
+    ///
+    /// Pour une méthode qui modifie la ressource, `handler` est toujours
+    /// appelé et les réponses `GET`/`HEAD` de l'URI sont retirées.
+    pub fn call<F>(&self, method: &str, uri: &str, handler: F) -> Arc<R>
+    where
+        F: FnOnce() -> R,
+    {
+        if let Some(response) = self.lookup(method, uri) {
+            return response;
+        }
+        let response = handler();
+        if matches!(method, "POST" | "PUT" | "PATCH" | "DELETE") {
+            self.invalidate(uri);
+        }
+        self.store(method, uri, response)
+    }
+
+    /// Retire les réponses `GET` et `HEAD` de `uri`; retourne leur nombre
+    pub fn invalidate(&self, uri: &str) -> usize {
+        let keys = [key("GET", uri), key("HEAD", uri)];
+        self.lock().remove_many(keys).len()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<String, Arc<R>>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn cacheable(method: &str) -> bool {
+    matches!(method, "GET" | "HEAD")
+}
+
+fn key(method: &str, uri: &str) -> String {
+    format!("{} {}", method, uri)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_call_caches_get_only() {
+        let cache =
+            ResponseCache::new(10, Duration::from_secs(60)).cache_if(|status| *status < 400);
+        let calls = Cell::new(0);
+        let handler = |status| {
+            calls.set(calls.get() + 1);
+            status
+        };
+
+        cache.call("GET", "/a", || handler(200));
+        cache.call("GET", "/a", || handler(200));
+        cache.call("HEAD", "/a", || handler(200));
+        cache.call("GET", "/b", || handler(404));
+        cache.call("GET", "/b", || handler(404));
+        assert_eq!(calls.get(), 4);
+        assert_eq!(cache.len(), 2);
+
+        cache.call("POST", "/a", || handler(201));
+        cache.call("POST", "/a", || handler(201));
+        assert_eq!(calls.get(), 6);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_ttl() {
+        let cache = ResponseCache::new(10, Duration::ZERO);
+        cache.store("GET", "/a", "réponse");
+        assert!(cache.lookup("GET", "/a").is_none());
+    }
+}
@@ -0,0 +1,167 @@
+//! Nouvelles tentatives, avec attente croissante, autour d'un chargeur
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::thread;
+use std::time::Duration;
+
+use crate::loader::CacheLoader;
+
+/// Nombre de tentatives et attente entre deux tentatives
+///
+/// L'attente double (voir `multiplier`) à chaque échec, sans dépasser le
+/// plafond de `backoff`. Avec `jitter`, une part aléatoire de l'attente est
+/// retirée pour que des appelants en échec au même moment ne réessaient pas
+/// ensemble.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::{CacheLoader, LoadingCache, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new(3)
+///     .backoff(Duration::from_millis(1), Duration::from_millis(10))
+///     .jitter(0.5);
+/// let loader = (|id: &u32| Ok::<_, String>(format!("user-{}", id))).with_retry(policy);
+/// let users = LoadingCache::new(100, loader);
+///
+/// assert_eq!(users.get(&1), Ok("user-1".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    attempts: u32,
+    initial: Duration,
+    max: Duration,
+    multiplier: f64,
+    jitter: f64,
+}
+
+impl RetryPolicy {
+    /// `attempts` appels au plus (au moins un); 100 ms puis le double, sans
+    /// dépasser 5 s, sans part aléatoire
+    pub fn new(attempts: u32) -> Self {
+        Self {
+            attempts: attempts.max(1),
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(5),
+            multiplier: 2.0,
+            jitter: 0.0,
+        }
+    }
+
+    /// Attente après le premier échec, et plafond des suivantes
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial = initial;
+        self.max = max.max(initial);
+        self
+    }
+
+    /// Facteur appliqué à l'attente après chaque échec (au moins 1)
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Part de l'attente retirée au hasard, entre 0 et 1
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Attente avant la tentative `retry` + 1 (`retry` à partir de 1)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.powi(retry.saturating_sub(1) as i32);
+        let delay = self.initial.mul_f64(factor).min(self.max);
+        if self.jitter == 0.0 {
+            return delay;
+        }
+        let random = RandomState::new().hash_one(retry) as f64 / u64::MAX as f64;
+        delay.mul_f64(1.0 - self.jitter * random)
+    }
+}
+
+/// Chargeur qui rappelle `loader` selon une `RetryPolicy`, voir
+/// `CacheLoader::with_retry`
+///
+/// Toutes les erreurs sont retentées; la dernière est renvoyée quand les
+/// tentatives sont épuisées. L'attente bloque le thread appelant.
+#[derive(Debug, Clone)]
+pub struct Retry<L> {
+    loader: L,
+    policy: RetryPolicy,
+}
+
+impl<L> Retry<L> {
+    pub fn new(loader: L, policy: RetryPolicy) -> Self {
+        Self { loader, policy }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+}
+
+impl<K, V, L> CacheLoader<K, V> for Retry<L>
+where
+    L: CacheLoader<K, V>,
+{
+    type Error = L::Error;
+
+    fn load(&self, key: &K) -> Result<V, L::Error> {
+        let mut retry = 0;
+        loop {
+            match self.loader.load(key) {
+                Err(_) if retry + 1 < self.policy.attempts => {
+                    retry += 1;
+                    thread::sleep(self.policy.delay(retry));
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_retries_until_success() {
+        let policy = RetryPolicy::new(3).backoff(Duration::ZERO, Duration::ZERO);
+        let failures = Cell::new(2);
+        let loader = |key: &i32| match failures.get() {
+            0 => Ok(key * 10),
+            n => {
+                failures.set(n - 1);
+                Err(n)
+            }
+        };
+
+        assert_eq!(Retry::new(&loader, policy.clone()).load(&1), Ok(10));
+        failures.set(2);
+        let once = RetryPolicy {
+            attempts: 2,
+            ..policy
+        };
+        assert_eq!(Retry::new(&loader, once).load(&1), Err(1));
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy =
+            RetryPolicy::new(5).backoff(Duration::from_millis(100), Duration::from_millis(300));
+        let delays: Vec<_> = (1..=4)
+            .map(|retry| policy.delay(retry).as_millis())
+            .collect();
+        assert_eq!(delays, vec![100, 200, 300, 300]);
+
+        let jittered = policy.jitter(0.5).delay(2);
+        assert!(jittered >= Duration::from_millis(100) && jittered <= Duration::from_millis(200));
+    }
+}
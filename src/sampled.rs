@@ -0,0 +1,328 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::stats::CacheStats;
+
+/// Nombre d'entrées examinées par éviction si rien n'est précisé
+pub const DEFAULT_SAMPLE_SIZE: usize = 5;
+
+/// Compteurs de hits et misses répartis pour que deux threads se partagent
+/// rarement le même
+const STRIPES: usize = 16;
+
+thread_local! {
+    /// Compteur attribué au thread courant à sa première lecture
+    static STRIPE: usize = {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        NEXT.fetch_add(1, Ordering::Relaxed) % STRIPES
+    };
+}
+
+/// Cache LRU approximatif pour des débits de lecture extrêmes
+///
+/// Au lieu de maintenir un ordre LRU exact (qui impose une écriture par
+/// lecture), chaque entrée porte un horodatage logique mis à jour par une
+/// opération atomique `Relaxed`. L'horloge n'avance qu'aux écritures: une
+/// lecture la lit sans la modifier, et ne réécrit l'horodatage de l'entrée
+/// que s'il a changé. Les hits et misses vont dans des compteurs répartis
+/// entre threads.
+///
+/// Les lectures prennent encore le verrou partagé: elles ne s'attendent
+/// pas entre elles, mais chacune modifie le compteur de lecteurs du
+/// verrou, et toutes attendent la fin d'une écriture en cours. Une
+/// lecture sans aucun verrou demanderait une récupération mémoire différée
+/// (par époques) que le crate n'implémente pas.
+///
+/// À l'éviction, `sample_size` entrées tirées au hasard sont comparées et la
+/// plus anciennement utilisée est retirée (comme l'éviction approchée de
+/// Redis). Plus l'échantillon est grand, plus on se rapproche du LRU exact.
+///
+/// # Ordonnancement mémoire
+///
+/// Les horodatages et compteurs n'ont besoin d'aucune synchronisation: une
+/// valeur légèrement périmée ne fait que rendre l'éviction un peu moins
+/// précise. La cohérence des données elles-mêmes est assurée par le verrou.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::SampledLruCache;
+///
+/// let cache = SampledLruCache::new(2, 8);
+/// cache.put("a", 1);
+/// cache.put("b", 2);
+/// cache.get(&"a");
+/// cache.put("c", 3); // l'échantillon couvre tout le cache: "b" est évincé
+///
+/// assert!(cache.contains_key(&"a"));
+/// assert!(!cache.contains_key(&"b"));
+/// ```
+pub struct SampledLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    inner: Arc<Sampled<K, V>>,
+}
+
+struct Sampled<K, V> {
+    capacity: usize,
+    sample_size: usize,
+    table: RwLock<Table<K, V>>,
+    /// Avancée par les écritures seulement
+    clock: AtomicU64,
+    seed: AtomicU64,
+    hits: [Counter; STRIPES],
+    misses: [Counter; STRIPES],
+    evictions: AtomicU64,
+}
+
+/// Un compteur par ligne de cache, sans faux partage entre voisins
+#[derive(Default)]
+#[repr(align(64))]
+struct Counter(AtomicU64);
+
+impl Counter {
+    fn sum(counters: &[Counter]) -> u64 {
+        counters
+            .iter()
+            .map(|counter| counter.0.load(Ordering::Relaxed))
+            .sum()
+    }
+}
+
+struct Table<K, V> {
+    entries: HashMap<K, Entry<V>>,
+    // Clés indexées par position, pour tirer un échantillon en O(1)
+    slots: Vec<K>,
+}
+
+struct Entry<V> {
+    value: Arc<V>,
+    last_access: AtomicU64,
+    slot: usize,
+}
+
+impl<K, V> Clone for SampledLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> SampledLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize, sample_size: usize) -> Self {
+        Self {
+            inner: Arc::new(Sampled {
+                capacity,
+                sample_size: sample_size.max(1),
+                table: RwLock::new(Table {
+                    entries: HashMap::new(),
+                    slots: Vec::new(),
+                }),
+                clock: AtomicU64::new(0),
+                seed: AtomicU64::new(RandomState::new().hash_one(0u64) | 1),
+                hits: Default::default(),
+                misses: Default::default(),
+                evictions: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    pub fn put(&self, key: K, value: V) -> Option<Arc<V>> {
+        if self.inner.capacity == 0 {
+            return None;
+        }
+
+        let mut table = self.write();
+        let now = self.tick();
+
+        if let Some(entry) = table.entries.get_mut(&key) {
+            entry.last_access.store(now, Ordering::Relaxed);
+            return Some(std::mem::replace(&mut entry.value, Arc::new(value)));
+        }
+
+        if table.entries.len() >= self.inner.capacity {
+            self.evict_sampled(&mut table);
+        }
+
+        let slot = table.slots.len();
+        table.slots.push(key.clone());
+        table.entries.insert(
+            key,
+            Entry {
+                value: Arc::new(value),
+                last_access: AtomicU64::new(now),
+                slot,
+            },
+        );
+        None
+    }
+
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.get_arc(key).map(|value| V::clone(&value))
+    }
+
+    /// Lecture sous verrou partagé; la récence est notée de façon atomique
+    pub fn get_arc(&self, key: &K) -> Option<Arc<V>> {
+        let table = self.read();
+        let stripe = STRIPE.with(|stripe| *stripe);
+        match table.entries.get(key) {
+            Some(entry) => {
+                // Une entrée très lue n'est réécrite qu'une fois par écriture
+                let now = self.inner.clock.load(Ordering::Relaxed);
+                if entry.last_access.load(Ordering::Relaxed) != now {
+                    entry.last_access.store(now, Ordering::Relaxed);
+                }
+                self.inner.hits[stripe].0.fetch_add(1, Ordering::Relaxed);
+                Some(Arc::clone(&entry.value))
+            }
+            None => {
+                self.inner.misses[stripe].0.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    pub fn peek(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.read()
+            .entries
+            .get(key)
+            .map(|entry| V::clone(&entry.value))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.read().entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    pub fn sample_size(&self) -> usize {
+        self.inner.sample_size
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: Counter::sum(&self.inner.hits),
+            misses: Counter::sum(&self.inner.misses),
+            evictions: self.inner.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    fn evict_sampled(&self, table: &mut Table<K, V>) {
+        let len = table.slots.len();
+        if len == 0 {
+            return;
+        }
+
+        // Petit cache: l'échantillon couvre tout, l'éviction est exacte
+        let candidates: Vec<usize> = if self.inner.sample_size >= len {
+            (0..len).collect()
+        } else {
+            (0..self.inner.sample_size)
+                .map(|_| (self.random() % len as u64) as usize)
+                .collect()
+        };
+
+        let victim = candidates
+            .into_iter()
+            .min_by_key(|&slot| {
+                let key = &table.slots[slot];
+                table.entries[key].last_access.load(Ordering::Relaxed)
+            })
+            .unwrap_or(0);
+
+        let key = table.slots.swap_remove(victim);
+        table.entries.remove(&key);
+        if let Some(moved) = table.slots.get(victim) {
+            if let Some(entry) = table.entries.get_mut(moved) {
+                entry.slot = victim;
+            }
+        }
+        self.inner.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn tick(&self) -> u64 {
+        self.inner.clock.fetch_add(1, Ordering::Relaxed)
+    }
+
+    // xorshift64: suffisant pour choisir un échantillon, sans dépendance
+    fn random(&self) -> u64 {
+        let mut x = self.inner.seed.load(Ordering::Relaxed);
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.inner.seed.store(x, Ordering::Relaxed);
+        x
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Table<K, V>> {
+        self.inner.table.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Table<K, V>> {
+        self.inner.table.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_capacity_holds_under_concurrency() {
+        let cache = SampledLruCache::new(50, DEFAULT_SAMPLE_SIZE);
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        cache.put(t * 100 + i, i);
+                        cache.get(&(t * 100));
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(cache.len(), 50);
+        let stats = cache.stats();
+        assert_eq!(stats.evictions, 350);
+        assert_eq!(stats.hits + stats.misses, 400);
+        // Les slots restent alignés avec la table après les swap_remove
+        let table = cache.read();
+        for (slot, key) in table.slots.iter().enumerate() {
+            assert_eq!(table.entries[key].slot, slot);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Boucle d'acceptation TCP commune aux modes serveur
+
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+/// Serveur lancé sur un thread de fond, arrêté à l'abandon
+///
+/// L'arrêt ferme l'écoute: les connexions déjà ouvertes sont servies
+/// jusqu'à ce que leur client les ferme.
+pub struct ServerHandle {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    /// Adresse d'écoute (utile après un `bind` sur le port 0)
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Arrête l'écoute et attend la fin du thread d'acceptation
+    pub fn stop(self) {}
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Une connexion à soi-même débloque `accept`
+        let _ = TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Accepte les connexions jusqu'à `stop`, chacune servie par `handle` sur
+/// son propre thread
+pub(crate) fn serve<F>(listener: &TcpListener, stop: &AtomicBool, handle: F)
+where
+    F: Fn(TcpStream) -> io::Result<()> + Clone + Send + 'static,
+{
+    for stream in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else { continue };
+        let handle = handle.clone();
+        // Une erreur ne concerne que cette connexion
+        thread::spawn(move || handle(stream));
+    }
+}
+
+/// Comme `serve`, sur un thread de fond
+pub(crate) fn spawn<F>(listener: TcpListener, handle: F) -> io::Result<ServerHandle>
+where
+    F: Fn(TcpStream) -> io::Result<()> + Clone + Send + 'static,
+{
+    let addr = listener.local_addr()?;
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopping = Arc::clone(&stop);
+    let thread = thread::spawn(move || serve(&listener, &stopping, handle));
+    Ok(ServerHandle {
+        addr,
+        stop,
+        thread: Some(thread),
+    })
+}
@@ -0,0 +1,145 @@
+//! Persistance répartie entre plusieurs fichiers de segment
+//!
+//! ```text
+//! cache/
+//! ├── shard-0      - instantané des clés dont crc32(clé) % n == 0
+//! ├── shard-1
+//! └── ...
+//! ```
+//!
+//! Seuls les segments modifiés depuis la dernière sauvegarde sont réécrits,
+//! chacun sur son propre thread.
+
+use std::fs;
+use std::hash::Hash;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use crate::cache::LruCache;
+use crate::checksum::crc32;
+use crate::persistent::write_atomic;
+use crate::stats::CacheStats;
+
+pub(crate) struct ShardFiles {
+    /// Segments à réécrire à la prochaine sauvegarde
+    dirty: Vec<bool>,
+}
+
+impl ShardFiles {
+    pub(crate) fn new(count: usize) -> Self {
+        Self {
+            dirty: vec![false; count.max(1)],
+        }
+    }
+
+    pub(crate) fn path(root: &Path, shard: usize) -> PathBuf {
+        root.join(format!("shard-{}", shard))
+    }
+
+    /// Segment d'une clé, d'après ses octets persistés: stable d'une
+    /// exécution à l'autre
+    pub(crate) fn shard_of(&self, key: &[u8]) -> usize {
+        crc32(key) as usize % self.dirty.len()
+    }
+
+    pub(crate) fn mark(&mut self, key: &[u8]) {
+        let shard = self.shard_of(key);
+        self.dirty[shard] = true;
+    }
+
+    pub(crate) fn mark_all(&mut self) {
+        self.dirty.fill(true);
+    }
+
+    pub(crate) fn dirty(&self) -> Vec<usize> {
+        (0..self.dirty.len()).filter(|&i| self.dirty[i]).collect()
+    }
+
+    /// Écrit les segments en parallèle, puis les marque comme sauvegardés
+    pub(crate) fn write(
+        &mut self,
+        root: &Path,
+        files: Vec<(usize, Vec<u8>)>,
+        keep_backup: bool,
+        sync: bool,
+    ) -> io::Result<()> {
+        fs::create_dir_all(root)?;
+        let results: Vec<io::Result<()>> = thread::scope(|scope| {
+            let writers: Vec<_> = files
+                .iter()
+                .map(|(shard, bytes)| {
+                    let path = Self::path(root, *shard);
+                    scope.spawn(move || write_atomic(&path, bytes, keep_backup, sync))
+                })
+                .collect();
+            writers
+                .into_iter()
+                .map(|writer| writer.join().expect("écriture de segment"))
+                .collect()
+        });
+        results.into_iter().collect::<io::Result<()>>()?;
+
+        for (shard, _) in files {
+            self.dirty[shard] = false;
+        }
+        Ok(())
+    }
+}
+
+/// Fusionne les segments relus en un seul cache
+///
+/// L'ordre LRU n'est connu qu'au sein d'un segment: les entrées sont
+/// entrelacées selon leur rang relatif dans le leur. Les compteurs de
+/// chaque segment datant de sa dernière écriture, on garde les plus grands.
+pub(crate) fn merge<K, V>(shards: Vec<LruCache<K, V>>, capacity: usize) -> LruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    let mut ranked = Vec::new();
+    let mut stats = CacheStats::default();
+    for (shard, cache) in shards.iter().enumerate() {
+        let saved = cache.stats();
+        stats.hits = stats.hits.max(saved.hits);
+        stats.misses = stats.misses.max(saved.misses);
+        stats.evictions = stats.evictions.max(saved.evictions);
+
+        let len = cache.len() as f64;
+        for (index, (key, _)) in cache.iter().enumerate() {
+            ranked.push(((index + 1) as f64 / len, shard, key.clone()));
+        }
+    }
+    ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let mut shards = shards;
+    let mut merged = LruCache::new(capacity);
+    merged.set_stats(stats);
+    for (_, shard, key) in ranked {
+        let (expires_at, hits) = shards[shard].entry_metadata(&key);
+        if let Some(value) = shards[shard].remove(&key) {
+            merged.restore_entry(key.clone(), value);
+            merged.restore_metadata(&key, expires_at, hits);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_interleaves_shards() {
+        let mut first = LruCache::new(10);
+        first.put("a1", 1);
+        first.put("a2", 2);
+        let mut second = LruCache::new(10);
+        for (key, value) in [("b1", 1), ("b2", 2), ("b3", 3), ("b4", 4)] {
+            second.put(key, value);
+        }
+
+        let merged = merge(vec![first, second], 10);
+        let keys: Vec<_> = merged.iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec!["b1", "a1", "b2", "b3", "a2", "b4"]);
+    }
+}
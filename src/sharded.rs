@@ -0,0 +1,304 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::maintenance::{Maintain, Watermarks};
+use crate::stats::CacheStats;
+use crate::sync::SyncLruCache;
+
+/// Occupation et statistiques d'un segment de `ShardedLruCache`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShardStats {
+    pub len: usize,
+    pub capacity: usize,
+    pub stats: CacheStats,
+}
+
+impl ShardStats {
+    pub fn hit_rate(&self) -> f64 {
+        self.stats.hit_rate()
+    }
+}
+
+/// Cache concurrent partitionné en segments verrouillés indépendamment
+///
+/// Chaque clé appartient à un seul segment (selon son hash), ce qui réduit
+/// la contention quand de nombreux threads écrivent en même temps. L'ordre
+/// LRU est maintenu par segment: la capacité totale est répartie entre eux.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::ShardedLruCache;
+///
+/// let cache = ShardedLruCache::new(1000, 8);
+/// cache.put("a".to_string(), 1);
+///
+/// assert_eq!(cache.get(&"a".to_string()), Some(1));
+/// assert_eq!(cache.shard_count(), 8);
+/// ```
+pub struct ShardedLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    shards: Arc<Vec<SyncLruCache<K, V>>>,
+    hasher: RandomState,
+}
+
+impl<K, V> Clone for ShardedLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            shards: Arc::clone(&self.shards),
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+impl<K, V> ShardedLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Crée un cache de `capacity` entrées réparties sur `shard_count` segments
+    ///
+    /// La capacité de chaque segment est arrondie au supérieur; au moins un
+    /// segment est toujours créé.
+    pub fn new(capacity: usize, shard_count: usize) -> Self {
+        let shard_count = shard_count.max(1);
+        let per_shard = capacity.div_ceil(shard_count);
+
+        Self {
+            shards: Arc::new(
+                (0..shard_count)
+                    .map(|_| SyncLruCache::new(per_shard))
+                    .collect(),
+            ),
+            hasher: RandomState::new(),
+        }
+    }
+
+    pub fn put(&self, key: K, value: V) -> Option<Arc<V>> {
+        self.shard(&key).put(key, value)
+    }
+
+    pub fn put_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<Arc<V>> {
+        self.shard(&key).put_with_ttl(key, value, ttl)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<Arc<V>> {
+        self.shard(key).remove(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.shard(key).get(key)
+    }
+
+    pub fn get_arc(&self, key: &K) -> Option<Arc<V>> {
+        self.shard(key).get_arc(key)
+    }
+
+    pub fn peek(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.shard(key).peek(key)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.shard(key).contains_key(key)
+    }
+
+    /// Voir `SyncLruCache::get_or_insert_with`: le verrou est pris par clé
+    pub fn get_or_insert_with<F>(&self, key: K, load: F) -> V
+    where
+        F: FnOnce() -> V,
+        V: Clone,
+    {
+        self.shard(&key).get_or_insert_with(key, load)
+    }
+
+    /// Nombre total d'entrées, tous segments confondus
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(SyncLruCache::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(SyncLruCache::is_empty)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shards.iter().map(SyncLruCache::capacity).sum()
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Statistiques cumulées de tous les segments
+    pub fn stats(&self) -> CacheStats {
+        self.shards.iter().map(SyncLruCache::stats).sum()
+    }
+
+    /// Statistiques de chaque segment, dans l'ordre des segments
+    ///
+    /// Un segment nettement plus rempli ou plus sollicité que les autres
+    /// révèle une mauvaise répartition des clés.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::ShardedLruCache;
+    ///
+    /// let cache = ShardedLruCache::new(100, 4);
+    /// cache.put(1, "a");
+    ///
+    /// let shards = cache.shard_stats();
+    /// assert_eq!(shards.len(), 4);
+    /// assert_eq!(shards.iter().map(|s| s.len).sum::<usize>(), 1);
+    /// ```
+    pub fn shard_stats(&self) -> Vec<ShardStats> {
+        self.shards
+            .iter()
+            .map(|shard| ShardStats {
+                len: shard.len(),
+                capacity: shard.capacity(),
+                stats: shard.stats(),
+            })
+            .collect()
+    }
+
+    pub fn purge_expired(&self) -> usize {
+        self.shards.iter().map(SyncLruCache::purge_expired).sum()
+    }
+
+    /// Copie des entrées de tous les segments
+    ///
+    /// Chaque segment est copié à un instant précis, l'un après l'autre:
+    /// l'ordre n'est significatif qu'à l'intérieur d'un segment.
+    pub fn snapshot(&self) -> Vec<(K, V)>
+    where
+        V: Clone,
+    {
+        self.shards.iter().flat_map(SyncLruCache::snapshot).collect()
+    }
+
+    pub fn snapshot_arc(&self) -> Vec<(K, Arc<V>)> {
+        self.shards
+            .iter()
+            .flat_map(SyncLruCache::snapshot_arc)
+            .collect()
+    }
+
+    fn shard(&self, key: &K) -> &SyncLruCache<K, V> {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        &self.shards[index]
+    }
+}
+
+/// Les seuils s'appliquent à chaque segment au prorata de leur nombre
+impl<K, V> Maintain for ShardedLruCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    fn run_maintenance(&self, watermarks: Option<Watermarks>) {
+        let count = self.shards.len();
+        let per_shard = watermarks.map(|w| Watermarks {
+            high: w.high.div_ceil(count),
+            low: w.low / count,
+        });
+
+        for shard in self.shards.iter() {
+            shard.run_maintenance(per_shard);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_sharded_concurrent_puts() {
+        let cache = ShardedLruCache::new(1000, 4);
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    for i in 0..50 {
+                        cache.put(t * 50 + i, i);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(cache.len(), 200);
+        assert_eq!(cache.get(&120), Some(20));
+        assert_eq!(cache.get(&9999), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+
+    #[test]
+    fn test_get_arc_shares_cached_value() {
+        let cache = ShardedLruCache::new(1, 1);
+        cache.put(1, "un".to_string());
+
+        let value = cache.get_arc(&1).unwrap();
+        assert!(Arc::ptr_eq(&value, &cache.get_arc(&1).unwrap()));
+        assert!(Arc::ptr_eq(&value, &cache.snapshot_arc()[0].1));
+
+        cache.put(2, "deux".to_string()); // évince 1
+        assert_eq!(cache.get_arc(&1), None);
+        assert_eq!(Arc::strong_count(&value), 1);
+        assert_eq!(*value, "un");
+    }
+
+    #[test]
+    fn test_shard_stats_attribution() {
+        let cache = ShardedLruCache::new(8, 4);
+        let index = |key: &u32| cache.hasher.hash_one(key) as usize % 4;
+
+        for key in 0..40 {
+            cache.put(key, key);
+        }
+        for key in 0..40 {
+            cache.get(&key);
+        }
+
+        // Chaque segment garde ses 2 dernières clés: le reste est évincé,
+        // puis compté comme miss
+        let mut puts = [0u64; 4];
+        for key in 0..40 {
+            puts[index(&key)] += 1;
+        }
+        let shards = cache.shard_stats();
+        for (shard, puts) in shards.iter().zip(puts) {
+            let kept = puts.min(2);
+            assert_eq!(shard.len as u64, kept);
+            assert_eq!(shard.stats.hits, kept);
+            assert_eq!(shard.stats.misses, puts - kept);
+            assert_eq!(shard.stats.evictions, puts - kept);
+        }
+
+        let total: CacheStats = shards.iter().map(|shard| shard.stats).sum();
+        assert_eq!(total, cache.stats());
+        assert_eq!(
+            (total.hits + total.misses, total.evictions),
+            (40, 40 - total.hits)
+        );
+    }
+}
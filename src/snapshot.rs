@@ -0,0 +1,61 @@
+use std::time::Instant;
+
+use crate::stats::CacheStats;
+
+/// Copie du contenu d'un `LruCache`, obtenue par `export` et rechargée par
+/// `import`
+///
+/// Les entrées sont rangées de la moins à la plus récemment utilisée. Les
+/// TTL et les hits par entrée ne sont pas conservés par `export`; ils le
+/// sont par `LruCache::snapshot`, avec les étiquettes et les versions.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::LruCache;
+///
+/// let mut cache = LruCache::new(2);
+/// cache.put("a", 1);
+/// let checkpoint = cache.export();
+///
+/// cache.put("b", 2);
+/// cache.import(checkpoint);
+/// assert!(!cache.contains_key(&"b"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheSnapshot<K, V> {
+    pub capacity: usize,
+    pub entries: Vec<(K, V)>,
+    pub stats: CacheStats,
+    /// Métadonnées de chaque entrée, associées à sa clé
+    /// (`LruCache::snapshot` uniquement)
+    pub(crate) state: Option<SnapshotState<K>>,
+}
+
+impl<K, V> CacheSnapshot<K, V> {
+    /// Instantané sans métadonnées par entrée, comme celui de `export`
+    pub fn new(capacity: usize, entries: Vec<(K, V)>, stats: CacheStats) -> Self {
+        Self {
+            capacity,
+            entries,
+            stats,
+            state: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SnapshotState<K> {
+    /// Par clé et non par position: `entries` est public et peut avoir été
+    /// réordonné ou modifié avant `restore`
+    pub(crate) entries: Vec<(K, EntryState)>,
+    pub(crate) next_version: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EntryState {
+    pub(crate) expires_at: Option<Instant>,
+    pub(crate) hits: u64,
+    pub(crate) tags: Vec<String>,
+    pub(crate) version: u64,
+}
@@ -0,0 +1,1195 @@
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+use crate::cache::{EntryInfo, LruCache, VersionConflict};
+use crate::maintenance::{Maintain, Watermarks};
+use crate::snapshot::CacheSnapshot;
+use crate::stats::CacheStats;
+
+/// Un chargeur a rappelé `get_or_insert_with` sur la clé qu'il charge
+///
+/// Attendre la fin de son propre chargement bloquerait le thread pour
+/// toujours: l'appel est refusé à la place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReentrantLoadError;
+
+impl fmt::Display for ReentrantLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "chargement réentrant de la clé en cours de chargement")
+    }
+}
+
+impl std::error::Error for ReentrantLoadError {}
+
+/// Sort des écritures reçues par un cache gelé, voir `SyncLruCache::freeze`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrozenWrites {
+    /// Écritures ignorées sans erreur, y compris par `try_put`
+    Ignore,
+    /// Écritures ignorées; `try_put` renvoie `FrozenError`
+    Reject,
+}
+
+/// Écriture refusée: cache gelé avec `FrozenWrites::Reject`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrozenError;
+
+impl fmt::Display for FrozenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cache gelé: écriture refusée")
+    }
+}
+
+impl std::error::Error for FrozenError {}
+
+const THAWED: u8 = 0;
+const FROZEN_IGNORE: u8 = 1;
+const FROZEN_REJECT: u8 = 2;
+
+/// `compare_and_put` refusé: un autre écrivain a changé la valeur entre
+/// la lecture et l'écriture
+#[derive(Debug, Clone, PartialEq)]
+pub struct CasError<V> {
+    /// Valeur trouvée à la place de celle attendue (`None`: clé absente)
+    pub current: Option<Arc<V>>,
+    /// Valeur refusée, rendue à l'appelant
+    pub rejected: V,
+}
+
+impl<V> fmt::Display for CasError<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "valeur modifiée depuis sa lecture")
+    }
+}
+
+impl<V: fmt::Debug> std::error::Error for CasError<V> {}
+
+/// Cache LRU partageable entre threads
+///
+/// Les méthodes prennent `&self`; cloner le cache ne copie pas les données,
+/// les clones partagent le même contenu.
+///
+/// Les lectures qui ne modifient pas l'ordre LRU (`peek`, `contains_key`,
+/// `len`...) ne prennent que le verrou partagé et ne se bloquent donc pas
+/// entre elles. `get` promeut la clé et prend le verrou exclusif.
+///
+/// Les valeurs sont stockées derrière un `Arc`: `get_arc` en retourne un
+/// clone bon marché qui reste valide après éviction de l'entrée.
+///
+/// # Exemples
+///
+/// ```
+/// use lru_cache::SyncLruCache;
+/// use std::thread;
+///
+/// let cache = SyncLruCache::new(10);
+/// let worker = cache.clone();
+///
+/// thread::spawn(move || {
+///     worker.put(1, "un".to_string());
+/// })
+/// .join()
+/// .unwrap();
+///
+/// assert_eq!(cache.get(&1), Some("un".to_string()));
+/// ```
+pub struct SyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    inner: Arc<RwLock<LruCache<K, Arc<V>>>>,
+    loaders: Arc<Mutex<HashMap<K, Arc<KeyLock>>>>,
+    frozen: Arc<AtomicU8>,
+}
+
+impl<K, V> Clone for SyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            loaders: Arc::clone(&self.loaders),
+            frozen: Arc::clone(&self.frozen),
+        }
+    }
+}
+
+impl<K, V> SyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(LruCache::new(capacity))),
+            loaders: Arc::new(Mutex::new(HashMap::new())),
+            frozen: Arc::new(AtomicU8::new(THAWED)),
+        }
+    }
+
+    /// Gèle le cache: il passe en lecture seule pour tous ses clones
+    ///
+    /// Les lectures restent possibles mais ne prennent que le verrou
+    /// partagé: ni l'ordre LRU, ni les hits, ni les statistiques ne
+    /// changent. Les écritures (insertions, suppressions, transactions,
+    /// maintenance) sont ignorées; `writes` décide si `try_put` le signale.
+    /// Les `get_or_insert_with` chargent toujours les clés absentes, sans
+    /// les insérer.
+    ///
+    /// `freeze` attend la fin des écritures en cours: une fois qu'il a
+    /// rendu la main, plus aucune écriture n'atteint le cache.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::{FrozenError, FrozenWrites, SyncLruCache};
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// cache.put("config", 1);
+    ///
+    /// cache.freeze(FrozenWrites::Reject);
+    /// assert_eq!(cache.try_put("config", 2), Err(FrozenError));
+    /// assert_eq!(cache.get(&"config"), Some(1));
+    ///
+    /// cache.thaw();
+    /// cache.put("config", 2);
+    /// assert_eq!(cache.get(&"config"), Some(2));
+    /// ```
+    pub fn freeze(&self, writes: FrozenWrites) {
+        let state = match writes {
+            FrozenWrites::Ignore => FROZEN_IGNORE,
+            FrozenWrites::Reject => FROZEN_REJECT,
+        };
+        let _cache = self.write();
+        self.frozen.store(state, Ordering::SeqCst);
+    }
+
+    /// Annule `freeze`
+    pub fn thaw(&self) {
+        let _cache = self.write();
+        self.frozen.store(THAWED, Ordering::SeqCst);
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen.load(Ordering::SeqCst) != THAWED
+    }
+
+    /// Comme `put`, mais signale une écriture refusée par un cache gelé
+    /// avec `FrozenWrites::Reject`
+    pub fn try_put(&self, key: K, value: V) -> Result<Option<Arc<V>>, FrozenError> {
+        // L'état est lu sous le verrou: un `freeze` concurrent passe avant
+        // ou après l'écriture, jamais entre la vérification et l'écriture
+        let mut cache = self.write();
+        match self.frozen.load(Ordering::SeqCst) {
+            THAWED => Ok(cache.put(key, Arc::new(value))),
+            FROZEN_REJECT => Err(FrozenError),
+            _ => Ok(None),
+        }
+    }
+
+    /// Insère une paire clé-valeur, retourne l'ancienne valeur
+    pub fn put(&self, key: K, value: V) -> Option<Arc<V>> {
+        self.writable()?.put(key, Arc::new(value))
+    }
+
+    /// Remplace la valeur seulement si elle vaut toujours `expected`
+    /// (`None`: seulement si la clé est absente), retourne l'ancienne valeur
+    ///
+    /// La comparaison et l'écriture se font sous le même verrou: deux
+    /// écrivains partis de la même valeur ne peuvent pas s'écraser, le
+    /// second reçoit la valeur du premier dans l'erreur.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// cache.put("stock", 5);
+    ///
+    /// assert!(cache.compare_and_put("stock", Some(&5), 4).is_ok());
+    /// let err = cache.compare_and_put("stock", Some(&5), 4).unwrap_err();
+    /// assert_eq!(err.current.as_deref(), Some(&4));
+    /// ```
+    pub fn compare_and_put(
+        &self,
+        key: K,
+        expected: Option<&V>,
+        value: V,
+    ) -> Result<Option<Arc<V>>, CasError<V>>
+    where
+        V: PartialEq,
+    {
+        let Some(mut cache) = self.writable() else {
+            return Err(CasError {
+                current: self.peek_arc(&key),
+                rejected: value,
+            });
+        };
+        let current = cache.peek(&key).cloned();
+        if current.as_deref() != expected {
+            return Err(CasError {
+                current,
+                rejected: value,
+            });
+        }
+        Ok(cache.put(key, Arc::new(value)))
+    }
+
+    /// Écrit seulement si l'entrée est toujours à la version `expected`,
+    /// voir `LruCache::put_if_version`
+    pub fn put_if_version(
+        &self,
+        key: K,
+        value: V,
+        expected: Option<u64>,
+    ) -> Result<u64, VersionConflict<V>> {
+        let Some(mut cache) = self.writable() else {
+            return Err(VersionConflict {
+                current: self.read().version(&key),
+                rejected: value,
+            });
+        };
+        cache
+            .put_if_version(key, Arc::new(value), expected)
+            .map_err(|conflict| VersionConflict {
+                current: conflict.current,
+                rejected: Arc::try_unwrap(conflict.rejected)
+                    .unwrap_or_else(|_| unreachable!("valeur jamais partagée")),
+            })
+    }
+
+    /// Insère une entrée qui expire après `ttl`
+    pub fn put_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<Arc<V>> {
+        self.writable()?.put_with_ttl(key, Arc::new(value), ttl)
+    }
+
+    /// Insère un lot sous un seul verrou et renvoie les entrées évincées
+    /// (voir `LruCache::put_many`)
+    pub fn put_many<I>(&self, entries: I) -> Vec<(K, Arc<V>)>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let entries: Vec<(K, Arc<V>)> = entries
+            .into_iter()
+            .map(|(key, value)| (key, Arc::new(value)))
+            .collect();
+        match self.writable() {
+            Some(mut cache) => cache.put_many(entries),
+            None => Vec::new(),
+        }
+    }
+
+    /// Insère une entrée portant des étiquettes (voir
+    /// `LruCache::put_with_tags`)
+    pub fn put_with_tags<I, T>(&self, key: K, value: V, tags: I) -> Option<Arc<V>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.writable()?.put_with_tags(key, Arc::new(value), tags)
+    }
+
+    /// Retire sous un seul verrou toutes les entrées portant `tag`
+    pub fn invalidate_tag(&self, tag: &str) -> Vec<(K, Arc<V>)> {
+        match self.writable() {
+            Some(mut cache) => cache.invalidate_tag(tag),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn remove(&self, key: &K) -> Option<Arc<V>> {
+        self.writable()?.remove(key)
+    }
+
+    /// Retire un lot de clés sous un seul verrou et renvoie les entrées
+    /// retirées (voir `LruCache::remove_many`)
+    pub fn remove_many<I>(&self, keys: I) -> Vec<(K, Arc<V>)>
+    where
+        I: IntoIterator<Item = K>,
+    {
+        match self.writable() {
+            Some(mut cache) => cache.remove_many(keys),
+            None => Vec::new(),
+        }
+    }
+
+    /// Applique un lot d'opérations de façon atomique
+    ///
+    /// Les écritures faites via `txn` sont mises en attente puis appliquées
+    /// ensemble, sous le verrou exclusif tenu pendant toute la closure: les
+    /// autres threads voient soit l'état d'avant, soit l'état d'après. Si la
+    /// closure panique, rien n'est appliqué.
+    ///
+    /// La closure ne doit passer que par `txn`: toute autre méthode de ce
+    /// cache (ou d'un de ses clones), même une lecture, attend ce verrou et
+    /// bloque le thread indéfiniment. Contrairement à `get_or_insert_with`,
+    /// cet interblocage n'est pas détecté.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// cache.put("solde:a", 100);
+    /// cache.put("solde:b", 0);
+    ///
+    /// cache.transaction(|txn| {
+    ///     let a = *txn.peek(&"solde:a").unwrap();
+    ///     txn.put("solde:a", a - 30);
+    ///     txn.put("solde:b", 30);
+    /// });
+    ///
+    /// assert_eq!(cache.get(&"solde:a"), Some(70));
+    /// assert_eq!(cache.get(&"solde:b"), Some(30));
+    /// ```
+    pub fn transaction<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Transaction<'_, K, V>) -> R,
+    {
+        let mut cache = self.write();
+        let mut txn = Transaction {
+            cache: &cache,
+            ops: Vec::new(),
+        };
+
+        let result = f(&mut txn);
+        let ops = txn.ops;
+        if self.is_frozen() {
+            return result;
+        }
+
+        for op in ops {
+            match op {
+                TxnOp::Put(key, value) => {
+                    cache.put(key, value);
+                }
+                TxnOp::Remove(key) => {
+                    cache.remove(&key);
+                }
+            }
+        }
+        result
+    }
+
+    /// Récupère une copie de la valeur et la marque comme récemment utilisée
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.get_arc(key).map(|value| V::clone(&value))
+    }
+
+    /// Récupère plusieurs valeurs sous un seul verrou (voir
+    /// `LruCache::get_many`)
+    pub fn get_many<I>(&self, keys: I) -> Vec<Option<V>>
+    where
+        I: IntoIterator<Item = K>,
+        V: Clone,
+    {
+        if self.is_frozen() {
+            let cache = self.read();
+            return keys
+                .into_iter()
+                .map(|key| cache.peek(&key).map(|value| V::clone(value)))
+                .collect();
+        }
+        self.write()
+            .get_many(keys)
+            .into_iter()
+            .map(|value| value.map(|value| V::clone(value)))
+            .collect()
+    }
+
+    /// Valeur partagée et métadonnées, voir `LruCache::get_with_meta`
+    pub fn get_with_meta(&self, key: &K) -> Option<(Arc<V>, EntryInfo)> {
+        if self.is_frozen() {
+            let cache = self.read();
+            let info = EntryInfo {
+                version: cache.version(key)?,
+                hits: cache.hit_count(key)?,
+                ttl: cache.time_to_live(key),
+            };
+            return Some((Arc::clone(cache.peek(key)?), info));
+        }
+        self.write()
+            .get_with_meta(key)
+            .map(|(value, info)| (Arc::clone(value), info))
+    }
+
+    /// Récupère la valeur partagée et la marque comme récemment utilisée
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(1);
+    /// cache.put("a", vec![1, 2, 3]);
+    ///
+    /// let value = cache.get_arc(&"a").unwrap();
+    /// cache.put("b", vec![4]); // évince "a"
+    ///
+    /// assert_eq!(*value, vec![1, 2, 3]);
+    /// ```
+    pub fn get_arc(&self, key: &K) -> Option<Arc<V>> {
+        if self.is_frozen() {
+            return self.peek_arc(key);
+        }
+        self.write().get(key).cloned()
+    }
+
+    /// Copie de la valeur sans la promouvoir (verrou partagé uniquement)
+    pub fn peek(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.peek_arc(key).map(|value| V::clone(&value))
+    }
+
+    pub fn peek_arc(&self, key: &K) -> Option<Arc<V>> {
+        self.read().peek(key).cloned()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.read().contains_key(key)
+    }
+
+    /// Voir `LruCache::hit_count`
+    pub fn hit_count(&self, key: &K) -> Option<u64> {
+        self.read().hit_count(key)
+    }
+
+    /// Voir `LruCache::time_to_live`
+    pub fn time_to_live(&self, key: &K) -> Option<Duration> {
+        self.read().time_to_live(key)
+    }
+
+    /// Voir `LruCache::set_ttl`; `false` si le cache est gelé
+    pub fn set_ttl(&self, key: &K, ttl: Option<Duration>) -> bool {
+        self.writable().is_some_and(|mut cache| cache.set_ttl(key, ttl))
+    }
+
+    /// Retourne la valeur en cache, ou exécute `load` et insère son résultat
+    ///
+    /// Le verrou est pris par clé pendant le chargement: deux appels pour la
+    /// même clé n'exécutent `load` qu'une fois, mais un chargement lent ne
+    /// bloque ni les lectures ni les écritures des autres clés.
+    ///
+    /// Aucun verrou du cache n'est tenu pendant `load`, qui peut donc appeler
+    /// les autres méthodes du cache. Rappeler `get_or_insert_with` sur la même
+    /// clé depuis `load` provoque une panique explicite plutôt qu'un
+    /// interblocage; voir `try_get_or_insert_with`, et ses limites entre
+    /// plusieurs threads.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// assert_eq!(cache.get_or_insert_with(1, || "un"), "un");
+    /// assert_eq!(cache.get_or_insert_with(1, || unreachable!()), "un");
+    /// ```
+    pub fn get_or_insert_with<F>(&self, key: K, load: F) -> V
+    where
+        F: FnOnce() -> V,
+        V: Clone,
+    {
+        match self.try_get_or_insert_with(key, load) {
+            Ok(value) => value,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Comme `get_or_insert_with`, mais retourne une erreur au lieu de
+    /// paniquer si `load` recharge sa propre clé
+    ///
+    /// Seule la réentrance sur le thread qui charge est détectée. Deux
+    /// threads dont les chargements s'imbriquent en sens inverse (l'un
+    /// charge `a` puis `b` depuis `load`, l'autre `b` puis `a`) s'attendent
+    /// mutuellement sans fin: les chargements imbriqués doivent prendre
+    /// leurs clés toujours dans le même ordre.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::{ReentrantLoadError, SyncLruCache};
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// let inner = cache.clone();
+    ///
+    /// let value = cache.try_get_or_insert_with("a", || {
+    ///     // Lecture non mutante: autorisée pendant le chargement
+    ///     assert_eq!(inner.peek(&"b"), None);
+    ///     let reentrant = inner.try_get_or_insert_with("a", || "jamais");
+    ///     assert_eq!(reentrant, Err(ReentrantLoadError));
+    ///     "chargé"
+    /// });
+    ///
+    /// assert_eq!(value, Ok("chargé"));
+    /// ```
+    pub fn try_get_or_insert_with<F>(&self, key: K, load: F) -> Result<V, ReentrantLoadError>
+    where
+        F: FnOnce() -> V,
+        V: Clone,
+    {
+        self.load_with(key, None, || Ok::<V, Infallible>(load()))
+            .map(|loaded| match loaded {
+                Ok(value) => value,
+                Err(never) => match never {},
+            })
+    }
+
+    /// Comme `get_or_insert_with`, pour un chargement qui peut échouer
+    ///
+    /// Rien n'est inséré si `load` échoue; l'appel suivant réessaie.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// let parse = |text: &str| {
+    ///     cache.get_or_try_insert_with(text.to_string(), || text.parse::<i32>())
+    /// };
+    ///
+    /// assert_eq!(parse("42"), Ok(42));
+    /// assert!(parse("x").is_err());
+    /// assert!(!cache.contains_key(&"x".to_string()));
+    /// ```
+    pub fn get_or_try_insert_with<E, F>(&self, key: K, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+        V: Clone,
+    {
+        match self.load_with(key, None, load) {
+            Ok(loaded) => loaded,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Comme `get_or_try_insert_with`, la valeur chargée expirant après `ttl`
+    pub fn get_or_try_insert_with_ttl<E, F>(&self, key: K, ttl: Duration, load: F) -> Result<V, E>
+    where
+        F: FnOnce() -> Result<V, E>,
+        V: Clone,
+    {
+        match self.load_with(key, Some(ttl), load) {
+            Ok(loaded) => loaded,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Chargement dédoublonné par clé, commun aux méthodes `*_insert_with`
+    fn load_with<E, F>(
+        &self,
+        key: K,
+        ttl: Option<Duration>,
+        load: F,
+    ) -> Result<Result<V, E>, ReentrantLoadError>
+    where
+        F: FnOnce() -> Result<V, E>,
+        V: Clone,
+    {
+        if let Some(value) = self.get(&key) {
+            return Ok(Ok(value));
+        }
+
+        let key_lock = Arc::clone(self.loaders().entry(key.clone()).or_default());
+        if key_lock.is_held_by_current_thread() {
+            return Err(ReentrantLoadError);
+        }
+
+        let _loading = LoaderGuard::acquire(self, &key, &key_lock);
+
+        // Un autre thread a pu charger la clé pendant l'attente. Le miss est
+        // déjà compté par la première lecture: celle-ci ne touche pas aux
+        // statistiques
+        let value = match self.peek(&key) {
+            Some(value) => Ok(value),
+            None => load().inspect(|value| match ttl {
+                Some(ttl) => {
+                    self.put_with_ttl(key.clone(), value.clone(), ttl);
+                }
+                None => {
+                    self.put(key.clone(), value.clone());
+                }
+            }),
+        };
+
+        Ok(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.read().capacity()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.read().stats()
+    }
+
+    pub fn purge_expired(&self) -> usize {
+        self.writable().map_or(0, |mut cache| cache.purge_expired())
+    }
+
+    pub fn shrink_to(&self, len: usize) -> usize {
+        self.writable().map_or(0, |mut cache| cache.shrink_to(len))
+    }
+
+    /// État complet du cache sous le verrou partagé, voir
+    /// `LruCache::snapshot`; les valeurs ne sont pas copiées
+    pub fn checkpoint(&self) -> CacheSnapshot<K, Arc<V>> {
+        self.read().snapshot()
+    }
+
+    /// Rétablit un état obtenu par `checkpoint` (sans effet si le cache est
+    /// gelé)
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(10);
+    /// cache.put("prix", 10);
+    /// let before = cache.checkpoint();
+    ///
+    /// cache.put("prix", 1000);
+    /// cache.restore(before);
+    /// assert_eq!(cache.get(&"prix"), Some(10));
+    /// ```
+    pub fn restore(&self, snapshot: CacheSnapshot<K, Arc<V>>) {
+        if let Some(mut cache) = self.writable() {
+            cache.restore(snapshot);
+        }
+    }
+
+    /// Copie instantanée des entrées, de la moins à la plus récente
+    ///
+    /// Le verrou partagé n'est tenu que le temps de cloner les `Arc`: les
+    /// valeurs sont copiées ensuite, sans bloquer les autres threads.
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::SyncLruCache;
+    ///
+    /// let cache = SyncLruCache::new(3);
+    /// cache.put("a", 1);
+    /// cache.put("b", 2);
+    ///
+    /// assert_eq!(cache.snapshot(), vec![("a", 1), ("b", 2)]);
+    /// ```
+    pub fn snapshot(&self) -> Vec<(K, V)>
+    where
+        V: Clone,
+    {
+        self.snapshot_arc()
+            .into_iter()
+            .map(|(key, value)| (key, V::clone(&value)))
+            .collect()
+    }
+
+    /// Copie des clés, de la moins à la plus récente
+    pub fn keys(&self) -> Vec<K> {
+        self.read().iter().map(|(key, _)| key.clone()).collect()
+    }
+
+    pub fn snapshot_arc(&self) -> Vec<(K, Arc<V>)> {
+        self.read()
+            .iter()
+            .map(|(key, value)| (key.clone(), Arc::clone(value)))
+            .collect()
+    }
+
+    fn loaders(&self) -> MutexGuard<'_, HashMap<K, Arc<KeyLock>>> {
+        self.loaders.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Retire le verrou de la clé si plus aucun chargement ne l'attend
+    fn release_loader(&self, key: &K, key_lock: &Arc<KeyLock>) {
+        let mut loaders = self.loaders();
+        // Une référence dans la table, une chez l'appelant
+        if Arc::strong_count(key_lock) == 2 {
+            loaders.remove(key);
+        }
+    }
+
+    // Un thread qui panique ne laisse jamais le cache dans un état
+    // incohérent: on ignore l'empoisonnement du verrou.
+    fn read(&self) -> RwLockReadGuard<'_, LruCache<K, Arc<V>>> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, LruCache<K, Arc<V>>> {
+        self.inner.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Verrou exclusif pour une écriture, `None` si le cache est gelé
+    ///
+    /// L'état est lu une fois le verrou pris, et `freeze` prend ce même
+    /// verrou: un gel ne peut pas survenir entre la vérification et
+    /// l'écriture.
+    fn writable(&self) -> Option<RwLockWriteGuard<'_, LruCache<K, Arc<V>>>> {
+        let cache = self.write();
+        (!self.is_frozen()).then_some(cache)
+    }
+}
+
+impl<K, V> SyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone + AsRef<str>,
+{
+    /// Copie des entrées dont la clé commence par `prefix` (voir
+    /// `LruCache::iter_prefix`)
+    pub fn entries_with_prefix(&self, prefix: &str) -> Vec<(K, Arc<V>)> {
+        self.read()
+            .iter_prefix(prefix)
+            .map(|(key, value)| (key.clone(), Arc::clone(value)))
+            .collect()
+    }
+
+    /// Retire sous un seul verrou les entrées dont la clé commence par
+    /// `prefix`
+    pub fn remove_prefix(&self, prefix: &str) -> Vec<(K, Arc<V>)> {
+        match self.writable() {
+            Some(mut cache) => cache.remove_prefix(prefix),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<K, T> SyncLruCache<K, VecDeque<T>>
+where
+    K: Hash + Eq + Clone,
+    T: Clone,
+{
+    /// Ajoute `item` à la liste de `key` sous un seul verrou, voir
+    /// `LruCache::append`
+    ///
+    /// La liste n'est copiée que si un `get_arc` en garde encore une
+    /// référence.
+    pub fn append(&self, key: K, item: T, max_items: usize) -> usize {
+        let Some(mut cache) = self.writable() else {
+            return 0;
+        };
+        if cache.capacity() == 0 || max_items == 0 {
+            return 0;
+        }
+        let mut item = Some(item);
+        let appended = cache.modify(&key, |items| {
+            let items = Arc::make_mut(items);
+            items.extend(item.take());
+            items.drain(..items.len().saturating_sub(max_items));
+            items.len()
+        });
+        match (appended, item) {
+            (Some(len), _) => len,
+            (None, item) => {
+                cache.put(key, Arc::new(item.into_iter().collect()));
+                1
+            }
+        }
+    }
+}
+
+/// Lot d'opérations en attente, voir `SyncLruCache::transaction`
+///
+/// Les lectures tiennent compte des écritures déjà faites dans la
+/// transaction.
+pub struct Transaction<'a, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: &'a LruCache<K, Arc<V>>,
+    ops: Vec<TxnOp<K, V>>,
+}
+
+enum TxnOp<K, V> {
+    Put(K, Arc<V>),
+    Remove(K),
+}
+
+impl<K, V> Transaction<'_, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn put(&mut self, key: K, value: V) {
+        self.ops.push(TxnOp::Put(key, Arc::new(value)));
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.ops.push(TxnOp::Remove(key.clone()));
+    }
+
+    /// Valeur vue par la transaction, sans modifier l'ordre LRU
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let pending = self.ops.iter().rev().find_map(|op| match op {
+            TxnOp::Put(k, value) if k == key => Some(Some(value)),
+            TxnOp::Remove(k) if k == key => Some(None),
+            _ => None,
+        });
+
+        match pending {
+            Some(value) => value.map(|value| &**value),
+            None => self.cache.peek(key).map(|value| &**value),
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.peek(key).is_some()
+    }
+}
+
+/// Verrou de chargement d'une clé, qui connaît le thread qui le détient
+#[derive(Default)]
+struct KeyLock {
+    lock: Mutex<()>,
+    owner: Mutex<Option<ThreadId>>,
+}
+
+impl KeyLock {
+    fn is_held_by_current_thread(&self) -> bool {
+        *self.owner.lock().unwrap_or_else(|e| e.into_inner()) == Some(thread::current().id())
+    }
+
+    fn set_owner(&self, owner: Option<ThreadId>) {
+        *self.owner.lock().unwrap_or_else(|e| e.into_inner()) = owner;
+    }
+}
+
+/// Chargement en cours: libère le verrou de la clé même si `load` panique
+struct LoaderGuard<'a, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: &'a SyncLruCache<K, V>,
+    key: &'a K,
+    key_lock: &'a Arc<KeyLock>,
+    guard: Option<MutexGuard<'a, ()>>,
+}
+
+impl<'a, K, V> LoaderGuard<'a, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn acquire(cache: &'a SyncLruCache<K, V>, key: &'a K, key_lock: &'a Arc<KeyLock>) -> Self {
+        let guard = key_lock.lock.lock().unwrap_or_else(|e| e.into_inner());
+        key_lock.set_owner(Some(thread::current().id()));
+
+        Self {
+            cache,
+            key,
+            key_lock,
+            guard: Some(guard),
+        }
+    }
+}
+
+impl<K, V> Drop for LoaderGuard<'_, K, V>
+where
+    K: Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        self.key_lock.set_owner(None);
+        self.guard.take();
+        self.cache.release_loader(self.key, self.key_lock);
+    }
+}
+
+impl<K, V> Maintain for SyncLruCache<K, V>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Send + Sync + 'static,
+{
+    fn run_maintenance(&self, watermarks: Option<Watermarks>) {
+        let Some(mut cache) = self.writable() else {
+            return;
+        };
+        cache.purge_expired();
+
+        if let Some(watermarks) = watermarks {
+            if cache.len() > watermarks.high {
+                cache.shrink_to(watermarks.low);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::thread;
+
+    #[test]
+    fn test_shared_across_threads() {
+        let cache = SyncLruCache::new(100);
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    for i in 0..10 {
+                        cache.put(t * 10 + i, i);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(cache.len(), 40);
+        assert_eq!(cache.get(&35), Some(5));
+    }
+
+    #[test]
+    fn test_slow_loader_does_not_block_other_keys() {
+        use std::sync::mpsc;
+
+        let cache = SyncLruCache::new(10);
+        let (started, wait_started) = mpsc::channel();
+        let (release, wait_release) = mpsc::channel::<()>();
+
+        let loader = {
+            let cache = cache.clone();
+            thread::spawn(move || {
+                cache.get_or_insert_with("lent", || {
+                    started.send(()).unwrap();
+                    wait_release.recv().unwrap();
+                    1
+                })
+            })
+        };
+
+        wait_started.recv().unwrap();
+        cache.put("autre", 2);
+        assert_eq!(cache.get(&"autre"), Some(2));
+        assert_eq!(
+            cache.get_or_insert_with("rapide", || 3),
+            3,
+            "un autre chargement ne doit pas attendre"
+        );
+
+        release.send(()).unwrap();
+        assert_eq!(loader.join().unwrap(), 1);
+        assert!(cache.loaders().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_panic_applies_nothing() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let cache = SyncLruCache::new(10);
+        cache.put("a", 1);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            cache.transaction(|txn| {
+                txn.remove(&"a");
+                txn.put("b", 2);
+                assert!(!txn.contains_key(&"a"));
+                panic!("abandon");
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert!(!cache.contains_key(&"b"));
+    }
+
+    #[test]
+    fn test_compare_and_put() {
+        let cache = SyncLruCache::new(2);
+        assert_eq!(cache.compare_and_put("a", None, 1), Ok(None));
+        let err = cache.compare_and_put("a", None, 2).unwrap_err();
+        assert_eq!((err.current, err.rejected), (Some(Arc::new(1)), 2));
+
+        cache.put_with_ttl("b", 1, Duration::ZERO);
+        assert!(cache.compare_and_put("b", Some(&1), 2).is_err());
+        assert!(cache.compare_and_put("b", None, 2).is_ok());
+        assert_eq!(cache.get(&"b"), Some(2));
+    }
+
+    #[test]
+    fn test_frozen_reads_do_not_promote() {
+        let cache = SyncLruCache::new(2);
+        cache.put(1, "a");
+        cache.put(2, "b");
+
+        cache.freeze(FrozenWrites::Ignore);
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.put(3, "c"), None);
+        assert_eq!(cache.try_put(3, "c"), Ok(None));
+        assert_eq!(cache.remove(&2), None);
+        assert_eq!(cache.get_or_insert_with(4, || "d"), "d");
+        assert_eq!(cache.keys(), vec![1, 2]);
+        assert_eq!(cache.stats(), CacheStats::default());
+
+        cache.thaw();
+        cache.put(3, "c"); // 1 n'a pas été promue: elle est évincée
+        assert_eq!(cache.keys(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_no_write_after_freeze() {
+        let cache = SyncLruCache::new(1000);
+        let writers: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    let mut i = 0;
+                    while cache.try_put(t * 250 + i % 250, i).is_ok() {
+                        i += 1;
+                    }
+                })
+            })
+            .collect();
+
+        thread::sleep(Duration::from_millis(5));
+        cache.freeze(FrozenWrites::Reject);
+        let frozen = cache.snapshot();
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        // Les écrivains ont tous fini par voir le gel, sans rien écrire après
+        assert_eq!(cache.snapshot(), frozen);
+    }
+
+    #[test]
+    fn test_load_counts_one_miss() {
+        let cache = SyncLruCache::new(10);
+        assert_eq!(cache.get_or_insert_with(1, || "un"), "un");
+        assert_eq!((cache.stats().hits, cache.stats().misses), (0, 1));
+
+        assert_eq!(cache.get_or_insert_with(1, || unreachable!()), "un");
+        assert_eq!((cache.stats().hits, cache.stats().misses), (1, 1));
+    }
+
+    #[test]
+    fn test_reentrant_load() {
+        let cache = SyncLruCache::new(10);
+        let inner = cache.clone();
+
+        let value = cache.try_get_or_insert_with(1, || {
+            assert_eq!(
+                inner.try_get_or_insert_with(1, || 0),
+                Err(ReentrantLoadError)
+            );
+            // Une autre clé se charge normalement depuis `load`
+            assert_eq!(inner.try_get_or_insert_with(2, || 20), Ok(20));
+            10
+        });
+        assert_eq!(value, Ok(10));
+        assert_eq!(cache.keys(), vec![2, 1]);
+        assert!(cache.loaders().is_empty());
+
+        // La clé est libérée: un nouveau chargement n'est plus réentrant
+        cache.remove(&1);
+        assert_eq!(cache.try_get_or_insert_with(1, || 11), Ok(11));
+
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            cache.get_or_insert_with(3, || inner.get_or_insert_with(3, || 0))
+        }));
+        assert!(panicked.is_err());
+        assert!(!cache.contains_key(&3));
+        assert!(cache.loaders().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_during_writes() {
+        let cache = SyncLruCache::new(16);
+        let writers: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    for i in 0..2000 {
+                        let key = (t * 7 + i) % 40;
+                        cache.put(key, (key, i));
+                        if i % 3 == 0 {
+                            cache.remove(&((key + 1) % 40));
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        // Chaque valeur porte sa clé: une entrée déchirée ne la porterait pas
+        loop {
+            let done = writers.iter().all(|writer| writer.is_finished());
+            let snapshot = cache.snapshot();
+            assert!(snapshot.len() <= 16);
+            let keys: HashSet<_> = snapshot.iter().map(|(key, _)| *key).collect();
+            assert_eq!(keys.len(), snapshot.len());
+            assert!(snapshot.iter().all(|(key, (owner, _))| key == owner));
+
+            let shared = cache.snapshot_arc();
+            assert!(shared.len() <= 16);
+            assert!(shared.iter().all(|(key, value)| *key == value.0));
+            if done {
+                break;
+            }
+        }
+        for writer in writers {
+            writer.join().unwrap();
+        }
+        assert_eq!(cache.snapshot().len(), cache.len());
+    }
+
+    #[test]
+    fn test_get_arc_shares_cached_value() {
+        let cache = SyncLruCache::new(1);
+        cache.put("a", vec![1, 2, 3]);
+
+        let first = cache.get_arc(&"a").unwrap();
+        let stored = cache.peek_arc(&"a").unwrap();
+        assert!(Arc::ptr_eq(&first, &stored));
+        assert!(Arc::ptr_eq(&first, &cache.get_arc(&"a").unwrap()));
+        drop(stored);
+
+        // L'éviction ne retire que la référence du cache
+        cache.put("b", vec![4]);
+        assert!(!cache.contains_key(&"a"));
+        assert_eq!(Arc::strong_count(&first), 1);
+        assert_eq!(*first, vec![1, 2, 3]);
+
+        cache.put("a", vec![1, 2, 3]);
+        assert!(!Arc::ptr_eq(&first, &cache.get_arc(&"a").unwrap()));
+    }
+
+    #[test]
+    fn test_peek_under_read_lock() {
+        let cache = SyncLruCache::new(2);
+        cache.put(1, "a");
+        cache.put(2, "b");
+
+        // Deux verrous partagés simultanés: pas d'interblocage
+        let guard = cache.read();
+        assert_eq!(cache.peek(&1), Some("a"));
+        assert!(cache.contains_key(&2));
+        drop(guard);
+
+        cache.put(3, "c"); // peek n'a pas promu 1
+        assert!(!cache.contains_key(&1));
+        assert_eq!(cache.stats().hits, 0);
+    }
+}
@@ -0,0 +1,250 @@
+//! `PersistentLruCache` partagé entre threads
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::codec::Persistable;
+use crate::error::CacheError;
+use crate::maintenance::{Maintain, Watermarks};
+use crate::persistent::{PersistOptions, PersistentLruCache};
+use crate::stats::CacheStats;
+
+/// Cache persistant `Send + Sync`, partageable par `clone`
+///
+/// Chaque opération, sauvegarde automatique comprise, s'exécute sous un
+/// verrou interne: deux threads ne peuvent pas écrire le fichier en même
+/// temps ni sauvegarder un état à moitié modifié. Le fichier est en plus
+/// verrouillé contre les autres processus (`LockPolicy::Fail` si aucune
+/// politique n'est choisie).
+///
+/// Contrairement à `BackgroundPersistentLruCache`, les sauvegardes restent
+/// synchrones et suivent la politique `autosave`.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use std::thread;
+/// use lru_cache::{PersistOptions, SyncPersistentLruCache};
+///
+/// let cache: SyncPersistentLruCache<String, u64> =
+///     SyncPersistentLruCache::open(1000, "sessions.cache", PersistOptions::new()).unwrap();
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let cache = cache.clone();
+///         thread::spawn(move || cache.put(format!("worker-{}", i), i))
+///     })
+///     .collect();
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+/// ```
+pub struct SyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    inner: Arc<Mutex<PersistentLruCache<K, V>>>,
+}
+
+impl<K, V> Clone for SyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V> From<PersistentLruCache<K, V>> for SyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    /// Partage un cache déjà ouvert, avec le verrou de fichier qu'il a pris
+    fn from(cache: PersistentLruCache<K, V>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(cache)),
+        }
+    }
+}
+
+impl<K, V> SyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    /// Ouvre le fichier, verrouillé contre les autres processus
+    pub fn open(capacity: usize, path: &str, options: PersistOptions) -> Result<Self, CacheError> {
+        let cache = PersistentLruCache::with_options(capacity, path, options.locked())?;
+        Ok(cache.into())
+    }
+
+    pub fn put(&self, key: K, value: V) -> Option<V> {
+        self.lock().put(key, value)
+    }
+
+    pub fn put_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<V> {
+        self.lock().put_with_ttl(key, value, ttl)
+    }
+
+    /// Comme `put`, mais renvoie l'erreur de sauvegarde automatique
+    pub fn try_put(&self, key: K, value: V) -> Result<Option<V>, CacheError> {
+        self.lock().try_put(key, value)
+    }
+
+    pub fn remove(&self, key: &K) -> Option<V> {
+        self.lock().remove(key)
+    }
+
+    pub fn try_remove(&self, key: &K) -> Result<Option<V>, CacheError> {
+        self.lock().try_remove(key)
+    }
+
+    /// Copie de la valeur, marquée comme récemment utilisée
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.lock().get(key).cloned()
+    }
+
+    /// Copie de la valeur sans la promouvoir
+    pub fn peek<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.lock().peek(key).cloned()
+    }
+
+    pub fn save(&self) -> Result<(), CacheError> {
+        self.lock().save()
+    }
+
+    pub fn flush(&self) -> Result<(), CacheError> {
+        self.lock().flush()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().stats()
+    }
+
+    /// Exécute `f` sous le verrou, pour les opérations sans équivalent ici
+    /// ou plusieurs opérations qui doivent se suivre sans interruption
+    pub fn with<R>(&self, f: impl FnOnce(&mut PersistentLruCache<K, V>) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PersistentLruCache<K, V>> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Retire les entrées expirées, applique les seuils, puis sauvegarde les
+/// écritures en attente
+///
+/// Avec `Autosave::Manual`, la tâche de maintenance regroupe ainsi les
+/// sauvegardes: au plus une par intervalle, et aucune sans écriture. Une
+/// erreur de sauvegarde va au gestionnaire `PersistOptions::on_error`.
+impl<K, V> Maintain for SyncPersistentLruCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone + Send + 'static,
+    V: Persistable + Send + 'static,
+{
+    fn run_maintenance(&self, watermarks: Option<Watermarks>) {
+        let mut cache = self.lock();
+        cache.purge_expired();
+        if let Some(watermarks) = watermarks {
+            if cache.len() > watermarks.high {
+                cache.shrink_to(watermarks.low);
+            }
+        }
+        if cache.is_dirty() {
+            let result = cache.flush();
+            cache.report(result);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistent::{Autosave, LockPolicy};
+    use std::fs;
+    use std::thread;
+
+    #[test]
+    fn test_shared_between_threads() {
+        let path = "test_sync_persistent.log";
+        let options = PersistOptions::new().write_ahead_log(10);
+        let cache: SyncPersistentLruCache<String, u32> =
+            SyncPersistentLruCache::open(100, path, options.clone()).unwrap();
+
+        // Le fichier est verrouillé contre une seconde ouverture
+        let second: Result<PersistentLruCache<String, u32>, _> =
+            PersistentLruCache::with_options(100, path, options.clone().lock(LockPolicy::Fail));
+        assert!(matches!(second, Err(CacheError::Locked(_))));
+
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let cache = cache.clone();
+                thread::spawn(move || {
+                    for i in 0..10 {
+                        cache.put(format!("{}-{}", t, i), i);
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        drop(cache);
+
+        let mut reopened: PersistentLruCache<String, u32> =
+            PersistentLruCache::with_options(100, path, options).unwrap();
+        for t in 0..4 {
+            assert_eq!(reopened.get(&format!("{}-9", t)), Some(&9));
+        }
+
+        drop(reopened);
+        fs::remove_file(path).ok();
+        fs::remove_file("test_sync_persistent.log.lock").ok();
+    }
+
+    #[test]
+    fn test_maintenance_flushes_when_dirty() {
+        let path = "test_sync_persistent_maintenance.txt";
+        let options = PersistOptions::new().autosave(Autosave::Manual);
+        let cache: SyncPersistentLruCache<String, u32> =
+            SyncPersistentLruCache::open(10, path, options.clone()).unwrap();
+        for i in 0..5 {
+            cache.put(i.to_string(), i);
+        }
+        cache.put_with_ttl("expirée".into(), 9, Duration::ZERO);
+        assert!(cache.with(|cache| cache.is_dirty()));
+
+        let watermarks = Watermarks { high: 4, low: 3 };
+        cache.run_maintenance(Some(watermarks));
+        assert!(!cache.with(|cache| cache.is_dirty()));
+        assert_eq!(cache.stats().evictions, 2);
+        drop(cache);
+
+        let reopened: PersistentLruCache<String, u32> =
+            PersistentLruCache::with_options(10, path, options).unwrap();
+        let keys: Vec<&String> = reopened.iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["2", "3", "4"]);
+
+        drop(reopened);
+        fs::remove_file(path).ok();
+        fs::remove_file("test_sync_persistent_maintenance.txt.lock").ok();
+    }
+}
@@ -0,0 +1,364 @@
+//! Caches et opérations aléatoires pour les tests par propriétés, avec un
+//! modèle de référence (feature `testing`)
+
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+
+use crate::cache::LruCache;
+use crate::stats::CacheStats;
+
+/// Longueur maximale des collections tirées
+const MAX_LEN: u64 = 32;
+
+/// Capacité maximale des caches tirés, assez petite pour que les
+/// évictions soient fréquentes
+const MAX_CAPACITY: u64 = 16;
+
+/// Octets tirés par `Unstructured::from_seed`
+const SEED_LEN: usize = 4096;
+
+/// Source de valeurs construite sur des octets, comme
+/// `arbitrary::Unstructured`
+///
+/// Les octets viennent d'un fuzzer (`new`) ou d'une graine (`from_seed`).
+/// Une fois les octets épuisés, chaque tirage donne la plus petite valeur
+/// possible: collections vides, zéro, `false`.
+#[derive(Debug, Clone)]
+pub struct Unstructured {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl Unstructured {
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            data: data.into(),
+            pos: 0,
+        }
+    }
+
+    /// Octets pseudo-aléatoires reproductibles: la même graine rejoue le
+    /// même cas
+    pub fn from_seed(seed: u64) -> Self {
+        let mut state = seed;
+        let mut data = Vec::with_capacity(SEED_LEN);
+        while data.len() < SEED_LEN {
+            // SplitMix64
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            data.extend_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+        }
+        Self::new(data)
+    }
+
+    /// Octets restants
+    pub fn len(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Entier de `range`, lu sur autant d'octets que l'intervalle en demande
+    pub fn int_in_range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = (*range.start(), *range.end());
+        if start >= end {
+            return start;
+        }
+        let span = end - start;
+        let mut value = 0u64;
+        let mut remaining = span;
+        while remaining > 0 && !self.is_empty() {
+            value = (value << 8) | self.data[self.pos] as u64;
+            self.pos += 1;
+            remaining >>= 8;
+        }
+        match span.checked_add(1) {
+            Some(count) => start + value % count,
+            None => value,
+        }
+    }
+
+    pub fn arbitrary<T: Arbitrary>(&mut self) -> T {
+        T::arbitrary(self)
+    }
+
+    fn arbitrary_len(&mut self) -> usize {
+        self.int_in_range(0..=MAX_LEN) as usize
+    }
+}
+
+/// Valeur tirée d'un `Unstructured`, comme le trait du crate `arbitrary`
+pub trait Arbitrary: Sized {
+    fn arbitrary(u: &mut Unstructured) -> Self;
+}
+
+macro_rules! arbitrary_uint {
+    ($($t:ty),*) => {
+        $(impl Arbitrary for $t {
+            fn arbitrary(u: &mut Unstructured) -> Self {
+                u.int_in_range(0..=<$t>::MAX as u64) as $t
+            }
+        })*
+    };
+}
+
+arbitrary_uint!(u8, u16, u32, u64, usize);
+
+impl Arbitrary for bool {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        u.int_in_range(0..=1) == 1
+    }
+}
+
+/// Courtes chaînes sur l'alphabet `a`–`h`, pour que les clés se répètent
+impl Arbitrary for String {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        let len = u.int_in_range(0..=4);
+        (0..len)
+            .map(|_| (b'a' + u.int_in_range(0..=7) as u8) as char)
+            .collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Vec<T> {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        let len = u.arbitrary_len();
+        (0..len).map(|_| u.arbitrary()).collect()
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Option<T> {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        u.arbitrary::<bool>().then(|| u.arbitrary())
+    }
+}
+
+impl<A: Arbitrary, B: Arbitrary> Arbitrary for (A, B) {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        (u.arbitrary(), u.arbitrary())
+    }
+}
+
+/// Capacité de 0 à 16, puis un historique d'opérations tirées
+impl<K, V> Arbitrary for LruCache<K, V>
+where
+    K: Arbitrary + Hash + Eq + Clone,
+    V: Arbitrary + Clone,
+{
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        let mut cache = LruCache::new(u.int_in_range(0..=MAX_CAPACITY) as usize);
+        for operation in u.arbitrary::<Vec<Operation<K, V>>>() {
+            operation.apply(&mut cache);
+        }
+        cache
+    }
+}
+
+/// Opération sur un cache, sans TTL pour rester indépendante de l'horloge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation<K, V> {
+    Put(K, V),
+    Get(K),
+    Peek(K),
+    Remove(K),
+    ContainsKey(K),
+    ShrinkTo(usize),
+    Clear,
+}
+
+/// Résultat d'une `Operation`, à comparer entre le cache et le modèle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<V> {
+    /// `Put` (ancienne valeur), `Get`, `Peek`, `Remove`
+    Value(Option<V>),
+    /// `ContainsKey`
+    Found(bool),
+    /// `ShrinkTo`: entrées évincées
+    Evicted(usize),
+    /// `Clear`
+    Done,
+}
+
+impl<K, V> Operation<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// Applique l'opération à `cache`
+    ///
+    /// # Exemples
+    ///
+    /// ```
+    /// use lru_cache::{LruCache, LruModel, Operation, Unstructured};
+    ///
+    /// for seed in 0..100 {
+    ///     let mut u = Unstructured::from_seed(seed);
+    ///     let capacity = u.int_in_range(0..=8) as usize;
+    ///     let mut cache = LruCache::new(capacity);
+    ///     let mut model = LruModel::new(capacity);
+    ///
+    ///     for operation in u.arbitrary::<Vec<Operation<u8, u8>>>() {
+    ///         let expected = model.apply(operation.clone());
+    ///         assert_eq!(operation.apply(&mut cache), expected, "graine {}", seed);
+    ///     }
+    ///     assert_eq!(Vec::from(cache), model.entries(), "graine {}", seed);
+    /// }
+    /// ```
+    pub fn apply(self, cache: &mut LruCache<K, V>) -> Outcome<V> {
+        match self {
+            Operation::Put(key, value) => Outcome::Value(cache.put(key, value)),
+            Operation::Get(key) => Outcome::Value(cache.get(&key).cloned()),
+            Operation::Peek(key) => Outcome::Value(cache.peek(&key).cloned()),
+            Operation::Remove(key) => Outcome::Value(cache.remove(&key)),
+            Operation::ContainsKey(key) => Outcome::Found(cache.contains_key(&key)),
+            Operation::ShrinkTo(len) => Outcome::Evicted(cache.shrink_to(len)),
+            Operation::Clear => {
+                cache.clear();
+                Outcome::Done
+            }
+        }
+    }
+}
+
+/// Surtout des `Put` et des `Get`, pour que le cache se remplisse
+impl<K: Arbitrary, V: Arbitrary> Arbitrary for Operation<K, V> {
+    fn arbitrary(u: &mut Unstructured) -> Self {
+        match u.int_in_range(0..=10) {
+            0..=3 => Operation::Put(u.arbitrary(), u.arbitrary()),
+            4 | 5 => Operation::Get(u.arbitrary()),
+            6 => Operation::Peek(u.arbitrary()),
+            7 => Operation::Remove(u.arbitrary()),
+            8 => Operation::ContainsKey(u.arbitrary()),
+            9 => Operation::ShrinkTo(u.int_in_range(0..=MAX_CAPACITY) as usize),
+            _ => Operation::Clear,
+        }
+    }
+}
+
+/// Modèle de référence d'un `LruCache`: une liste parcourue à chaque
+/// opération, lente mais évidente
+///
+/// Tient les mêmes statistiques que le cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LruModel<K, V> {
+    capacity: usize,
+    /// De la moins à la plus récemment utilisée
+    entries: Vec<(K, V)>,
+    stats: CacheStats,
+}
+
+impl<K, V> LruModel<K, V>
+where
+    K: Eq + Clone,
+    V: Clone,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::new(),
+            stats: CacheStats::default(),
+        }
+    }
+
+    pub fn apply(&mut self, operation: Operation<K, V>) -> Outcome<V> {
+        match operation {
+            Operation::Put(key, value) => {
+                if self.capacity == 0 {
+                    return Outcome::Value(None);
+                }
+                let previous = self.take(&key).map(|(_, old)| old);
+                if previous.is_none() && self.entries.len() == self.capacity {
+                    self.entries.remove(0);
+                    self.stats.evictions += 1;
+                }
+                self.entries.push((key, value));
+                Outcome::Value(previous)
+            }
+            Operation::Get(key) => match self.take(&key) {
+                Some(entry) => {
+                    self.stats.hits += 1;
+                    let value = entry.1.clone();
+                    self.entries.push(entry);
+                    Outcome::Value(Some(value))
+                }
+                None => {
+                    self.stats.misses += 1;
+                    Outcome::Value(None)
+                }
+            },
+            Operation::Peek(key) => Outcome::Value(
+                self.entries
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, value)| value.clone()),
+            ),
+            Operation::Remove(key) => Outcome::Value(self.take(&key).map(|(_, value)| value)),
+            Operation::ContainsKey(key) => {
+                Outcome::Found(self.entries.iter().any(|(k, _)| *k == key))
+            }
+            Operation::ShrinkTo(len) => {
+                let evicted = self.entries.len().saturating_sub(len);
+                self.entries.drain(..evicted);
+                self.stats.evictions += evicted as u64;
+                Outcome::Evicted(evicted)
+            }
+            Operation::Clear => {
+                self.entries.clear();
+                Outcome::Done
+            }
+        }
+    }
+
+    /// Entrées de la moins à la plus récemment utilisée, comme
+    /// `Vec::from(cache)`
+    pub fn entries(&self) -> Vec<(K, V)> {
+        self.entries.clone()
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    fn take(&mut self, key: &K) -> Option<(K, V)> {
+        let pos = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_matches_model() {
+        for seed in 0..500 {
+            let mut u = Unstructured::from_seed(seed);
+            let mut cache: LruCache<String, u8> = u.arbitrary();
+            let mut model = LruModel::new(cache.capacity());
+            for (key, value) in cache.iter() {
+                model.apply(Operation::Put(key.clone(), *value));
+            }
+            cache.reset_stats();
+
+            for operation in u.arbitrary::<Vec<Operation<String, u8>>>() {
+                let expected = model.apply(operation.clone());
+                assert_eq!(operation.apply(&mut cache), expected, "graine {}", seed);
+            }
+            assert_eq!(cache.stats(), model.stats(), "graine {}", seed);
+            assert_eq!(Vec::from(cache), model.entries(), "graine {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_exhausted_input() {
+        let mut u = Unstructured::new([0xFF]);
+        assert_eq!(u.int_in_range(0..=1000), 0xFF);
+        assert!(u.is_empty());
+        assert_eq!(u.arbitrary::<Vec<u8>>(), Vec::<u8>::new());
+        assert_eq!(u.arbitrary::<LruCache<u8, u8>>().capacity(), 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Cache à deux niveaux: LRU en mémoire devant un cache persistant
+
+use std::hash::Hash;
+
+use crate::cache::LruCache;
+use crate::codec::Persistable;
+use crate::loader::CacheLoader;
+use crate::persistent::PersistentLruCache;
+
+/// Petit `LruCache` en mémoire devant un `PersistentLruCache` plus grand
+///
+/// Une entrée n'est que dans un niveau à la fois. L'entrée évincée du
+/// niveau mémoire descend dans le niveau disque; un défaut en mémoire
+/// cherche dans le niveau disque et y remonte l'entrée trouvée. À la
+/// destruction, les entrées en mémoire descendent toutes dans le niveau
+/// disque, qui les sauvegarde selon ses propres options.
+///
+/// Chaque descente est une écriture du niveau disque: un mode journal
+/// (`PersistOptions::write_ahead_log`) ou une sauvegarde groupée
+/// (`Autosave::EveryN`) évite de réécrire tout le fichier à chaque fois.
+///
+/// # Exemples
+///
+/// ```no_run
+/// use lru_cache::{PersistOptions, PersistentLruCache, TieredCache};
+///
+/// let options = PersistOptions::new().write_ahead_log(1000);
+/// let disk: PersistentLruCache =
+///     PersistentLruCache::with_options(100_000, "cache.log", options).unwrap();
+/// let mut cache = TieredCache::new(1000, disk);
+///
+/// cache.put("a".to_string(), "1".to_string());
+/// assert_eq!(cache.get(&"a".to_string()), Some(&"1".to_string()));
+/// ```
+pub struct TieredCache<K = String, V = String>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    front: LruCache<K, V>,
+    back: PersistentLruCache<K, V>,
+}
+
+impl<K, V> TieredCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    /// `front_capacity` entrées en mémoire (au moins une) devant `back`
+    pub fn new(front_capacity: usize, back: PersistentLruCache<K, V>) -> Self {
+        Self {
+            front: LruCache::new(front_capacity.max(1)),
+            back,
+        }
+    }
+
+    /// Insère en mémoire; l'entrée la moins récente descend si besoin
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let demoted = self.back.contains_key(&key).then(|| self.back.remove(&key));
+        let previous = self.insert_front(key, value);
+        previous.or(demoted.flatten())
+    }
+
+    /// Valeur en mémoire, ou remontée du niveau disque
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        if !self.front.contains_key(key) {
+            if let Some(value) = self.back.remove(key) {
+                self.insert_front(key.clone(), value);
+            }
+        }
+        self.front.get(key)
+    }
+
+    /// Comme `get`, en appelant `loader` si la clé n'est dans aucun niveau
+    pub fn get_or_load<L>(&mut self, key: &K, loader: &L) -> Result<&V, L::Error>
+    where
+        L: CacheLoader<K, V>,
+    {
+        if !self.contains_key(key) {
+            let value = loader.load(key)?;
+            self.insert_front(key.clone(), value);
+        }
+        Ok(self.get(key).expect("entrée chargée"))
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.front.remove(key).or_else(|| self.back.remove(key))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.front.contains_key(key) || self.back.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Niveau mémoire (statistiques, inspection)
+    pub fn front(&self) -> &LruCache<K, V> {
+        &self.front
+    }
+
+    /// Niveau disque (sauvegarde, statistiques)
+    pub fn back(&mut self) -> &mut PersistentLruCache<K, V> {
+        &mut self.back
+    }
+
+    fn insert_front(&mut self, key: K, value: V) -> Option<V> {
+        if self.front.len() >= self.front.capacity() && !self.front.contains_key(&key) {
+            self.demote_lru();
+        }
+        self.front.put(key, value)
+    }
+
+    fn demote_lru(&mut self) {
+        let lru = self.front.iter().next().map(|(key, _)| key.clone());
+        if let Some(key) = lru {
+            if let Some(value) = self.front.remove(&key) {
+                self.back.put(key, value);
+            }
+        }
+    }
+}
+
+impl<K, V> Drop for TieredCache<K, V>
+where
+    K: Persistable + Hash + Eq + Clone,
+    V: Persistable,
+{
+    fn drop(&mut self) {
+        while !self.front.is_empty() {
+            self.demote_lru();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistent::PersistOptions;
+    use std::fs;
+
+    #[test]
+    fn test_demote_and_promote() {
+        let path = "test_tiered.log";
+        let options = PersistOptions::new().write_ahead_log(100);
+        let back: PersistentLruCache =
+            PersistentLruCache::with_options(10, path, options.clone()).unwrap();
+        let mut cache = TieredCache::new(2, back);
+
+        for key in ["a", "b", "c"] {
+            cache.put(key.to_string(), key.to_uppercase());
+        }
+        assert!(!cache.front().contains_key("a"));
+        assert!(cache.back().contains_key("a"));
+
+        // "a" remonte, "b" descend
+        assert_eq!(cache.get(&"a".to_string()), Some(&"A".to_string()));
+        assert!(cache.back().contains_key("b"));
+        assert_eq!(cache.len(), 3);
+
+        drop(cache);
+        let mut reopened: PersistentLruCache =
+            PersistentLruCache::with_options(10, path, options).unwrap();
+        assert_eq!(reopened.len(), 3);
+        assert_eq!(reopened.get("c"), Some(&"C".to_string()));
+
+        drop(reopened);
+        fs::remove_file(path).ok();
+    }
+}
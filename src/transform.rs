@@ -0,0 +1,182 @@
+use std::fmt::Debug;
+use std::io;
+
+/// Transformation réversible appliquée aux octets écrits sur disque
+///
+/// Les transformations s'enchaînent dans l'ordre de `PersistOptions::transform`
+/// à la sauvegarde, et en sens inverse au chargement. Un instantané est
+/// transformé en un bloc; en mode journal, chaque enregistrement l'est
+/// séparément.
+///
+/// ```
+/// use lru_cache::Transform;
+///
+/// /// Inverse les bits (exemple jouet)
+/// #[derive(Debug)]
+/// struct Invert;
+///
+/// impl Transform for Invert {
+///     fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+///         bytes.iter().map(|b| !b).collect()
+///     }
+///
+///     fn revert(&self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+///         Ok(self.apply(bytes))
+///     }
+/// }
+///
+/// assert_eq!(Invert.revert(&Invert.apply(b"abc")).unwrap(), b"abc");
+/// ```
+pub trait Transform: Debug + Send + Sync {
+    fn apply(&self, bytes: &[u8]) -> Vec<u8>;
+
+    /// Erreur `InvalidData` si les octets n'ont pas été produits par `apply`
+    fn revert(&self, bytes: &[u8]) -> io::Result<Vec<u8>>;
+}
+
+/// Compression LZ77 sans dépendance (feature `compression`)
+///
+/// Efficace sur les fichiers répétitifs (JSON, texte). Pour gzip ou zstd,
+/// implémenter `Transform` au-dessus de la bibliothèque voulue.
+///
+/// ```
+/// use lru_cache::{LzCompression, Transform};
+///
+/// let json = r#"{"key": "a", "value": "1"}, "#.repeat(100);
+/// let packed = LzCompression.apply(json.as_bytes());
+/// assert!(packed.len() * 10 < json.len());
+/// assert_eq!(LzCompression.revert(&packed).unwrap(), json.as_bytes());
+/// ```
+#[cfg(feature = "compression")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LzCompression;
+
+#[cfg(feature = "compression")]
+mod lz {
+    use super::*;
+
+    const MIN_MATCH: usize = 4;
+    const MAX_OFFSET: usize = u16::MAX as usize;
+    const HASH_BITS: u32 = 14;
+
+    /// Suite de blocs: nombre de littéraux, littéraux, puis (sauf en fin de
+    /// flux) longueur de la copie moins 4 et distance, en varints
+    impl Transform for LzCompression {
+        fn apply(&self, input: &[u8]) -> Vec<u8> {
+            let mut out = Vec::with_capacity(input.len() / 2 + 16);
+            let mut table = vec![usize::MAX; 1 << HASH_BITS];
+            let mut literal_start = 0;
+            let mut pos = 0;
+
+            while pos + MIN_MATCH <= input.len() {
+                let slot = hash(&input[pos..pos + MIN_MATCH]);
+                let candidate = table[slot];
+                table[slot] = pos;
+
+                let found = candidate != usize::MAX
+                    && pos - candidate <= MAX_OFFSET
+                    && input[candidate..candidate + MIN_MATCH] == input[pos..pos + MIN_MATCH];
+                if !found {
+                    pos += 1;
+                    continue;
+                }
+
+                let len = MIN_MATCH
+                    + input[pos + MIN_MATCH..]
+                        .iter()
+                        .zip(&input[candidate + MIN_MATCH..])
+                        .take_while(|(a, b)| a == b)
+                        .count();
+
+                write_varint(&mut out, pos - literal_start);
+                out.extend_from_slice(&input[literal_start..pos]);
+                write_varint(&mut out, len - MIN_MATCH);
+                write_varint(&mut out, pos - candidate);
+
+                pos += len;
+                literal_start = pos;
+            }
+
+            write_varint(&mut out, input.len() - literal_start);
+            out.extend_from_slice(&input[literal_start..]);
+            out
+        }
+
+        fn revert(&self, mut input: &[u8]) -> io::Result<Vec<u8>> {
+            let corrupted = || io::Error::new(io::ErrorKind::InvalidData, "données LZ corrompues");
+            let mut out = Vec::with_capacity(input.len() * 2);
+
+            loop {
+                let literals = read_varint(&mut input).ok_or_else(corrupted)?;
+                if literals > input.len() {
+                    return Err(corrupted());
+                }
+                let (head, rest) = input.split_at(literals);
+                out.extend_from_slice(head);
+                input = rest;
+
+                if input.is_empty() {
+                    return Ok(out);
+                }
+
+                let len = read_varint(&mut input).ok_or_else(corrupted)? + MIN_MATCH;
+                let offset = read_varint(&mut input).ok_or_else(corrupted)?;
+                if offset == 0 || offset > out.len() {
+                    return Err(corrupted());
+                }
+                // Les copies peuvent se chevaucher: octet par octet
+                let start = out.len() - offset;
+                for i in 0..len {
+                    out.push(out[start + i]);
+                }
+            }
+        }
+    }
+
+    fn hash(bytes: &[u8]) -> usize {
+        let word = u32::from_le_bytes(bytes.try_into().unwrap());
+        (word.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn write_varint(out: &mut Vec<u8>, mut n: usize) {
+        while n >= 0x80 {
+            out.push(n as u8 | 0x80);
+            n >>= 7;
+        }
+        out.push(n as u8);
+    }
+
+    fn read_varint(input: &mut &[u8]) -> Option<usize> {
+        let mut n = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let (&b, rest) = input.split_first()?;
+            *input = rest;
+            n |= ((b & 0x7f) as usize) << shift;
+            if b & 0x80 == 0 {
+                return Some(n);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(all(test, feature = "compression"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lz_round_trip() {
+        let inputs: [&[u8]; 4] = [
+            b"",
+            b"abc",
+            b"aaaaaaaaaaaaaaaaaaaaaaaa",
+            b"abcdabcdXabcdabcdabcdY",
+        ];
+        for input in inputs {
+            let packed = LzCompression.apply(input);
+            assert_eq!(LzCompression.revert(&packed).unwrap(), input);
+        }
+
+        assert!(LzCompression.revert(&[0, 0, 5]).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+//! Écriture différée et groupée vers un stockage (write-behind)
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::sync::SyncLruCache;
+use crate::writer::CacheWriter;
+
+/// Cache dont les écritures partent vers un `CacheWriter` par lots, sur un
+/// thread de fond
+///
+/// `put` et `remove` modifient le cache tout de suite et mettent l'écriture
+/// en attente; plusieurs écritures d'une même clé n'en font qu'une. Le
+/// thread vide la file toutes les `interval`, ou dès que `batch_size`
+/// écritures attendent, par lots de `batch_size`. Un lot refusé reste en
+/// attente pour la vidange suivante, sauf si la clé a été réécrite depuis.
+///
+/// À la disparition du dernier clone, le thread vide la file une dernière
+/// fois avant de s'arrêter; `flush` vide la file sans attendre et renvoie
+/// l'erreur du stockage.
+///
+/// # Exemples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+/// use lru_cache::{CacheWriter, SyncLruCache, WriteBehindCache};
+///
+/// #[derive(Default, Clone)]
+/// struct Table(Arc<Mutex<HashMap<u32, String>>>);
+///
+/// impl CacheWriter<u32, String> for Table {
+///     type Error = String;
+///
+///     fn write(&self, key: &u32, value: &String) -> Result<(), String> {
+///         self.0.lock().unwrap().insert(*key, value.clone());
+///         Ok(())
+///     }
+///
+///     fn delete(&self, key: &u32) -> Result<(), String> {
+///         self.0.lock().unwrap().remove(key);
+///         Ok(())
+///     }
+/// }
+///
+/// let table = Table::default();
+/// let cache = WriteBehindCache::with_cache(
+///     SyncLruCache::new(100),
+///     table.clone(),
+///     50,
+///     Duration::from_millis(200),
+/// );
+/// cache.put(1, "alice".to_string());
+/// assert_eq!(cache.get(&1), Some("alice".to_string()));
+///
+/// drop(cache); // vidange finale
+/// assert_eq!(table.0.lock().unwrap().get(&1), Some(&"alice".to_string()));
+/// ```
+pub struct WriteBehindCache<K, V, W>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    W: CacheWriter<K, V> + Send + Sync + 'static,
+{
+    inner: Arc<Inner<K, V, W>>,
+}
+
+struct Inner<K, V, W>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    W: CacheWriter<K, V> + Send + Sync + 'static,
+{
+    cache: SyncLruCache<K, V>,
+    queue: Arc<Queue<K, V, W>>,
+    wake: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Écritures en attente et vidange, partagées avec le thread de fond
+struct Queue<K, V, W> {
+    writer: W,
+    batch_size: usize,
+    /// Dernière écriture de chaque clé, `None` pour une suppression
+    pending: Mutex<HashMap<K, Option<V>>>,
+    /// Une seule vidange à la fois
+    flushing: Mutex<()>,
+}
+
+impl<K, V, W> Clone for WriteBehindCache<K, V, W>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    W: CacheWriter<K, V> + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<K, V, W> WriteBehindCache<K, V, W>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    W: CacheWriter<K, V> + Send + Sync + 'static,
+{
+    /// Lots de 100 écritures, au plus une seconde d'attente
+    pub fn new(capacity: usize, writer: W) -> Self {
+        Self::with_cache(
+            SyncLruCache::new(capacity),
+            writer,
+            100,
+            Duration::from_secs(1),
+        )
+    }
+
+    pub fn with_cache(
+        cache: SyncLruCache<K, V>,
+        writer: W,
+        batch_size: usize,
+        interval: Duration,
+    ) -> Self {
+        let queue = Arc::new(Queue {
+            writer,
+            batch_size: batch_size.max(1),
+            pending: Mutex::new(HashMap::new()),
+            flushing: Mutex::new(()),
+        });
+        let (wake, woken) = mpsc::channel::<()>();
+
+        let shared = Arc::clone(&queue);
+        let thread = thread::spawn(move || {
+            while let Ok(()) | Err(RecvTimeoutError::Timeout) = woken.recv_timeout(interval) {
+                let _ = shared.flush();
+            }
+            let _ = shared.flush();
+        });
+
+        Self {
+            inner: Arc::new(Inner {
+                cache,
+                queue,
+                wake: Some(wake),
+                thread: Some(thread),
+            }),
+        }
+    }
+
+    /// Met à jour le cache et met l'écriture en attente
+    pub fn put(&self, key: K, value: V) {
+        self.inner.cache.put(key.clone(), value.clone());
+        self.enqueue(key, Some(value));
+    }
+
+    pub fn remove(&self, key: &K) {
+        self.inner.cache.remove(key);
+        self.enqueue(key.clone(), None);
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.inner.cache.get(key)
+    }
+
+    /// Nombre d'écritures pas encore transmises au stockage
+    pub fn pending(&self) -> usize {
+        lock(&self.inner.queue.pending).len()
+    }
+
+    /// Transmet les écritures en attente sans attendre le thread de fond
+    pub fn flush(&self) -> Result<(), W::Error> {
+        self.inner.queue.flush()
+    }
+
+    /// Cache sous-jacent
+    pub fn cache(&self) -> &SyncLruCache<K, V> {
+        &self.inner.cache
+    }
+
+    fn enqueue(&self, key: K, value: Option<V>) {
+        let mut pending = lock(&self.inner.queue.pending);
+        pending.insert(key, value);
+        if pending.len() >= self.inner.queue.batch_size {
+            if let Some(wake) = &self.inner.wake {
+                let _ = wake.send(());
+            }
+        }
+    }
+}
+
+impl<K, V, W> Queue<K, V, W>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    W: CacheWriter<K, V>,
+{
+    fn flush(&self) -> Result<(), W::Error> {
+        let _flushing = lock(&self.flushing);
+        let batch: Vec<(K, Option<V>)> = lock(&self.pending).drain().collect();
+
+        let mut chunks = batch.chunks(self.batch_size);
+        while let Some(chunk) = chunks.next() {
+            if let Err(err) = self.writer.write_batch(chunk) {
+                // Remettre en attente ce qui n'a pas été écrit, sauf les
+                // clés réécrites entre-temps
+                let mut pending = lock(&self.pending);
+                for (key, value) in std::iter::once(chunk).chain(chunks).flatten() {
+                    pending.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K, V, W> Drop for Inner<K, V, W>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    V: Clone + Send + Sync + 'static,
+    W: CacheWriter<K, V> + Send + Sync + 'static,
+{
+    fn drop(&mut self) {
+        // Fermer le canal réveille le thread pour la vidange finale
+        self.wake.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Stockage qui compte ses lots et peut refuser d'écrire
+    #[derive(Default)]
+    struct Store {
+        rows: Mutex<HashMap<u32, u32>>,
+        batches: Mutex<usize>,
+        down: AtomicBool,
+    }
+
+    impl CacheWriter<u32, u32> for Arc<Store> {
+        type Error = ();
+
+        fn write(&self, key: &u32, value: &u32) -> Result<(), ()> {
+            self.rows.lock().unwrap().insert(*key, *value);
+            Ok(())
+        }
+
+        fn delete(&self, key: &u32) -> Result<(), ()> {
+            self.rows.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        fn write_batch(&self, batch: &[(u32, Option<u32>)]) -> Result<(), ()> {
+            if self.down.load(Ordering::SeqCst) {
+                return Err(());
+            }
+            *self.batches.lock().unwrap() += 1;
+            for (key, value) in batch {
+                match value {
+                    Some(value) => self.write(key, value)?,
+                    None => self.delete(key)?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_batched_and_drained() {
+        let store = Arc::new(Store::default());
+        let cache = WriteBehindCache::with_cache(
+            SyncLruCache::new(100),
+            Arc::clone(&store),
+            10,
+            Duration::from_secs(60),
+        );
+
+        cache.put(1, 1);
+        cache.put(1, 2); // fusionnée avec la précédente
+        cache.put(2, 2);
+        cache.remove(&2);
+        assert_eq!(cache.get(&1), Some(2));
+        assert_eq!(cache.pending(), 2);
+
+        store.down.store(true, Ordering::SeqCst);
+        assert!(cache.flush().is_err());
+        assert_eq!(cache.pending(), 2);
+
+        store.down.store(false, Ordering::SeqCst);
+        cache.put(3, 3);
+        drop(cache); // vidange finale, un seul lot
+        assert_eq!(*store.batches.lock().unwrap(), 1);
+        let rows = store.rows.lock().unwrap();
+        assert_eq!(
+            (rows.get(&1), rows.get(&2), rows.get(&3)),
+            (Some(&2), None, Some(&3))
+        );
+    }
+}
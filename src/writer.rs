@@ -0,0 +1,173 @@
+//! Écriture synchrone vers un stockage de référence (write-through)
+
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use crate::sync::SyncLruCache;
+
+/// Stockage de référence derrière un cache: base de données, service...
+pub trait CacheWriter<K, V> {
+    type Error;
+
+    fn write(&self, key: &K, value: &V) -> Result<(), Self::Error>;
+
+    fn delete(&self, key: &K) -> Result<(), Self::Error>;
+
+    /// Applique un lot d'écritures (`None`: suppression), une à une par
+    /// défaut; à redéfinir si le stockage sait écrire par lots
+    fn write_batch(&self, batch: &[(K, Option<V>)]) -> Result<(), Self::Error> {
+        for (key, value) in batch {
+            match value {
+                Some(value) => self.write(key, value)?,
+                None => self.delete(key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Cache dont chaque écriture passe d'abord par un `CacheWriter`
+///
+/// `put` écrit dans le stockage puis, seulement en cas de succès, dans le
+/// cache: le cache ne contient jamais une valeur que le stockage n'a pas.
+/// Les écritures sont sérialisées pour que le cache et le stockage voient
+/// le même ordre. Pour charger aussi les clés absentes, partager le même
+/// `SyncLruCache` avec un `LoadingCache` (`with_cache`).
+///
+/// # Exemples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::Mutex;
+/// use lru_cache::{CacheWriter, WriteThroughCache};
+///
+/// #[derive(Default)]
+/// struct Table(Mutex<HashMap<u32, String>>);
+///
+/// impl CacheWriter<u32, String> for Table {
+///     type Error = String;
+///
+///     fn write(&self, key: &u32, value: &String) -> Result<(), String> {
+///         self.0.lock().unwrap().insert(*key, value.clone());
+///         Ok(())
+///     }
+///
+///     fn delete(&self, key: &u32) -> Result<(), String> {
+///         self.0.lock().unwrap().remove(key);
+///         Ok(())
+///     }
+/// }
+///
+/// let cache = WriteThroughCache::new(100, Table::default());
+/// cache.put(1, "alice".to_string()).unwrap();
+/// assert_eq!(cache.get(&1), Some("alice".to_string()));
+/// ```
+pub struct WriteThroughCache<K, V, W>
+where
+    K: Hash + Eq + Clone,
+{
+    cache: SyncLruCache<K, V>,
+    writer: Arc<W>,
+    writes: Arc<Mutex<()>>,
+}
+
+impl<K, V, W> Clone for WriteThroughCache<K, V, W>
+where
+    K: Hash + Eq + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cache: self.cache.clone(),
+            writer: Arc::clone(&self.writer),
+            writes: Arc::clone(&self.writes),
+        }
+    }
+}
+
+impl<K, V, W> WriteThroughCache<K, V, W>
+where
+    K: Hash + Eq + Clone,
+    W: CacheWriter<K, V>,
+{
+    pub fn new(capacity: usize, writer: W) -> Self {
+        Self::with_cache(SyncLruCache::new(capacity), writer)
+    }
+
+    /// Écrit à travers `cache`, partagé avec ses autres clones
+    pub fn with_cache(cache: SyncLruCache<K, V>, writer: W) -> Self {
+        Self {
+            cache,
+            writer: Arc::new(writer),
+            writes: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Écrit dans le stockage, puis dans le cache; rien n'est mis en cache
+    /// si le stockage refuse l'écriture
+    pub fn put(&self, key: K, value: V) -> Result<(), W::Error> {
+        let _writing = self.writes.lock().unwrap_or_else(|e| e.into_inner());
+        self.writer.write(&key, &value)?;
+        self.cache.put(key, value);
+        Ok(())
+    }
+
+    /// Supprime du stockage, puis du cache
+    pub fn remove(&self, key: &K) -> Result<(), W::Error> {
+        let _writing = self.writes.lock().unwrap_or_else(|e| e.into_inner());
+        self.writer.delete(key)?;
+        self.cache.remove(key);
+        Ok(())
+    }
+
+    pub fn get(&self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        self.cache.get(key)
+    }
+
+    /// Cache sous-jacent
+    pub fn cache(&self) -> &SyncLruCache<K, V> {
+        &self.cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Stockage qui refuse les valeurs négatives
+    #[derive(Default)]
+    struct Store(Mutex<HashMap<&'static str, i32>>);
+
+    impl CacheWriter<&'static str, i32> for Store {
+        type Error = &'static str;
+
+        fn write(&self, key: &&'static str, value: &i32) -> Result<(), Self::Error> {
+            if *value < 0 {
+                return Err("valeur refusée");
+            }
+            self.0.lock().unwrap().insert(key, *value);
+            Ok(())
+        }
+
+        fn delete(&self, key: &&'static str) -> Result<(), Self::Error> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_store_first() {
+        let cache = WriteThroughCache::new(10, Store::default());
+        cache.put("a", 1).unwrap();
+        assert_eq!(cache.put("a", -1), Err("valeur refusée"));
+        assert_eq!(cache.get(&"a"), Some(1));
+        assert_eq!(cache.writer.0.lock().unwrap().get("a"), Some(&1));
+
+        cache.remove(&"a").unwrap();
+        assert_eq!(cache.get(&"a"), None);
+        assert!(cache.writer.0.lock().unwrap().is_empty());
+    }
+}